@@ -0,0 +1,9 @@
+use scraper::Html;
+
+use crate::api::selector::select_one;
+
+// the 18+ confirmation is served in place of the content, the client keeps the
+// confirmed cookie and whether a page was gated
+pub(crate) fn is_gated(document: &Html) -> bool {
+    select_one(&document.root_element(), "gate.age").is_some()
+}
@@ -0,0 +1,110 @@
+use futures::executor::block_on;
+use scraper::{ElementRef, Html};
+use serde::Serialize;
+use url::Url;
+
+use crate::api::{
+    client::BahamutClient,
+    selector::{select, select_one},
+};
+
+pub static ANI_DN: &str = "https://ani.gamer.com.tw/";
+
+#[derive(Clone, Default, Serialize)]
+pub struct AnimeEpisode {
+    pub name: String,
+    pub episode: String,
+    pub date: String,
+    pub url: String,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct AnimeDay {
+    pub day: String,
+    pub items: Vec<AnimeEpisode>,
+}
+
+// new episodes and weekly schedule on the front page of 動畫瘋
+#[derive(Clone, Default, Serialize)]
+pub struct AnimeHome {
+    pub episodes: Vec<AnimeEpisode>,
+    pub schedule: Vec<AnimeDay>,
+}
+
+fn text_of(document: &ElementRef, key: &str) -> Option<String> {
+    let text = select_one(document, key)?
+        .text()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    Some(text)
+}
+
+fn absolute_url(href: &str) -> Option<String> {
+    let base = Url::parse(ANI_DN).ok()?;
+    Some(base.join(href).ok()?.to_string())
+}
+
+impl AnimeHome {
+    pub fn get(client: &BahamutClient) -> AnimeHome {
+        let url = Url::parse(ANI_DN).unwrap();
+        match block_on(client.get_document(&url)) {
+            Ok(document) => AnimeHome::from(&document),
+            Err(_) => AnimeHome::default(),
+        }
+    }
+
+    fn try_episodes_from_html(document: &Html) -> Option<Vec<AnimeEpisode>> {
+        let episodes = select(&document.root_element(), "anime.new")
+            .into_iter()
+            .filter_map(|el| {
+                let href = select_one(&el, "anime.new.link")?.value().attr("href")?;
+                Some(AnimeEpisode {
+                    name: text_of(&el, "anime.new.name")?,
+                    episode: text_of(&el, "anime.new.episode").unwrap_or_default(),
+                    date: text_of(&el, "anime.new.date").unwrap_or_default(),
+                    url: absolute_url(href)?,
+                })
+            })
+            .collect();
+
+        Some(episodes)
+    }
+
+    fn try_schedule_from_html(document: &Html) -> Option<Vec<AnimeDay>> {
+        let schedule = select(&document.root_element(), "anime.day")
+            .into_iter()
+            .filter_map(|el| {
+                let items = select(&el, "anime.day.item")
+                    .into_iter()
+                    .filter_map(|item| {
+                        Some(AnimeEpisode {
+                            name: text_of(&item, "anime.day.name")?,
+                            episode: text_of(&item, "anime.day.episode").unwrap_or_default(),
+                            date: text_of(&item, "anime.day.time").unwrap_or_default(),
+                            url: absolute_url(item.value().attr("href")?)?,
+                        })
+                    })
+                    .collect();
+
+                Some(AnimeDay {
+                    day: text_of(&el, "anime.day.title")?,
+                    items,
+                })
+            })
+            .collect();
+
+        Some(schedule)
+    }
+}
+
+impl From<&Html> for AnimeHome {
+    fn from(document: &Html) -> Self {
+        AnimeHome {
+            episodes: AnimeHome::try_episodes_from_html(document).unwrap_or_default(),
+            schedule: AnimeHome::try_schedule_from_html(document).unwrap_or_default(),
+        }
+    }
+}
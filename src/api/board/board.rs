@@ -1,13 +1,16 @@
 use std::collections::HashMap;
 
-use scraper::{Html, Selector};
+use scraper::Html;
 use url::Url;
 
-use crate::api::{UrlWithId, WebSite, DN};
+use crate::api::{
+    selector::{select, select_one},
+    UrlWithId, WebSite, DN,
+};
 
 use super::{
     category::{BoardCategory, BoardCategoryId},
-    post::BoardPost,
+    post::{BoardPost, BoardPostRef},
 };
 
 pub struct Board {
@@ -43,19 +46,22 @@ impl UrlWithId<(&str, u16)> for Board {
 
 impl Board {
     pub fn post(&self) -> Vec<BoardPost> {
-        let selector = Selector::parse(".b-list__row").expect("parse selector error");
-        self.document
-            .select(&selector)
+        select(&self.document.root_element(), "board.row")
+            .into_iter()
             .filter_map(|root| BoardPost::try_from(root).ok())
             .collect::<Vec<BoardPost>>()
     }
 
+    // same as post() but borrowing the text from the page
+    pub fn post_refs(&self) -> Vec<BoardPostRef<'_>> {
+        select(&self.document.root_element(), "board.row")
+            .into_iter()
+            .filter_map(|root| BoardPostRef::try_from(root).ok())
+            .collect::<Vec<BoardPostRef>>()
+    }
+
     fn try_name_from_html(document: &Html) -> Option<String> {
-        let selector = Selector::parse("head title").expect("parse selector error");
-        let title = document
-            .select(&selector)
-            .next()
-            .expect("get name fail")
+        let title = select_one(&document.root_element(), "board.name")?
             .text()
             .collect::<String>();
 
@@ -76,15 +82,16 @@ impl Board {
     fn try_category_map_from_html(document: &Html) -> Option<HashMap<String, BoardCategory>> {
         let mut map: HashMap<String, BoardCategory> = HashMap::new();
 
-        let selector = Selector::parse(".b-tags__item a").expect("parse selector error");
-        document.select(&selector).for_each(|elm| {
-            let href = elm.value().attr("href").unwrap();
-            let url = Url::parse(href).unwrap();
-            let id = BoardCategoryId::try_from(url).unwrap();
-            let name = elm.text().collect::<String>();
+        select(&document.root_element(), "board.tag")
+            .into_iter()
+            .for_each(|elm| {
+                let href = elm.value().attr("href").unwrap();
+                let url = Url::parse(href).unwrap();
+                let id = BoardCategoryId::try_from(url).unwrap();
+                let name = elm.text().collect::<String>();
 
-            map.insert(id.sub_id.to_owned(), BoardCategory { id, name });
-        });
+                map.insert(id.sub_id.to_owned(), BoardCategory { id, name });
+            });
 
         Some(map)
     }
@@ -0,0 +1,94 @@
+use lazy_static::lazy_static;
+
+use std::collections::HashMap;
+
+use jieba_rs::Jieba;
+use serde::Serialize;
+
+lazy_static! {
+    static ref JIEBA: Jieba = Jieba::new();
+}
+
+static STOP_WORDS: &[&str] = &[
+    "的",
+    "了",
+    "是",
+    "在",
+    "有",
+    "和",
+    "與",
+    "及",
+    "或",
+    "就",
+    "都",
+    "也",
+    "嗎",
+    "呢",
+    "吧",
+    "啊",
+    "什麼",
+    "怎麼",
+    "這個",
+    "那個",
+    "一下",
+    "一個",
+    "請問",
+    "問題",
+    "有沒有",
+    "大家",
+    "可以",
+    "自己",
+];
+
+#[derive(Clone, Serialize)]
+pub struct BoardKeyword {
+    pub word: String,
+    pub count: usize,
+}
+
+// drop the category tag like 【情報】 in front of the title
+fn strip_tag(title: &str) -> &str {
+    let title = title.trim();
+    match title.strip_prefix('【').and_then(|t| t.split_once('】')) {
+        Some((_, rest)) => rest,
+        None => title,
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    let len = word.chars().count();
+    let is_cjk = word.chars().any(|c| c as u32 >= 0x2e80);
+    let is_alphanumeric = word.chars().all(char::is_alphanumeric);
+
+    is_alphanumeric
+        && ((is_cjk && len >= 2) || (!is_cjk && len >= 3))
+        && !word.chars().all(|c| c.is_ascii_digit())
+        && !STOP_WORDS.contains(&word)
+}
+
+// frequent terms in the titles, each title counts a term once
+pub fn trending_keywords(titles: &[String], limit: usize) -> Vec<BoardKeyword> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    titles.iter().for_each(|title| {
+        let mut words: Vec<String> = JIEBA
+            .cut(strip_tag(title), true)
+            .into_iter()
+            .map(|token| token.word.trim().to_lowercase())
+            .filter(|word| is_keyword(word))
+            .collect();
+        words.sort();
+        words.dedup();
+        words
+            .into_iter()
+            .for_each(|word| *counts.entry(word).or_default() += 1);
+    });
+
+    let mut keywords: Vec<BoardKeyword> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(word, count)| BoardKeyword { word, count })
+        .collect();
+    keywords.sort_by(|a, b| b.count.cmp(&a.count).then(a.word.cmp(&b.word)));
+    keywords.truncate(limit);
+    keywords
+}
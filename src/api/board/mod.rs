@@ -1,10 +1,14 @@
 #[allow(clippy::module_inception)]
 mod board;
 mod category;
+mod keyword;
+mod moderation;
 mod page;
 mod post;
 
 pub use board::Board;
 pub use category::{BoardCategory, BoardCategoryId};
+pub use keyword::{trending_keywords, BoardKeyword};
+pub use moderation::{ModerationEntry, ModerationLog};
 pub use page::BoardPage;
-pub use post::BoardPost;
+pub use post::{split_prefix, BoardPost, BoardPostRef, TOMBSTONE};
@@ -0,0 +1,69 @@
+use futures::executor::block_on;
+use scraper::ElementRef;
+use serde::Serialize;
+use url::Url;
+
+use crate::api::{client::BahamutClient, selector::select, UrlWithId, DN};
+
+// one deletion, lock or move of the 處理紀錄 page
+#[derive(Clone, Default, Serialize)]
+pub struct ModerationEntry {
+    pub date: String,
+    pub action: String,
+    pub title: String,
+    pub moderator: String,
+    pub reason: String,
+}
+
+pub struct ModerationLog;
+
+impl UrlWithId<&str> for ModerationLog {
+    fn url(bsn: &str) -> Url {
+        let url = format!("{}{}?bsn={}", DN, "B_log.php", bsn);
+        Url::parse(url.as_ref()).expect("invalid url")
+    }
+}
+
+fn cell_text(cell: &ElementRef) -> String {
+    let text = cell.text().collect::<String>();
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+impl ModerationLog {
+    // most recent first as on the page, the header row has no cells
+    pub fn get(client: &BahamutClient, bsn: &str) -> Vec<ModerationEntry> {
+        let url = ModerationLog::url(bsn);
+        let document = match block_on(client.get_document(&url)) {
+            Ok(document) => document,
+            Err(_) => return vec![],
+        };
+
+        select(&document.root_element(), "moderation.row")
+            .into_iter()
+            .filter_map(|row| ModerationEntry::try_from(row).ok())
+            .collect()
+    }
+}
+
+impl TryFrom<ElementRef<'_>> for ModerationEntry {
+    type Error = &'static str;
+
+    // cells of date, action, title, moderator and the optional reason
+    fn try_from(row: ElementRef) -> Result<Self, &'static str> {
+        let cells: Vec<String> = select(&row, "moderation.cell")
+            .iter()
+            .map(cell_text)
+            .collect();
+        if cells.len() < 4 {
+            return Err("not an entry");
+        }
+
+        Ok(ModerationEntry {
+            date: cells[0].to_owned(),
+            action: cells[1].to_owned(),
+            title: cells[2].to_owned(),
+            moderator: cells[3].to_owned(),
+            reason: cells.get(4).cloned().unwrap_or_default(),
+        })
+    }
+}
@@ -1,9 +1,9 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use scraper::{ElementRef, Html, Selector};
+use scraper::{ElementRef, Html};
 use url::Url;
 
-use crate::api::{CachedPage, UrlWithId};
+use crate::api::{client::BahamutClient, selector::select, CachedPage, UrlWithId};
 
 use super::board::Board;
 
@@ -11,34 +11,40 @@ pub struct BoardPage {
     pub id: String,
     pub page: u16,
     pub max: u16,
+    pub query: Option<String>,
 
     cache: HashMap<u16, Option<Board>>,
     first_page_cache: Option<Html>,
+    client: Arc<BahamutClient>,
 }
 
 impl BoardPage {
-    pub fn new(id: &str) -> BoardPage {
+    pub fn new(id: &str, client: Arc<BahamutClient>) -> BoardPage {
+        BoardPage::from_page(id, 1, client)
+    }
+
+    pub fn from_page(id: &str, page: u16, client: Arc<BahamutClient>) -> BoardPage {
         BoardPage {
             id: id.to_string(),
-            page: 1,
+            page,
             max: 0,
+            query: None,
             cache: HashMap::new(),
             first_page_cache: None,
+            client,
         }
     }
 
-    pub fn from_page(id: &str, page: u16) -> BoardPage {
+    // title search in the board
+    pub fn search(id: &str, query: &str, page: u16, client: Arc<BahamutClient>) -> BoardPage {
         BoardPage {
-            id: id.to_string(),
-            page,
-            max: 0,
-            cache: HashMap::new(),
-            first_page_cache: None,
+            query: Some(query.to_string()),
+            ..BoardPage::from_page(id, page, client)
         }
     }
 
     pub fn init(&mut self) {
-        if let Some(document) = self.get_page_html(1) {
+        if let Some(document) = self.fetch_page_html(1) {
             let root = document.root_element();
             let max = BoardPage::try_page_from_html(&root).map_or(0, |v| v);
             self.max = max;
@@ -46,10 +52,22 @@ impl BoardPage {
         }
     }
 
+    // page of the list showing the thread, only the first pages are looked through
+    pub fn find_thread(&mut self, id: &str, pages: u16) -> Option<u16> {
+        let last = if self.max == 0 {
+            pages
+        } else {
+            pages.min(self.max)
+        };
+        (1..=last).find(|page| {
+            self.get_and_cache(*page, false)
+                .is_some_and(|board| board.post_refs().iter().any(|post| post.id == id))
+        })
+    }
+
     fn try_page_from_html(document: &ElementRef) -> Option<u16> {
-        let selector = Selector::parse(".BH-pagebtnA a").unwrap();
-        let last = document.select(&selector).last().unwrap();
-        let page: u16 = last.text().collect::<String>().parse().unwrap();
+        let last = select(document, "page.button").into_iter().next_back()?;
+        let page: u16 = last.text().collect::<String>().parse().ok()?;
         Some(page)
     }
 }
@@ -72,7 +90,13 @@ impl CachedPage<Board> for BoardPage {
     }
 
     fn url(&self, page: &u16) -> Url {
-        Board::url((self.id.as_str(), *page))
+        let mut url = Board::url((self.id.as_str(), *page));
+        if let Some(query) = &self.query {
+            url.query_pairs_mut()
+                .append_pair("qt", "1")
+                .append_pair("q", query);
+        }
+        url
     }
 
     fn page(&self) -> u16 {
@@ -90,4 +114,8 @@ impl CachedPage<Board> for BoardPage {
     fn max(&self) -> u16 {
         self.max
     }
+
+    fn client(&self) -> &BahamutClient {
+        &self.client
+    }
 }
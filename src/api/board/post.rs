@@ -1,13 +1,23 @@
-use crate::api::{UrlWithId, DN};
+use std::borrow::Cow;
+
+use crate::api::{
+    selector::{matches, select_one},
+    UrlWithId, DN,
+};
 
 use super::category::{BoardCategory, BoardCategoryId};
 
-use scraper::{ElementRef, Selector};
+use scraper::ElementRef;
+use serde::Serialize;
 use url::Url;
 
+#[derive(Clone, Serialize)]
 pub struct BoardPost {
     pub id: String,
     pub title: String,
+    // tag in front of the title, like 情報 of 【情報】
+    pub prefix: String,
+    pub author: String,
     pub date: String,
     pub desc: String,
     pub category: BoardCategory,
@@ -15,20 +25,31 @@ pub struct BoardPost {
     pub reply: u16,
     pub floor: u16,
     pub url: String,
+    // tombstone of a deleted thread, without a working link
+    pub deleted: bool,
+    // stuck on top of the first page by the moderators
+    pub pinned: bool,
 }
 
+// title of a deleted thread in the list
+pub static TOMBSTONE: &str = "此文章已被刪除";
+
 impl Default for BoardPost {
     fn default() -> Self {
         let empty: &str = "";
         BoardPost {
             id: String::from("0"),
             title: empty.to_string(),
+            prefix: empty.to_string(),
+            author: empty.to_string(),
             date: empty.to_string(),
             desc: empty.to_string(),
             url: empty.to_string(),
             gp: 0,
             reply: 0,
             floor: 0,
+            deleted: false,
+            pinned: false,
             category: BoardCategory {
                 name: empty.to_string(),
                 id: BoardCategoryId {
@@ -96,84 +117,186 @@ impl BoardPost {
     }
 }
 
-impl TryFrom<ElementRef<'_>> for BoardPost {
+// borrowed from the html of the board, for throwaway listing without the owned BoardPost
+#[derive(Serialize)]
+pub struct BoardPostRef<'a> {
+    pub id: Cow<'a, str>,
+    pub title: Cow<'a, str>,
+    pub prefix: Cow<'a, str>,
+    pub author: Cow<'a, str>,
+    pub date: Cow<'a, str>,
+    pub desc: Cow<'a, str>,
+    pub category: BoardCategory,
+    pub gp: u16,
+    pub reply: u16,
+    pub floor: u16,
+    pub url: String,
+    pub deleted: bool,
+    pub pinned: bool,
+}
+
+impl BoardPostRef<'_> {
+    pub fn into_owned(self) -> BoardPost {
+        BoardPost {
+            id: self.id.into_owned(),
+            title: self.title.into_owned(),
+            prefix: self.prefix.into_owned(),
+            author: self.author.into_owned(),
+            date: self.date.into_owned(),
+            desc: self.desc.into_owned(),
+            category: self.category,
+            gp: self.gp,
+            reply: self.reply,
+            floor: self.floor,
+            url: self.url,
+            deleted: self.deleted,
+            pinned: self.pinned,
+        }
+    }
+}
+
+// split the tag like 【情報】 off the title, return the tag with brackets and the name in it
+pub fn split_prefix(title: &str) -> Option<(&str, &str)> {
+    let title = title.trim_start();
+    let close = match title.chars().next()? {
+        '【' => '】',
+        '[' => ']',
+        '［' => '］',
+        _ => return None,
+    };
+
+    let end = title.find(close)? + close.len_utf8();
+    let tag = &title[..end];
+    let name = tag[tag.chars().next()?.len_utf8()..tag.len() - close.len_utf8()].trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some((tag, name))
+    }
+}
+
+// only allocate when the text is split into several nodes
+fn trimmed_text<'a>(elm: &ElementRef<'a>) -> Cow<'a, str> {
+    let mut texts = elm.text().filter(|t| !t.trim().is_empty());
+    match (texts.next(), texts.next()) {
+        (None, _) => Cow::Borrowed(""),
+        (Some(text), None) => Cow::Borrowed(text.trim()),
+        _ => Cow::Owned(elm.text().collect::<String>().trim().to_string()),
+    }
+}
+
+fn query_value<'a>(href: &'a str, key: &str) -> Option<&'a str> {
+    let (_, query) = href.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v)
+}
+
+impl<'a> TryFrom<ElementRef<'a>> for BoardPostRef<'a> {
     type Error = &'static str;
 
-    fn try_from(elm: ElementRef) -> Result<Self, &'static str> {
-        let mut post = BoardPost::default();
+    fn try_from(elm: ElementRef<'a>) -> Result<Self, &'static str> {
+        let mut post = BoardPostRef {
+            id: Cow::Borrowed("0"),
+            title: Cow::Borrowed(""),
+            prefix: Cow::Borrowed(""),
+            author: Cow::Borrowed(""),
+            date: Cow::Borrowed(""),
+            desc: Cow::Borrowed(""),
+            category: BoardPost::default().category,
+            gp: 0,
+            reply: 0,
+            floor: 0,
+            url: String::new(),
+            deleted: matches(&elm, "board.post.deleted"),
+            pinned: matches(&elm, "board.post.pinned"),
+        };
 
         // id
-        let selector = Selector::parse(".b-list__main a").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            let url = dom.value().attr("href").unwrap();
-            let url = format!("{}/{}", DN, url);
-            post.url = url.to_owned();
-
-            Url::parse(url.as_str())
-                .unwrap()
-                .query_pairs()
-                .into_iter()
-                .for_each(|(k, v)| {
-                    if k == "snA" {
-                        post.id(v.to_string());
-                    }
-
-                    if k == "tnum" {
-                        post.floor(v.parse::<u16>().map_or(0, |v| v));
-                    }
-                });
+        if let Some(dom) = select_one(&elm, "board.post.link") {
+            let href = dom.value().attr("href").unwrap();
+            post.url = format!("{}/{}", DN, href);
+
+            if let Some(id) = query_value(href, "snA") {
+                post.id = Cow::Borrowed(id);
+            }
+
+            if let Some(floor) = query_value(href, "tnum") {
+                post.floor = floor.parse::<u16>().map_or(0, |v| v);
+            }
         }
 
         // title
-        let selector = Selector::parse(".b-list__main__title").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            post.title(dom.text().collect::<String>().trim().into());
+        if let Some(dom) = select_one(&elm, "board.post.title") {
+            post.title = trimmed_text(&dom);
+            post.prefix = match &post.title {
+                Cow::Borrowed(title) => {
+                    Cow::Borrowed(split_prefix(title).map_or("", |(_, name)| name))
+                }
+                Cow::Owned(title) => {
+                    Cow::Owned(split_prefix(title).map_or("", |(_, name)| name).to_string())
+                }
+            };
+            post.deleted |= post.title.contains(TOMBSTONE);
+        } else if post.deleted {
+            post.title = Cow::Borrowed(TOMBSTONE);
         } else {
             return Err("ad post");
         }
 
+        // the link of a tombstone only leads to an error page
+        if post.deleted {
+            post.url.clear();
+        }
+
         // description
-        let selector = Selector::parse(".b-list__brief").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            post.desc(dom.text().collect::<String>().trim().into());
+        if let Some(dom) = select_one(&elm, "board.post.brief") {
+            post.desc = trimmed_text(&dom);
         }
 
         // gp
-        let selector = Selector::parse(".b-list__summary__gp").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            let text: String = dom.text().collect::<String>().trim().into();
-            post.gp(text.parse::<u16>().unwrap());
+        if let Some(dom) = select_one(&elm, "board.post.gp") {
+            post.gp = trimmed_text(&dom).parse::<u16>().unwrap_or(0);
         }
 
         // reply
-        let selector =
-            Selector::parse(".b-list__count__number span").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            let text: String = dom.text().collect::<String>().trim().into();
-            post.reply_count(text.parse::<u16>().unwrap());
+        if let Some(dom) = select_one(&elm, "board.post.count") {
+            post.reply = trimmed_text(&dom).parse::<u16>().unwrap_or(0);
+        }
+
+        // author
+        if let Some(dom) = select_one(&elm, "board.post.author") {
+            post.author = trimmed_text(&dom);
         }
 
         // date
-        let selector = Selector::parse(".b-list__time__edittime a").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
-            let text: String = dom.text().collect::<String>().trim().into();
-            post.date(text);
+        if let Some(dom) = select_one(&elm, "board.post.time") {
+            post.date = trimmed_text(&dom);
         }
 
         // category
-        let selector = Selector::parse(".b-list__summary__sort a").expect("parse selector error");
-        if let Some(dom) = elm.select(&selector).next() {
+        if let Some(dom) = select_one(&elm, "board.post.category") {
             let name = dom.text().collect::<String>();
             let href = dom.value().attr("href").unwrap();
             let url =
                 Url::parse(format!("{}/{}", DN, href).as_str()).expect("invalid category url");
 
-            post.category(BoardCategory {
+            post.category = BoardCategory {
                 name,
                 id: BoardCategoryId::try_from(url).expect("invalid category url"),
-            });
+            };
         }
 
         Ok(post)
     }
 }
+
+impl TryFrom<ElementRef<'_>> for BoardPost {
+    type Error = &'static str;
+
+    fn try_from(elm: ElementRef) -> Result<Self, &'static str> {
+        BoardPostRef::try_from(elm).map(BoardPostRef::into_owned)
+    }
+}
@@ -0,0 +1,507 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    mem,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{
+    file::{with_lock, write_atomic},
+    hash::content_hash,
+};
+
+static INDEX_FILE: &str = "index.json";
+// every process using the cache takes it to change the index
+static LOCK_FILE: &str = "index.lock";
+// bumped when the entries change in a way serde defaults can't cover
+static INDEX_VERSION: u32 = 1;
+static ZSTD_LEVEL: i32 = 3;
+// pages written before the index is saved with them
+static PUT_BATCH: usize = 16;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub board: String,
+    pub file: String,
+    // size on disk
+    pub size: u64,
+    pub fetched_at: u64,
+    pub hits: u64,
+    // entries written before compression are plain html
+    #[serde(default)]
+    pub compressed: bool,
+    #[serde(default)]
+    pub raw_size: u64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CacheIndex {
+    #[serde(default)]
+    version: u32,
+    entries: HashMap<String, CacheEntry>,
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct BoardCacheStats {
+    pub board: String,
+    pub entries: usize,
+    pub size: u64,
+    pub hits: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub size: u64,
+    pub raw_size: u64,
+    pub max_size: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub boards: Vec<BoardCacheStats>,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+struct DiskCache {
+    dir: PathBuf,
+    max_size: u64,
+    ttl: Duration,
+    index: CacheIndex,
+    pending: Pending,
+}
+
+// counted since the index was last saved, added to the one on disk with the next change or on flush
+#[derive(Default)]
+struct Pending {
+    hits: u64,
+    misses: u64,
+    entries: HashMap<String, u64>,
+    // written pages, the files are already in place
+    put: HashMap<String, CacheEntry>,
+    // expired entries by fetched_at, dropped unless another process has fetched them again
+    stale: HashMap<String, u64>,
+}
+
+impl Pending {
+    fn is_empty(&self) -> bool {
+        self.hits == 0 && self.misses == 0 && self.put.is_empty() && self.stale.is_empty()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn read_compressed(path: &Path) -> Option<String> {
+    let file = File::open(path).ok()?;
+    // entries are only ever replaced by a rename or unlinked, never written in place,
+    // so the mapped file keeps its content even when another process changes the entry
+    let mmap = unsafe { Mmap::map(&file) }.ok()?;
+    let html = zstd::decode_all(&mmap[..]).ok()?;
+    String::from_utf8(html).ok()
+}
+
+fn read_index(dir: &Path) -> CacheIndex {
+    let mut index: CacheIndex = fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(s.as_ref()).ok())
+        // from a newer version, the cache only costs a refetch
+        .filter(|index: &CacheIndex| index.version <= INDEX_VERSION)
+        .unwrap_or_default();
+    index.version = INDEX_VERSION;
+    index
+}
+
+fn board_of(url: &Url) -> String {
+    url.query_pairs()
+        .find(|(k, _)| k == "bsn")
+        .map_or(String::new(), |(_, v)| v.to_string())
+}
+
+impl DiskCache {
+    fn save_index(&self) {
+        // renamed into place so a crash never leaves half an index
+        if let Ok(json) = serde_json::to_string(&self.index) {
+            write_atomic(&self.dir.join(INDEX_FILE), json).unwrap_or(());
+        }
+    }
+
+    // another process may have changed the index since it was read,
+    // so the change is made on a fresh read under the lock
+    fn update<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let lock = self.dir.join(LOCK_FILE);
+        with_lock(&lock, || {
+            self.reload();
+            let result = f(self);
+            self.save_index();
+            result
+        })
+    }
+
+    fn reload(&mut self) {
+        let pending = mem::take(&mut self.pending);
+        self.index = read_index(&self.dir);
+        self.index.hits += pending.hits;
+        self.index.misses += pending.misses;
+        for (url, hits) in pending.entries {
+            if let Some(entry) = self.index.entries.get_mut(&url) {
+                entry.hits += hits;
+            }
+        }
+        for (url, fetched_at) in pending.stale {
+            let stale = self.index.entries.get(&url);
+            if stale.is_some_and(|e| e.fetched_at == fetched_at) {
+                self.remove(url.as_ref());
+            }
+        }
+        for (url, entry) in pending.put {
+            // another process may have fetched the page again meanwhile
+            let current = self.index.entries.get(&url);
+            if current.is_none_or(|e| e.fetched_at <= entry.fetched_at) {
+                self.insert(url, entry);
+            }
+        }
+    }
+
+    // the file of a replaced entry is removed unless the new one took its name
+    fn insert(&mut self, url: String, entry: CacheEntry) {
+        let file = entry.file.to_owned();
+        if let Some(old) = self.index.entries.insert(url, entry) {
+            if old.file != file {
+                fs::remove_file(self.dir.join(old.file)).unwrap_or(());
+            }
+        }
+    }
+
+    // files of pages written by a process that exited before saving the index,
+    // only when too old to be served so the ones another process is writing stay
+    fn remove_unlisted(&self) {
+        let listed: Vec<&str> = self
+            .index
+            .entries
+            .values()
+            .map(|e| e.file.as_str())
+            .collect();
+        let Some(stale) = SystemTime::now().checked_sub(self.ttl) else {
+            return;
+        };
+        let files = fs::read_dir(&self.dir).into_iter().flatten().flatten();
+        for file in files {
+            let name = file.file_name().to_string_lossy().to_string();
+            let old = file
+                .metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| modified < stale);
+            if old && name != INDEX_FILE && name != LOCK_FILE && !listed.contains(&name.as_str()) {
+                fs::remove_file(file.path()).unwrap_or(());
+            }
+        }
+    }
+
+    fn remove(&mut self, url: &str) -> Option<CacheEntry> {
+        let entry = self.index.entries.remove(url)?;
+        fs::remove_file(self.dir.join(&entry.file)).unwrap_or(());
+        Some(entry)
+    }
+
+    fn remove_where(&mut self, f: impl Fn(&CacheEntry) -> bool) -> usize {
+        let urls: Vec<String> = self
+            .index
+            .entries
+            .iter()
+            .filter(|(_, e)| f(e))
+            .map(|(url, _)| url.to_owned())
+            .collect();
+
+        urls.iter().for_each(|url| {
+            self.remove(url);
+        });
+        urls.len()
+    }
+
+    fn read(&self, entry: &CacheEntry) -> Option<String> {
+        let path = self.dir.join(&entry.file);
+        if entry.compressed {
+            read_compressed(&path)
+        } else {
+            fs::read_to_string(path).ok()
+        }
+    }
+
+    // write the html compressed, return the entry without board and time
+    fn write(&self, file: String, html: &str) -> Option<CacheEntry> {
+        let data = zstd::encode_all(html.as_bytes(), ZSTD_LEVEL).ok()?;
+        write_atomic(&self.dir.join(&file), &data).ok()?;
+
+        Some(CacheEntry {
+            board: String::new(),
+            file,
+            size: data.len() as u64,
+            fetched_at: 0,
+            hits: 0,
+            compressed: true,
+            raw_size: html.len() as u64,
+        })
+    }
+
+    // compress the plain entries of the older version
+    fn compact(&mut self) -> usize {
+        let plain: Vec<(String, CacheEntry)> = self
+            .index
+            .entries
+            .iter()
+            .filter(|(_, e)| !e.compressed)
+            .map(|(url, e)| (url.to_owned(), e.clone()))
+            .collect();
+
+        let mut count = 0;
+        for (url, entry) in plain {
+            let compacted = self.read(&entry).and_then(|html| {
+                let file = format!("{}.zst", entry.file.trim_end_matches(".html"));
+                self.write(file, html.as_ref())
+            });
+
+            match compacted {
+                Some(compacted) => {
+                    fs::remove_file(self.dir.join(&entry.file)).unwrap_or(());
+                    self.index.entries.insert(
+                        url,
+                        CacheEntry {
+                            board: entry.board,
+                            fetched_at: entry.fetched_at,
+                            hits: entry.hits,
+                            ..compacted
+                        },
+                    );
+                    count += 1;
+                }
+                None => {
+                    self.remove(url.as_ref());
+                }
+            }
+        }
+
+        count
+    }
+
+    fn size(&self) -> u64 {
+        self.index.entries.values().map(|e| e.size).sum()
+    }
+
+    // drop the oldest entries until the cache fits in max_size
+    fn evict(&mut self) {
+        let mut size = self.size();
+        let mut entries: Vec<(String, u64, u64)> = self
+            .index
+            .entries
+            .iter()
+            .map(|(url, e)| (url.to_owned(), e.fetched_at, e.size))
+            .collect();
+        entries.sort_by_key(|(_, fetched_at, _)| *fetched_at);
+
+        for (url, _, entry_size) in entries {
+            if size <= self.max_size {
+                break;
+            }
+            self.remove(url.as_ref());
+            size -= entry_size;
+        }
+    }
+}
+
+// disk cache for page html, shared by the clones, the client holds one to read and write pages
+#[derive(Clone)]
+pub struct Cache {
+    disk: Arc<Mutex<DiskCache>>,
+    // no network, cached pages are served however old they are
+    offline: Arc<AtomicBool>,
+}
+
+impl Cache {
+    pub fn open(dir: PathBuf, max_size: u64, ttl: Duration) -> Cache {
+        fs::create_dir_all(&dir).unwrap_or(());
+        let mut cache = DiskCache {
+            dir,
+            max_size,
+            ttl,
+            index: CacheIndex::default(),
+            pending: Pending::default(),
+        };
+        cache.update(|cache| {
+            cache.compact();
+            cache.evict();
+            cache.remove_unlisted();
+        });
+
+        Cache {
+            disk: Arc::new(Mutex::new(cache)),
+            offline: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn with_disk<T>(&self, f: impl FnOnce(&mut DiskCache) -> T) -> Option<T> {
+        self.disk.lock().ok().map(|mut cache| f(&mut cache))
+    }
+
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::Relaxed);
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    pub fn get(&self, url: &Url) -> Option<String> {
+        let offline = self.is_offline();
+        self.with_disk(|cache| {
+            let ttl = if offline {
+                u64::MAX
+            } else {
+                cache.ttl.as_secs()
+            };
+            let key = url.as_str();
+            let fresh = cache
+                .index
+                .entries
+                .get(key)
+                .filter(|e| now().saturating_sub(e.fetched_at) <= ttl);
+
+            match fresh.and_then(|e| cache.read(e)) {
+                Some(html) => {
+                    cache.index.hits += 1;
+                    cache.pending.hits += 1;
+                    if let Some(entry) = cache.index.entries.get_mut(key) {
+                        entry.hits += 1;
+                        *cache.pending.entries.entry(key.to_owned()).or_default() += 1;
+                    }
+                    Some(html)
+                }
+                None => {
+                    cache.index.misses += 1;
+                    cache.pending.misses += 1;
+                    // kept for the next time the network is gone
+                    if !offline {
+                        if let Some(entry) = cache.index.entries.remove(key) {
+                            cache.pending.stale.insert(key.to_owned(), entry.fetched_at);
+                        }
+                    }
+                    None
+                }
+            }
+        })
+        .flatten()
+    }
+
+    pub fn put(&self, url: &Url, html: &str) {
+        self.with_disk(|cache| {
+            let file = format!("{:016x}.zst", content_hash(&[url.to_string()]));
+            let entry = match cache.write(file, html) {
+                Some(entry) => CacheEntry {
+                    board: board_of(url),
+                    fetched_at: now(),
+                    ..entry
+                },
+                None => return,
+            };
+
+            let key = url.to_string();
+            // the file has the same name, so the stale entry must not remove it
+            cache.pending.stale.remove(&key);
+            cache.pending.put.insert(key.to_owned(), entry.clone());
+            cache.insert(key, entry);
+            // the size limit is checked when the batch is saved
+            if cache.pending.put.len() >= PUT_BATCH {
+                cache.update(DiskCache::evict);
+            }
+        });
+    }
+
+    // save the pages, hits and misses since the last change, before the program exits
+    pub fn flush(&self) {
+        self.with_disk(|cache| {
+            if !cache.pending.is_empty() {
+                cache.update(|_| ());
+            }
+        });
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        // with the pages other processes have cached
+        self.with_disk(|cache| {
+            cache.update(|cache| {
+                let mut boards: BTreeMap<String, BoardCacheStats> = BTreeMap::new();
+                cache.index.entries.values().for_each(|e| {
+                    let stats = boards.entry(e.board.to_owned()).or_default();
+                    stats.board = e.board.to_owned();
+                    stats.entries += 1;
+                    stats.size += e.size;
+                    stats.hits += e.hits;
+                });
+
+                CacheStats {
+                    entries: cache.index.entries.len(),
+                    size: cache.size(),
+                    raw_size: cache
+                        .index
+                        .entries
+                        .values()
+                        .map(|e| e.raw_size.max(e.size))
+                        .sum(),
+                    max_size: cache.max_size,
+                    hits: cache.index.hits,
+                    misses: cache.index.misses,
+                    boards: boards.into_values().collect(),
+                }
+            })
+        })
+        .unwrap_or_default()
+    }
+
+    // remove entries fetched before max_age ago, return the count of removed entries
+    pub fn prune(&self, max_age: Duration) -> usize {
+        let max_age = max_age.as_secs();
+        self.with_disk(|cache| {
+            cache.update(|cache| {
+                cache.remove_where(|e| now().saturating_sub(e.fetched_at) > max_age)
+            })
+        })
+        .unwrap_or(0)
+    }
+
+    // clear the entries of a board, or all entries and stats when board is None
+    pub fn clear(&self, board: Option<&str>) -> usize {
+        self.with_disk(|cache| {
+            cache.update(|cache| match board {
+                Some(board) => cache.remove_where(|e| e.board == board),
+                None => {
+                    cache.index.hits = 0;
+                    cache.index.misses = 0;
+                    cache.remove_where(|_| true)
+                }
+            })
+        })
+        .unwrap_or(0)
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, RwLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use tokio::runtime::Handle;
+
+use scraper::Html;
+use serde::de::DeserializeOwned;
+use url::Url;
+
+use super::{age_gate, cache::Cache, is_background, metrics, Timeouts, TEXT_ONLY};
+
+// one per frontend, every page and fetch function takes it so tests and other
+// frontends can bring their own
+pub struct BahamutClient {
+    // the timeout is set by each request, see Timeouts
+    http: reqwest::Client,
+    // cookie of a logged in session, sent with every request
+    cookie: RwLock<Option<String>>,
+    // cookie sent after the age was confirmed on a gated board
+    age_cookie: RwLock<Option<String>>,
+    // set when a fetched page was the 18+ confirmation instead of the content
+    gated: AtomicBool,
+    // last failed request, the pages only return None
+    network_error: RwLock<Option<String>>,
+    // data saver, image and video blocks are left out when parsing
+    text_only: AtomicBool,
+    cache: Option<Cache>,
+    timeouts: RwLock<Timeouts>,
+    // least time between the start of two requests, zero to not wait
+    interval: RwLock<Duration>,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl Default for BahamutClient {
+    fn default() -> Self {
+        BahamutClient::new(reqwest::ClientBuilder::new().build().unwrap())
+    }
+}
+
+impl BahamutClient {
+    pub fn new(http: reqwest::Client) -> BahamutClient {
+        BahamutClient {
+            http,
+            cookie: RwLock::new(None),
+            age_cookie: RwLock::new(None),
+            gated: AtomicBool::new(false),
+            network_error: RwLock::new(None),
+            text_only: AtomicBool::new(false),
+            cache: None,
+            timeouts: RwLock::new(Timeouts::default()),
+            interval: RwLock::new(Duration::ZERO),
+            last_request: Mutex::new(None),
+        }
+    }
+
+    // pages are read from and written to the disk cache
+    pub fn with_cache(self, cache: Cache) -> BahamutClient {
+        BahamutClient {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    pub fn cache(&self) -> Option<&Cache> {
+        self.cache.as_ref()
+    }
+
+    pub fn set_interval(&self, interval: Duration) {
+        if let Ok(mut i) = self.interval.write() {
+            *i = interval;
+        }
+    }
+
+    pub fn set_cookie(&self, cookie: Option<String>) {
+        if let Ok(mut c) = self.cookie.write() {
+            *c = cookie;
+        }
+    }
+
+    pub fn cookie(&self) -> Option<String> {
+        self.cookie.read().ok()?.clone()
+    }
+
+    // user id of the logged in session, from the BAHAID cookie
+    pub fn session_user_id(&self) -> Option<String> {
+        let cookie = self.cookie()?;
+        cookie
+            .split(';')
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(k, _)| *k == "BAHAID")
+            .map(|(_, v)| v.trim().to_string())
+            .filter(|id| !id.is_empty())
+    }
+
+    pub fn confirm_age(&self, cookie: &str) {
+        if let Ok(mut c) = self.age_cookie.write() {
+            *c = Some(cookie.to_string());
+        }
+    }
+
+    // whether a page was gated since the last call
+    pub fn take_gated(&self) -> bool {
+        self.gated.swap(false, Ordering::Relaxed)
+    }
+
+    pub(crate) fn inspect(&self, document: &Html) {
+        if age_gate::is_gated(document) {
+            self.gated.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // error of a request failed since the last call, tells a network failure from a page that didn't parse
+    pub fn take_network_error(&self) -> Option<String> {
+        self.network_error.write().ok()?.take()
+    }
+
+    pub fn set_text_only(&self, on: bool) {
+        self.text_only.store(on, Ordering::Relaxed);
+    }
+
+    pub fn is_text_only(&self) -> bool {
+        self.text_only.load(Ordering::Relaxed)
+    }
+
+    // the parsers in `f` leave out images and videos in text only mode
+    pub(crate) fn parse<R>(&self, f: impl FnOnce() -> R) -> R {
+        TEXT_ONLY.sync_scope(self.is_text_only(), f)
+    }
+
+    pub fn set_timeouts(&self, timeouts: Timeouts) {
+        if let Ok(mut t) = self.timeouts.write() {
+            *t = timeouts;
+        }
+    }
+
+    pub(crate) fn request(&self, url: &Url) -> reqwest::RequestBuilder {
+        let req = self.http.get(url.as_str());
+        let age_cookie = self.age_cookie.read().ok().and_then(|c| c.clone());
+        let cookies: Vec<String> = [self.cookie(), age_cookie].into_iter().flatten().collect();
+
+        if cookies.is_empty() {
+            req
+        } else {
+            req.header(reqwest::header::COOKIE, cookies.join("; "))
+        }
+    }
+
+    // the turn is taken before sleeping, so requests from several threads are spaced too
+    async fn wait_turn(&self) {
+        let interval = self.interval.read().map_or(Duration::ZERO, |i| *i);
+        if interval.is_zero() {
+            return;
+        }
+
+        let wait = match self.last_request.lock() {
+            Ok(mut last) => {
+                let now = Instant::now();
+                let next = last.map_or(now, |t| (t + interval).max(now));
+                *last = Some(next);
+                next - now
+            }
+            Err(_) => Duration::ZERO,
+        };
+        if wait.is_zero() {
+            return;
+        }
+        // the blocking callers poll it with block_on, outside a runtime there's no timer
+        match Handle::try_current() {
+            Ok(_) => tokio::time::sleep(wait).await,
+            Err(_) => thread::sleep(wait),
+        }
+    }
+
+    async fn get_body<T, F, Fut>(&self, url: &Url, read: F) -> Result<T, Box<dyn std::error::Error>>
+    where
+        T: AsRef<[u8]>,
+        F: Fn(reqwest::Response) -> Fut,
+        Fut: Future<Output = reqwest::Result<T>>,
+    {
+        let timeouts = self.timeouts.read().map_or(Timeouts::default(), |t| *t);
+        let (timeout, retries) = if is_background() {
+            (timeouts.background, timeouts.background_retries)
+        } else {
+            (timeouts.foreground, 0)
+        };
+
+        let mut attempt = 0;
+        loop {
+            self.wait_turn().await;
+            let start = Instant::now();
+            let res = async { read(self.request(url).timeout(timeout).send().await?).await }.await;
+            metrics::record_request(
+                url.as_str(),
+                start,
+                res.as_ref().ok().map(|t| t.as_ref().len() as u64),
+            );
+
+            if res.is_ok() || attempt >= retries {
+                if let (Err(e), Ok(mut last)) = (&res, self.network_error.write()) {
+                    *last = Some(e.to_string());
+                }
+                return Ok(res?);
+            }
+            attempt += 1;
+        }
+    }
+
+    pub async fn get_text(&self, url: &Url) -> Result<String, Box<dyn std::error::Error>> {
+        self.get_body(url, |res| res.text()).await
+    }
+
+    pub async fn get_bytes(&self, url: &Url) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let bytes = self.get_body(url, |res| res.bytes()).await?;
+
+        Ok(bytes.to_vec())
+    }
+
+    pub async fn get_document(&self, url: &Url) -> Result<Html, Box<dyn std::error::Error>> {
+        let html = self.get_text(url).await?;
+
+        Ok(Html::parse_document(html.as_ref()))
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: &Url,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        let text = self.get_text(url).await?;
+
+        Ok(serde_json::from_str(text.as_ref())?)
+    }
+
+    // html of the page in the disk cache, when the client has one
+    pub(crate) fn cached(&self, url: &Url) -> Option<String> {
+        self.cache.as_ref()?.get(url)
+    }
+
+    pub(crate) fn store(&self, url: &Url, html: &str) {
+        if let Some(cache) = self.cache.as_ref() {
+            cache.put(url, html);
+        }
+    }
+}
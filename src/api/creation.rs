@@ -0,0 +1,151 @@
+use futures::executor::block_on;
+use scraper::{ElementRef, Html};
+use serde::Serialize;
+use url::Url;
+
+use crate::api::{
+    client::BahamutClient,
+    is_text_only,
+    post::PostDescription,
+    selector::{select, select_one},
+    user::User,
+};
+
+pub static HOME_DN: &str = "https://home.gamer.com.tw/";
+
+#[derive(Clone, Default, Serialize)]
+pub struct CreationItem {
+    pub title: String,
+    pub date: String,
+    pub url: String,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct CreationArticle {
+    pub title: String,
+    pub date: String,
+    pub author: String,
+    pub desc: PostDescription,
+}
+
+fn text_of(document: &ElementRef, key: &str) -> Option<String> {
+    let text = select_one(document, key)?.text().collect::<String>();
+    Some(text.trim().to_string())
+}
+
+fn absolute_url(href: &str) -> Option<String> {
+    let base = Url::parse(HOME_DN).ok()?;
+    Some(base.join(href).ok()?.to_string())
+}
+
+impl User {
+    // 創作 list in the 小屋 of the user
+    pub fn creations(client: &BahamutClient, id: &str) -> Vec<CreationItem> {
+        User::creations_page(client, id, 1)
+    }
+
+    // the first pages of the 創作 list, stop at the first empty page
+    pub fn creation_history(client: &BahamutClient, id: &str, pages: u16) -> Vec<CreationItem> {
+        let mut items: Vec<CreationItem> = vec![];
+        for page in 1..=pages.max(1) {
+            let list = User::creations_page(client, id, page);
+            // out of range pages may show the last page again
+            if list.is_empty()
+                || list
+                    .iter()
+                    .all(|item| items.iter().any(|i| i.url == item.url))
+            {
+                break;
+            }
+            items.extend(list);
+        }
+        items
+    }
+
+    pub fn creations_page(client: &BahamutClient, id: &str, page: u16) -> Vec<CreationItem> {
+        let url =
+            Url::parse(format!("{}creation.php?owner={}&page={}", HOME_DN, id, page).as_ref())
+                .unwrap();
+        if let Ok(document) = block_on(client.get_document(&url)) {
+            select(&document.root_element(), "creation.item")
+                .into_iter()
+                .filter_map(|el| {
+                    let link = select_one(&el, "creation.item.link")?;
+                    Some(CreationItem {
+                        title: link.text().collect::<String>().trim().to_string(),
+                        date: text_of(&el, "creation.item.date").unwrap_or_default(),
+                        url: absolute_url(link.value().attr("href")?)?,
+                    })
+                })
+                .collect::<Vec<CreationItem>>()
+        } else {
+            vec![]
+        }
+    }
+}
+
+impl CreationArticle {
+    pub fn get(client: &BahamutClient, url: &str) -> Option<CreationArticle> {
+        let url = Url::parse(url).ok()?;
+        let document = block_on(client.get_document(&url)).ok()?;
+        client.parse(|| CreationArticle::try_from(&document)).ok()
+    }
+
+    fn try_title_from_html(document: &Html) -> Option<String> {
+        text_of(&document.root_element(), "creation.title")
+    }
+
+    fn try_date_from_html(document: &Html) -> Option<String> {
+        text_of(&document.root_element(), "creation.date")
+    }
+
+    fn try_author_from_html(document: &Html) -> Option<String> {
+        text_of(&document.root_element(), "creation.author")
+    }
+
+    fn try_desc_from_html(document: &Html) -> Option<PostDescription> {
+        let content = select_one(&document.root_element(), "creation.content")?;
+
+        let desc = select(&content, "creation.content.block")
+            .into_iter()
+            .filter_map(|el| {
+                if el.value().name() == "img" {
+                    if is_text_only() {
+                        return None;
+                    }
+                    let src = el.value().attr("data-src").or(el.value().attr("src"))?;
+                    return absolute_url(src);
+                }
+
+                // only leaf blocks, nested blocks are visited on their own
+                if !select(&el, "creation.content.block").is_empty() {
+                    return None;
+                }
+
+                Some(el.text().collect::<String>().trim().to_string())
+            })
+            .collect::<PostDescription>();
+
+        // pure text content without any block
+        if desc.is_empty() {
+            return Some(content.text().map(|s| s.to_string()).collect());
+        }
+
+        Some(desc)
+    }
+}
+
+impl TryFrom<&Html> for CreationArticle {
+    type Error = &'static str;
+
+    fn try_from(document: &Html) -> Result<Self, Self::Error> {
+        let desc = CreationArticle::try_desc_from_html(document).ok_or("article not found")?;
+
+        Ok(CreationArticle {
+            title: CreationArticle::try_title_from_html(document).unwrap_or_default(),
+            date: CreationArticle::try_date_from_html(document).unwrap_or_default(),
+            author: CreationArticle::try_author_from_html(document).unwrap_or_default(),
+            desc,
+        })
+    }
+}
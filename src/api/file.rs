@@ -0,0 +1,40 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+fn tmp_of(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+// written to a temp file and renamed, a crash or another process reading it
+// never sees a half written file
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let tmp = tmp_of(path);
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents.as_ref())?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(tmp, path)
+}
+
+// shared by every process opening the path, released when the file is dropped
+pub fn lock_file(path: &Path) -> Option<File> {
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .ok()
+}
+
+// held across processes while f runs, f still runs when the lock can't be taken
+pub fn with_lock<R>(path: &Path, f: impl FnOnce() -> R) -> R {
+    let lock = lock_file(path).filter(|file| file.lock().is_ok());
+    let result = f();
+    drop(lock);
+    result
+}
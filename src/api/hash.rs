@@ -0,0 +1,16 @@
+// fnv-1a, kept in files and the stores so it has to stay the same across builds,
+// unlike the std hasher that may change with every rust release
+fn fnv(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+pub fn content_hash(desc: &[String]) -> u64 {
+    fnv(desc.iter().flat_map(|block| block.bytes().chain([b'\n'])))
+}
+
+// fetched images and downloaded files, to find the same one reposted under another link
+pub fn bytes_hash(bytes: &[u8]) -> u64 {
+    fnv(bytes.iter().copied())
+}
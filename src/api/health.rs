@@ -0,0 +1,28 @@
+use url::Url;
+
+use crate::api::{client::BahamutClient, DN};
+
+pub enum Health {
+    Ok,
+    // the error of the request
+    Unreachable(String),
+    // a cookie is saved but the site doesn't know it anymore
+    SessionExpired,
+}
+
+// the forum home page, when logged in its top bar links to the user's home with the id
+pub async fn check(client: &BahamutClient) -> Health {
+    let url = match Url::parse(DN) {
+        Ok(url) => url,
+        Err(e) => return Health::Unreachable(e.to_string()),
+    };
+    let html = match client.get_text(&url).await {
+        Ok(html) => html,
+        Err(e) => return Health::Unreachable(e.to_string()),
+    };
+
+    match client.session_user_id() {
+        Some(id) if !html.to_lowercase().contains(&id.to_lowercase()) => Health::SessionExpired,
+        _ => Health::Ok,
+    }
+}
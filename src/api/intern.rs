@@ -0,0 +1,38 @@
+use lazy_static::lazy_static;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, Weak},
+};
+
+use super::user::User;
+
+// dead entries are dropped when the table grows over this
+static PRUNE_SIZE: usize = 4096;
+
+lazy_static! {
+    // the same author shows up in many floors of a long thread
+    static ref USERS: Mutex<HashMap<String, Weak<User>>> = Mutex::new(HashMap::new());
+}
+
+// share the parsed user with the other floors of the same author
+pub fn intern_user(user: User) -> Arc<User> {
+    let mut users = match USERS.lock() {
+        Ok(users) => users,
+        Err(_) => return Arc::new(user),
+    };
+
+    if let Some(shared) = users.get(&user.id).and_then(Weak::upgrade) {
+        if *shared == user {
+            return shared;
+        }
+    }
+
+    if users.len() >= PRUNE_SIZE {
+        users.retain(|_, u| u.strong_count() > 0);
+    }
+
+    let shared = Arc::new(user);
+    users.insert(shared.id.to_owned(), Arc::downgrade(&shared));
+    shared
+}
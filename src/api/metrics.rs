@@ -0,0 +1,114 @@
+use lazy_static::lazy_static;
+
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// requests kept for the slowest recent request
+static RECENT_SIZE: usize = 20;
+
+lazy_static! {
+    static ref METRICS: Mutex<Metrics> = Mutex::new(Metrics::default());
+}
+
+#[derive(Clone)]
+pub struct RequestRecord {
+    pub url: String,
+    pub duration: Duration,
+    pub bytes: u64,
+    pub ok: bool,
+}
+
+// floors parsed from a thread page against the count the tnum of its url implies
+#[derive(Clone)]
+pub struct ParseCoverage {
+    pub url: String,
+    pub page: u16,
+    pub detected: usize,
+    pub expected: usize,
+}
+
+impl ParseCoverage {
+    // tnum may be older than the page, so only fewer floors point at a scraping regression
+    pub fn is_short(&self) -> bool {
+        self.detected < self.expected
+    }
+}
+
+// counters of this session
+#[derive(Clone, Default)]
+pub struct Metrics {
+    pub requests: u64,
+    pub failed: u64,
+    pub bytes: u64,
+    pub cache_hits: u64,
+    pub parses: u64,
+    pub parse_time: Duration,
+    pub recent: VecDeque<RequestRecord>,
+    // of the last parsed thread page
+    pub coverage: Option<ParseCoverage>,
+    // pages parsed with fewer floors than expected
+    pub short_pages: u64,
+}
+
+impl Metrics {
+    pub fn slowest(&self) -> Option<&RequestRecord> {
+        self.recent.iter().max_by_key(|r| r.duration)
+    }
+
+    pub fn average_parse_time(&self) -> Duration {
+        if self.parses == 0 {
+            Duration::ZERO
+        } else {
+            self.parse_time / self.parses as u32
+        }
+    }
+}
+
+pub fn snapshot() -> Metrics {
+    METRICS.lock().map_or(Metrics::default(), |m| m.clone())
+}
+
+pub(crate) fn record_request(url: &str, start: Instant, bytes: Option<u64>) {
+    if let Ok(mut m) = METRICS.lock() {
+        m.requests += 1;
+        m.bytes += bytes.unwrap_or(0);
+        if bytes.is_none() {
+            m.failed += 1;
+        }
+
+        if m.recent.len() >= RECENT_SIZE {
+            m.recent.pop_front();
+        }
+        m.recent.push_back(RequestRecord {
+            url: url.to_string(),
+            duration: start.elapsed(),
+            bytes: bytes.unwrap_or(0),
+            ok: bytes.is_some(),
+        });
+    }
+}
+
+pub(crate) fn record_cache_hit() {
+    if let Ok(mut m) = METRICS.lock() {
+        m.cache_hits += 1;
+    }
+}
+
+pub fn record_coverage(coverage: ParseCoverage) {
+    if let Ok(mut m) = METRICS.lock() {
+        if coverage.is_short() {
+            m.short_pages += 1;
+        }
+        m.coverage = Some(coverage);
+    }
+}
+
+pub(crate) fn record_parse(start: Instant) {
+    if let Ok(mut m) = METRICS.lock() {
+        m.parses += 1;
+        m.parse_time += start.elapsed();
+    }
+}
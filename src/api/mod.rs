@@ -1,41 +1,75 @@
-use lazy_static::lazy_static;
-
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    future::Future,
+    time::{Duration, Instant},
+};
 
 use futures::executor::block_on;
 use scraper::Html;
-use serde::de::DeserializeOwned;
 use url::Url;
 
+pub mod age_gate;
+pub mod anime;
 pub mod board;
+pub mod cache;
+pub mod client;
+pub mod creation;
+pub mod file;
+pub mod hash;
+pub mod health;
+pub mod intern;
+pub mod metrics;
+pub mod news;
 pub mod post;
+pub mod preview;
 pub mod search;
+pub mod selector;
 pub mod user;
 
 pub static DN: &str = "https://forum.gamer.com.tw/";
 
-lazy_static! {
-    static ref HTTP_CLIENT: reqwest::Client = reqwest::ClientBuilder::new()
-        .timeout(Duration::from_secs(5))
-        .build()
-        .unwrap();
+use client::BahamutClient;
+
+tokio::task_local! {
+    static BACKGROUND: bool;
+    // set from the client around parsing, see BahamutClient::parse
+    static TEXT_ONLY: bool;
 }
 
-async fn get_document(url: &Url) -> Result<Html, Box<dyn std::error::Error>> {
-    let html = HTTP_CLIENT.get(url.as_str()).send().await?.text().await?;
+// data saver, image and video blocks are left out when parsing
+pub fn is_text_only() -> bool {
+    TEXT_ONLY.try_with(|t| *t).unwrap_or(false)
+}
+
+// navigation fails fast to show the error, prefetch and polling wait longer and retry quietly
+#[derive(Clone, Copy)]
+pub struct Timeouts {
+    pub foreground: Duration,
+    pub background: Duration,
+    pub background_retries: u32,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Timeouts {
+            foreground: Duration::from_secs(5),
+            background: Duration::from_secs(20),
+            background_retries: 1,
+        }
+    }
+}
 
-    Ok(Html::parse_document(html.as_ref()))
+// requests made in `f` use the background timeout
+pub fn background<R>(f: impl FnOnce() -> R) -> R {
+    BACKGROUND.sync_scope(true, f)
 }
 
-async fn get_json<T: DeserializeOwned>(url: &Url) -> Result<T, Box<dyn std::error::Error>> {
-    let res = HTTP_CLIENT
-        .get(url.as_str())
-        .send()
-        .await?
-        .json::<T>()
-        .await?;
+pub async fn background_async<F: Future>(f: F) -> F::Output {
+    BACKGROUND.scope(true, f).await
+}
 
-    Ok(res)
+fn is_background() -> bool {
+    BACKGROUND.try_with(|b| *b).unwrap_or(false)
 }
 
 pub struct WebSite {
@@ -58,6 +92,7 @@ where
     fn increase_page(&mut self);
     fn decrease_page(&mut self);
     fn max(&self) -> u16;
+    fn client(&self) -> &BahamutClient;
 
     fn cached_page_html(&self, _: u16) -> Option<Html> {
         None
@@ -74,7 +109,26 @@ where
 
     fn get_page_html(&self, page: u16) -> Option<Html> {
         let url = self.url(&page);
-        block_on(get_document(&url)).ok()
+        if let Some(html) = self.client().cached(&url) {
+            metrics::record_cache_hit();
+            return Some(Html::parse_document(html.as_ref()));
+        }
+
+        self.fetch_page_html(page)
+    }
+
+    // always from network, the result is written to the disk cache
+    fn fetch_page_html(&self, page: u16) -> Option<Html> {
+        let html = self.fetch_page_text(page)?;
+        Some(Html::parse_document(html.as_ref()))
+    }
+
+    // raw html from network, for keeping the page as is
+    fn fetch_page_text(&self, page: u16) -> Option<String> {
+        let url = self.url(&page);
+        let html = block_on(self.client().get_text(&url)).ok()?;
+        self.client().store(&url, html.as_ref());
+        Some(html)
     }
 
     fn get(&self, page: u16, ignore_cache: bool) -> Option<T> {
@@ -85,18 +139,28 @@ where
 
         let cache = self.cache();
         if !ignore_cache && cache.contains_key(&page) {
+            metrics::record_cache_hit();
             return cache.get(&page).unwrap().as_ref().cloned();
         }
 
         let document = if let Some(v) = self.cached_page_html(page) {
             Some(v)
+        } else if ignore_cache {
+            self.fetch_page_html(page)
         } else {
             self.get_page_html(page)
         };
 
         if let Some(document) = document {
+            self.client().inspect(&document);
             let url = self.url(&page);
-            if let Ok(board) = T::try_from(WebSite { url, document }) {
+            let start = Instant::now();
+            let result = self
+                .client()
+                .parse(|| T::try_from(WebSite { url, document }));
+            metrics::record_parse(start);
+
+            if let Ok(board) = result {
                 return Some(board);
             }
         }
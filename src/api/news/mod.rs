@@ -0,0 +1,159 @@
+use futures::executor::block_on;
+use scraper::{ElementRef, Html};
+use serde::Serialize;
+use url::Url;
+
+use crate::api::{
+    client::BahamutClient,
+    is_text_only,
+    post::PostDescription,
+    selector::{select, select_one},
+};
+
+pub static GNN_DN: &str = "https://gnn.gamer.com.tw/";
+
+#[derive(Clone, Default, Serialize)]
+pub struct NewsHeadline {
+    pub title: String,
+    pub summary: String,
+    pub url: String,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct NewsBoard {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct NewsArticle {
+    pub title: String,
+    pub date: String,
+    pub desc: PostDescription,
+    pub boards: Vec<NewsBoard>,
+}
+
+fn text_of(document: &ElementRef, key: &str) -> Option<String> {
+    let text = select_one(document, key)?.text().collect::<String>();
+    Some(text.trim().to_string())
+}
+
+fn absolute_url(href: &str) -> Option<String> {
+    let base = Url::parse(GNN_DN).ok()?;
+    Some(base.join(href).ok()?.to_string())
+}
+
+pub struct News;
+
+impl News {
+    pub fn get_headlines(client: &BahamutClient) -> Vec<NewsHeadline> {
+        let url = Url::parse(GNN_DN).unwrap();
+        if let Ok(document) = block_on(client.get_document(&url)) {
+            select(&document.root_element(), "news.item")
+                .into_iter()
+                .filter_map(|el| {
+                    let link = select_one(&el, "news.item.link")?;
+                    Some(NewsHeadline {
+                        title: link.text().collect::<String>().trim().to_string(),
+                        summary: text_of(&el, "news.item.summary").unwrap_or_default(),
+                        url: absolute_url(link.value().attr("href")?)?,
+                    })
+                })
+                .collect::<Vec<NewsHeadline>>()
+        } else {
+            vec![]
+        }
+    }
+}
+
+impl NewsArticle {
+    pub fn get(client: &BahamutClient, url: &str) -> Option<NewsArticle> {
+        let url = Url::parse(url).ok()?;
+        let document = block_on(client.get_document(&url)).ok()?;
+        client.parse(|| NewsArticle::try_from(&document)).ok()
+    }
+
+    fn try_title_from_html(document: &Html) -> Option<String> {
+        text_of(&document.root_element(), "news.title")
+    }
+
+    fn try_date_from_html(document: &Html) -> Option<String> {
+        text_of(&document.root_element(), "news.date")
+    }
+
+    fn try_desc_from_html(document: &Html) -> Option<PostDescription> {
+        let content = select_one(&document.root_element(), "news.content")?;
+
+        let desc = select(&content, "news.content.block")
+            .into_iter()
+            .filter_map(|el| {
+                if el.value().name() == "img" {
+                    if is_text_only() {
+                        return None;
+                    }
+                    let src = el.value().attr("data-src").or(el.value().attr("src"))?;
+                    return absolute_url(src);
+                }
+
+                // only leaf blocks, nested blocks are visited on their own
+                if !select(&el, "news.content.block").is_empty() {
+                    return None;
+                }
+
+                let text = el.text().collect::<String>();
+                let text = text.trim();
+                if text.is_empty() {
+                    None
+                } else {
+                    Some(text.to_string())
+                }
+            })
+            .collect::<PostDescription>();
+
+        Some(desc)
+    }
+
+    // boards linked from the article, e.g. the forum board of the game
+    fn try_boards_from_html(document: &Html) -> Option<Vec<NewsBoard>> {
+        let mut boards: Vec<NewsBoard> = vec![];
+
+        select(&document.root_element(), "news.board")
+            .into_iter()
+            .for_each(|el| {
+                let href = match el.value().attr("href").and_then(absolute_url) {
+                    Some(href) => href,
+                    None => return,
+                };
+
+                let id = Url::parse(href.as_ref()).ok().and_then(|url| {
+                    url.query_pairs()
+                        .find(|(k, _)| k == "bsn")
+                        .map(|(_, v)| v.to_string())
+                });
+
+                let name = el.text().collect::<String>().trim().to_string();
+                if let Some(id) = id {
+                    if !name.is_empty() && !boards.iter().any(|b| b.id == id) {
+                        boards.push(NewsBoard { id, name });
+                    }
+                }
+            });
+
+        Some(boards)
+    }
+}
+
+impl TryFrom<&Html> for NewsArticle {
+    type Error = &'static str;
+
+    fn try_from(document: &Html) -> Result<Self, Self::Error> {
+        let desc = NewsArticle::try_desc_from_html(document).ok_or("article not found")?;
+
+        Ok(NewsArticle {
+            title: NewsArticle::try_title_from_html(document).unwrap_or_default(),
+            date: NewsArticle::try_date_from_html(document).unwrap_or_default(),
+            desc,
+            boards: NewsArticle::try_boards_from_html(document).unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    error::Error,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use reqwest::{header::RANGE, StatusCode};
+use scraper::ElementRef;
+use serde::Serialize;
+use url::Url;
+
+use crate::api::{client::BahamutClient, metrics, selector::select};
+
+// a file linked from a floor
+#[derive(Clone, Serialize, Default)]
+pub struct Attachment {
+    pub name: String,
+    pub url: String,
+    // as written beside the link, like 1.2MB
+    pub size: Option<String>,
+}
+
+impl Attachment {
+    pub fn try_from_html(document: &ElementRef) -> Vec<Attachment> {
+        select(document, "post.attachment")
+            .into_iter()
+            .filter_map(|el| {
+                let url = el.value().attr("href")?.trim().to_string();
+                let text = el
+                    .value()
+                    .attr("download")
+                    .filter(|s| !s.trim().is_empty())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| el.text().collect::<String>());
+                let (name, size) = split_size(text.trim());
+                let name = match name {
+                    "" => Url::parse(&url)
+                        .ok()?
+                        .path_segments()?
+                        .next_back()?
+                        .to_string(),
+                    name => name.to_string(),
+                };
+                let size = size
+                    .or_else(|| el.value().attr("title").and_then(|t| split_size(t).1))
+                    .map(str::to_string);
+                Some(Attachment { name, url, size })
+            })
+            .collect()
+    }
+
+    // saved as <name>.part until it's complete, and continued from there with a range request
+    pub async fn download(
+        &self,
+        client: &BahamutClient,
+        dir: &Path,
+    ) -> Result<PathBuf, Box<dyn Error>> {
+        let url = Url::parse(&self.url)?;
+        let name = file_name(&self.name);
+        let path = dir.join(&name);
+        let part = dir.join(format!("{}.part", name));
+        fs::create_dir_all(dir)?;
+
+        let offset = fs::metadata(&part).map_or(0, |m| m.len());
+        let mut req = client.request(&url);
+        if offset > 0 {
+            req = req.header(RANGE, format!("bytes={}-", offset));
+        }
+
+        let start = Instant::now();
+        let mut res = req.send().await?;
+        // the part was already complete
+        if offset > 0 && res.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            fs::rename(&part, &path)?;
+            return Ok(path);
+        }
+        res = res.error_for_status()?;
+
+        // the server may ignore the range and send it all again
+        let resume = res.status() == StatusCode::PARTIAL_CONTENT;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resume)
+            .truncate(!resume)
+            .open(&part)?;
+        let mut received = 0;
+        while let Some(chunk) = res.chunk().await? {
+            file.write_all(&chunk)?;
+            received += chunk.len() as u64;
+        }
+        metrics::record_request(url.as_str(), start, Some(received));
+
+        fs::rename(&part, &path)?;
+        Ok(path)
+    }
+}
+
+// `name.zip (1.2MB)` -> (`name.zip`, `1.2MB`)
+fn split_size(text: &str) -> (&str, Option<&str>) {
+    let open = match text
+        .strip_suffix([')', '）'])
+        .and_then(|t| t.rfind(['(', '（']))
+    {
+        Some(i) => i,
+        None => return (text, None),
+    };
+    let inner = text[open..]
+        .trim_start_matches(['(', '（'])
+        .trim_end_matches([')', '）'])
+        .trim();
+    let unit = inner.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.' || c == ' ');
+    let is_size = inner.len() > unit.len()
+        && ["B", "KB", "MB", "GB", "K", "M", "G"]
+            .iter()
+            .any(|u| unit.eq_ignore_ascii_case(u));
+
+    if is_size {
+        (text[..open].trim(), Some(inner))
+    } else {
+        (text, None)
+    }
+}
+
+// the name is from the page, keep it inside the download directory
+fn file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let name = match name.trim_matches(['.', ' ']) {
+        "" => "attachment".to_string(),
+        name => name.to_string(),
+    };
+
+    // device names are never files on Windows, whatever the extension
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    let stem = stem.to_ascii_uppercase();
+    let port = |prefix: &str| {
+        stem.strip_prefix(prefix)
+            .is_some_and(|n| n.len() == 1 && matches!(n.as_bytes()[0], b'1'..=b'9'))
+    };
+    if matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL") || port("COM") || port("LPT") {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_name_stays_in_directory() {
+        assert_eq!(file_name("../a/b\\c.zip"), "_a_b_c.zip");
+        assert_eq!(file_name("C:x.txt"), "C_x.txt");
+        assert_eq!(file_name(" .. "), "attachment");
+    }
+
+    #[test]
+    fn file_name_device_names() {
+        assert_eq!(file_name("con.txt"), "_con.txt");
+        assert_eq!(file_name("COM1"), "_COM1");
+        assert_eq!(file_name("COM0.zip"), "COM0.zip");
+        assert_eq!(file_name("console.txt"), "console.txt");
+    }
+}
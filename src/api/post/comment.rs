@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use url::Url;
 
-use crate::api::{get_json, DN};
+use crate::api::{client::BahamutClient, DN};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PostComment {
@@ -30,12 +30,13 @@ pub struct PostComment {
 
 impl PostComment {
     pub fn get_comment(
+        client: &BahamutClient,
         id: String,
         c_id: String,
     ) -> Result<Vec<PostComment>, Box<dyn std::error::Error>> {
         let url = format!("{}ajax/moreCommend.php?bsn={}&snB={}", DN, id, c_id);
         let url = Url::parse(url.as_ref()).unwrap();
-        let map = block_on(get_json::<HashMap<String, Value>>(&url))?;
+        let map = block_on(client.get_json::<HashMap<String, Value>>(&url))?;
 
         let mut list = map
             .iter()
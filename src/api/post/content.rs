@@ -1,61 +1,169 @@
-use scraper::{ElementRef, Selector};
+use std::sync::Arc;
+
+use scraper::ElementRef;
 use serde::Serialize;
 
-use crate::api::user::User;
+use crate::api::{
+    client::BahamutClient,
+    is_text_only,
+    selector::{select, select_one},
+    user::User,
+    DN,
+};
 
-use super::{comment::PostComment, PostDescription};
+use super::{attachment::Attachment, comment::PostComment, PostDescription};
 
 pub trait CommentReadable {
-    fn comment(&self) -> Vec<PostComment>;
+    // comments under the floor, from the ajax endpoint of the board
+    fn comment(
+        &self,
+        client: &BahamutClient,
+        bsn: &str,
+    ) -> Result<Vec<PostComment>, Box<dyn std::error::Error>>;
+}
+
+// system messages and notices pinned inside the thread are floors without an author
+#[derive(Clone, Copy, Serialize, Default, PartialEq)]
+pub enum FloorKind {
+    #[default]
+    Reply,
+    Notice,
 }
 
 #[derive(Clone, Serialize, Default)]
 pub struct PostContent {
     pub id: String,
     pub desc: PostDescription,
-    pub user: User,
+    pub user: Arc<User>,
     pub floor: u16,
     pub date: String,
+    pub emphasis: Vec<String>,
+    pub attachments: Vec<Attachment>,
+    pub kind: FloorKind,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PostOutline {
+    pub line: usize,
+    pub text: String,
 }
 
 impl CommentReadable for PostContent {
-    fn comment(&self) -> Vec<PostComment> {
-        vec![]
+    fn comment(
+        &self,
+        client: &BahamutClient,
+        bsn: &str,
+    ) -> Result<Vec<PostComment>, Box<dyn std::error::Error>> {
+        PostComment::get_comment(client, bsn.to_string(), self.id.to_owned())
     }
 }
 
 impl PostContent {
+    // link to this floor alone, the floor id is unique in the board
+    pub fn permalink(&self, board_id: &str) -> String {
+        format!("{}Co.php?bsn={}&sn={}", DN, board_id, self.id)
+    }
+
+    pub fn text(&self) -> String {
+        self.desc
+            .iter()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty() && !s.starts_with("http"))
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+
+    pub fn outline(&self) -> Vec<PostOutline> {
+        self.desc
+            .iter()
+            .enumerate()
+            .filter_map(|(line, s)| {
+                let text = s.trim();
+                let is_heading = text.chars().count() <= 40
+                    && (self.emphasis.iter().any(|e| e == text) || is_heading_like(text));
+
+                if !text.is_empty() && is_heading {
+                    Some(PostOutline {
+                        line,
+                        text: text.to_string(),
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub fn try_emphasis_from_html(document: &ElementRef) -> Option<Vec<String>> {
+        let emphasis = select(document, "post.emphasis.block")
+            .into_iter()
+            .filter_map(|el| {
+                let text = el.text().collect::<String>();
+                let text = text.trim();
+                let emphasis_text = select(&el, "post.emphasis")
+                    .into_iter()
+                    .flat_map(|e| e.text())
+                    .collect::<String>();
+
+                if !text.is_empty() && emphasis_text.trim() == text {
+                    Some(text.to_string())
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Some(emphasis)
+    }
+
     pub fn try_floor_from_html(document: &ElementRef) -> Option<u16> {
-        let selector = Selector::parse(".floor").unwrap();
-        let floor = document
-            .select(&selector)
-            .next()
-            .unwrap()
+        let floor = select_one(document, "post.floor.number")?
             .value()
-            .attr("data-floor")
-            .unwrap()
+            .attr("data-floor")?
             .parse::<u16>()
             .map_or(0u16, |v| v);
 
         Some(floor)
     }
 
+    // the text of the notice as its content, the floor number is kept when there is one
+    pub fn try_notice_from_html(document: &ElementRef) -> Option<PostContent> {
+        let notice = select_one(document, "post.notice")?;
+        let desc: PostDescription = notice
+            .text()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect();
+        if desc.is_empty() {
+            return None;
+        }
+
+        let id = PostContent::try_id_from_html(document)
+            .or_else(|| document.value().id().map(String::from))
+            .unwrap_or_default();
+        Some(PostContent {
+            id,
+            desc,
+            floor: PostContent::try_floor_from_html(document).unwrap_or_default(),
+            date: PostContent::try_date_from_html(document).unwrap_or_default(),
+            kind: FloorKind::Notice,
+            ..PostContent::default()
+        })
+    }
+
     pub fn try_id_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".c-article").unwrap();
-        let id = document.select(&selector).next()?.value().id()?;
+        let id = select_one(document, "post.article")?.value().id()?;
 
         Some(id.replace("cf", ""))
     }
 
     pub fn try_desc_from_html(document: &ElementRef) -> Option<PostDescription> {
-        let selector = Selector::parse(".c-article__content").unwrap();
-        let desc_selector = Selector::parse("div").unwrap();
-
-        let desc = document
-            .select(&selector)
+        let desc = select(document, "post.content")
+            .into_iter()
             .flat_map(|el| {
-                let content = el.select(&desc_selector);
-                let is_pure_text = content.clone().next().is_none();
+                let content = select(&el, "post.content.block");
+                let is_pure_text = content.is_empty();
 
                 if is_pure_text {
                     return el
@@ -65,20 +173,24 @@ impl PostContent {
                 }
 
                 content
+                    .into_iter()
                     .flat_map(|el| {
                         // youtube
-                        let yt_selector = Selector::parse(".video-youtube iframe").unwrap();
-                        let yt = el.select(&yt_selector).next();
-                        if yt.is_some() {
-                            return vec![yt.unwrap().value().attr("data-src").unwrap().to_string()];
+                        if let Some(yt) = select_one(&el, "post.youtube") {
+                            if is_text_only() {
+                                return vec![];
+                            }
+                            return vec![yt.value().attr("data-src").unwrap().to_string()];
                         }
 
                         // image
-                        let img_selector = Selector::parse("a img").unwrap();
-                        let img_dom = el.select(&img_selector);
-                        let img = img_dom.clone().next();
-                        if img.is_some() {
+                        let img_dom = select(&el, "post.image");
+                        if !img_dom.is_empty() {
+                            if is_text_only() {
+                                return vec![];
+                            }
                             return img_dom
+                                .into_iter()
                                 .map(|_img| _img.value().attr("data-src").unwrap().to_string())
                                 .collect::<Vec<String>>();
                         }
@@ -93,10 +205,7 @@ impl PostContent {
     }
 
     pub fn try_date_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".edittime").unwrap();
-        let date = document
-            .select(&selector)
-            .next()?
+        let date = select_one(document, "post.edittime")?
             .text()
             .next()?
             .to_string();
@@ -104,3 +213,33 @@ impl PostContent {
         Some(date)
     }
 }
+
+fn is_heading_like(text: &str) -> bool {
+    let mut chars = text.chars();
+    let first = match chars.next() {
+        Some(c) => c,
+        None => return false,
+    };
+
+    if "■□◆◇●○★☆▶▷#".contains(first) {
+        return true;
+    }
+
+    if first == '【' && text.ends_with('】') {
+        return true;
+    }
+
+    if first == '第' && text.chars().take(6).any(|c| "章節部篇回".contains(c)) {
+        return true;
+    }
+
+    // 1. / 1、 / 一、 / (1)
+    let is_numeral = |c: char| c.is_ascii_digit() || "一二三四五六七八九十".contains(c);
+    let text = text.trim_start_matches(['(', '（']);
+    let digits = text.chars().take_while(|c| is_numeral(*c)).count();
+    let mut rest = text.chars().skip(digits);
+
+    digits > 0
+        && rest.next().is_some_and(|c| ".、)）".contains(c))
+        && !rest.next().is_some_and(|c| c.is_ascii_digit())
+}
@@ -1,13 +1,17 @@
+mod attachment;
 mod comment;
 mod content;
 mod page;
+mod series;
 
 #[allow(clippy::module_inception)]
 mod post;
 
 pub type PostDescription = Vec<String>;
 
+pub use attachment::Attachment;
 pub use comment::PostComment;
-pub use content::{CommentReadable, PostContent};
+pub use content::{CommentReadable, FloorKind, PostContent, PostOutline};
 pub use page::{PostPage, PostPageRef, PostPageUrlParameter};
-pub use post::Post;
+pub use post::{Participant, Post, ReplyNode};
+pub use series::PostSeries;
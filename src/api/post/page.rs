@@ -1,25 +1,23 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use scraper::{ElementRef, Html, Selector};
+use scraper::{ElementRef, Html};
 use url::Url;
 
-use crate::api::{CachedPage, DN};
+use crate::api::{client::BahamutClient, selector::select, CachedPage, DN};
 
 use super::post::Post;
 
 #[derive(Default)]
 pub struct PostPageUrlParameter {
-    board_id: String,
-    id: String,
-    floor: u16,
+    pub board_id: String,
+    pub id: String,
+    pub floor: u16,
 }
 
 impl TryFrom<String> for PostPageUrlParameter {
     type Error = &'static str;
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let url = Url::parse(value.as_str())
-            .map_err(|_| "invalid url string")
-            .unwrap();
+        let url = Url::parse(value.as_str()).map_err(|_| "invalid url string")?;
         PostPageUrlParameter::try_from(url).map_err(|_| "")
     }
 }
@@ -55,10 +53,11 @@ pub struct PostPage {
 
     cache: HashMap<u16, Option<Post>>,
     first_page_html: Option<Html>,
+    client: Arc<BahamutClient>,
 }
 
 impl PostPage {
-    pub fn new(board_id: &str, id: &str) -> PostPage {
+    pub fn new(board_id: &str, id: &str, client: Arc<BahamutClient>) -> PostPage {
         PostPage {
             board_id: board_id.to_string(),
             id: id.to_string(),
@@ -67,27 +66,54 @@ impl PostPage {
             floor: 0,
             cache: HashMap::new(),
             first_page_html: None,
+            client,
         }
     }
 
+    pub fn from_param(param: PostPageUrlParameter, client: Arc<BahamutClient>) -> PostPage {
+        let mut page = PostPage::new(param.board_id.as_ref(), param.id.as_ref(), client);
+        page.floor(param.floor);
+        page
+    }
+
     pub fn init(&mut self) {
-        if let Some(document) = self.get_page_html(1) {
-            let root = document.root_element();
-            let max = PostPage::try_page_from_html(&root).map_or(0, |v| v);
-            self.max = max;
-            self.first_page_html = Some(document);
+        if let Some(document) = self.fetch_page_html(1) {
+            self.first_page(document);
         }
     }
 
+    // init with the first page fetched by the caller
+    pub fn first_page(&mut self, document: Html) {
+        let root = document.root_element();
+        self.max = PostPage::try_page_from_html(&root).map_or(0, |v| v);
+        self.first_page_html = Some(document);
+    }
+
     pub fn floor(&mut self, floor: u16) {
         self.floor = floor;
     }
 
+    // posts of later pages don't have the first floor
+    pub fn fill_starter(&self, post: &mut Post) {
+        if post.author.is_some() {
+            return;
+        }
+
+        let starter = self
+            .first_page_html
+            .as_ref()
+            .and_then(|html| Post::try_starter_from_posts(&Post::posts(&html.root_element())));
+
+        if let Some((author, created)) = starter {
+            post.author = Some(author);
+            post.created = created;
+        }
+    }
+
     fn try_page_from_html(document: &ElementRef) -> Option<u16> {
-        let selector = Selector::parse(".BH-pagebtnA a").unwrap();
-        let max: u16 = document
-            .select(&selector)
-            .last()?
+        let max: u16 = select(document, "page.button")
+            .into_iter()
+            .next_back()?
             .text()
             .next()?
             .to_string()
@@ -138,20 +164,9 @@ impl CachedPage<Post> for PostPage {
     fn max(&self) -> u16 {
         self.max
     }
-}
-
-impl TryFrom<PostPageUrlParameter> for PostPage {
-    type Error = &'static str;
 
-    fn try_from(value: PostPageUrlParameter) -> Result<Self, Self::Error> {
-        let PostPageUrlParameter {
-            board_id,
-            id,
-            floor,
-        } = value;
-        let mut page = PostPage::new(board_id.as_ref(), id.as_ref());
-        page.floor(floor);
-        Ok(page)
+    fn client(&self) -> &BahamutClient {
+        &self.client
     }
 }
 
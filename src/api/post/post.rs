@@ -1,37 +1,191 @@
-use scraper::{ElementRef, Selector};
+use std::{collections::HashMap, sync::Arc};
+
+use scraper::ElementRef;
 use url::Url;
 
-use crate::api::{user::User, WebSite};
+use crate::api::{
+    intern::intern_user,
+    metrics::ParseCoverage,
+    selector::{select, select_one},
+    user::User,
+    WebSite,
+};
+
+use super::{
+    attachment::Attachment,
+    content::{FloorKind, PostContent},
+};
 
-use super::content::PostContent;
+// floors of a full thread page
+pub const FLOORS_PER_PAGE: usize = 20;
 
 #[derive(Clone, Default)]
 pub struct Post {
     pub id: String,
     pub title: String,
-    pub posts: Vec<PostContent>,
+    pub board: String,
+    pub tags: Vec<String>,
+    // user id of board moderators
+    pub moderators: Vec<String>,
+    // shared between the page cache and the reader
+    pub posts: Vec<Arc<PostContent>>,
+    pub floor: u16,
+    // thread starter, from the first floor
+    pub author: Option<Arc<User>>,
+    pub created: String,
+}
+
+// a user of the loaded floors
+#[derive(Clone)]
+pub struct Participant {
+    pub user: Arc<User>,
+    // in the order of the floors
+    pub floors: Vec<u16>,
+}
+
+impl Participant {
+    pub fn first_floor(&self) -> u16 {
+        self.floors.first().copied().unwrap_or_default()
+    }
+
+    pub fn last_floor(&self) -> u16 {
+        self.floors.last().copied().unwrap_or_default()
+    }
+}
+
+// a floor of the reply tree, placed under the first loaded floor it quotes
+#[derive(Clone)]
+pub struct ReplyNode {
     pub floor: u16,
+    pub depth: usize,
+}
+
+// floors written like #12, B12 or 12樓, and users mentioned by @id
+fn quotes(text: &str) -> (Vec<u16>, Vec<String>) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut floors = vec![];
+    let mut mentions = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        if chars[i] == '@' {
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            if i > start + 1 {
+                mentions.push(chars[start + 1..i].iter().collect());
+            }
+            continue;
+        }
+
+        if !chars[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        let before = |n: usize| start.checked_sub(n).map(|j| chars[j]);
+        let hash = matches!(before(1), Some('#' | '＃'));
+        let b = matches!(before(1), Some('B' | 'b'))
+            && before(2).is_none_or(|c| !c.is_ascii_alphanumeric());
+        let suffix = chars.get(i) == Some(&'樓');
+        let suffix_only = before(1).is_none_or(|c| !c.is_ascii_alphanumeric());
+        if hash || b || (suffix && suffix_only) {
+            if let Ok(floor) = chars[start..i].iter().collect::<String>().parse() {
+                floors.push(floor);
+            }
+        }
+    }
+
+    (floors, mentions)
 }
 
 impl Post {
-    pub fn posts(document: &ElementRef) -> Vec<PostContent> {
-        let selector = Post::get_root_elm_selector();
-        document
-            .select(&selector)
+    // floors quoting a loaded floor before them, under it in the order of the floors.
+    // only the floors in a conversation are listed
+    pub fn conversation(&self) -> Vec<ReplyNode> {
+        let mut children: HashMap<u16, Vec<u16>> = HashMap::new();
+        let mut has_parent: Vec<u16> = vec![];
+        // the latest floor of each user, for @id
+        let mut latest: HashMap<String, u16> = HashMap::new();
+
+        for post in self.posts.iter() {
+            let text = post.desc.join("\n");
+            let (floors, mentions) = quotes(text.as_ref());
+            let mentioned = mentions
+                .iter()
+                .filter_map(|id| latest.get(&id.to_lowercase()).copied());
+            let parent = floors
+                .into_iter()
+                .chain(mentioned)
+                .find(|f| *f < post.floor && self.posts.iter().any(|p| p.floor == *f));
+
+            if let Some(parent) = parent {
+                children.entry(parent).or_default().push(post.floor);
+                has_parent.push(post.floor);
+            }
+            latest.insert(post.user.id.to_lowercase(), post.floor);
+        }
+
+        let mut nodes = vec![];
+        let mut stack: Vec<ReplyNode> = self
+            .posts
+            .iter()
+            .rev()
+            .map(|p| p.floor)
+            .filter(|f| !has_parent.contains(f) && children.contains_key(f))
+            .map(|floor| ReplyNode { floor, depth: 0 })
+            .collect();
+        while let Some(node) = stack.pop() {
+            if let Some(replies) = children.get(&node.floor) {
+                stack.extend(replies.iter().rev().map(|floor| ReplyNode {
+                    floor: *floor,
+                    depth: node.depth + 1,
+                }));
+            }
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    // unique users of the loaded pages in the order of their first floor
+    pub fn participants(&self) -> Vec<Participant> {
+        let mut participants: Vec<Participant> = vec![];
+        for post in self.posts.iter().filter(|p| p.kind == FloorKind::Reply) {
+            match participants.iter_mut().find(|p| p.user.id == post.user.id) {
+                Some(participant) => participant.floors.push(post.floor),
+                None => participants.push(Participant {
+                    user: post.user.clone(),
+                    floors: vec![post.floor],
+                }),
+            }
+        }
+        participants
+    }
+
+    pub fn posts(document: &ElementRef) -> Vec<Arc<PostContent>> {
+        select(document, "post.floor")
+            .into_iter()
             .filter_map(|dom| {
-                Some(PostContent {
+                if let Some(notice) = PostContent::try_notice_from_html(&dom) {
+                    return Some(Arc::new(notice));
+                }
+
+                Some(Arc::new(PostContent {
                     id: PostContent::try_id_from_html(&dom)?,
                     desc: PostContent::try_desc_from_html(&dom)?,
-                    user: User::try_from(&dom).ok()?,
+                    user: intern_user(User::try_from(&dom).ok()?),
                     floor: PostContent::try_floor_from_html(&dom)?,
                     date: PostContent::try_date_from_html(&dom)?,
-                })
+                    emphasis: PostContent::try_emphasis_from_html(&dom)?,
+                    attachments: Attachment::try_from_html(&dom),
+                    kind: FloorKind::Reply,
+                }))
             })
-            .collect::<Vec<PostContent>>()
-    }
-
-    fn get_root_elm_selector() -> Selector {
-        Selector::parse(".c-section[id]").unwrap()
+            .collect::<Vec<Arc<PostContent>>>()
     }
 
     fn try_id_from_url(url: &Url) -> Option<String> {
@@ -53,15 +207,84 @@ impl Post {
     }
 
     fn try_title_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".c-post__header__title").unwrap();
-        let title = document
-            .select(&selector)
-            .next()?
+        let title = select_one(document, "post.title")?
             .text()
             .collect::<String>();
 
         Some(title)
     }
+
+    // head title is like "title @board 哈啦板 - 巴哈姆特"
+    fn try_board_from_html(document: &ElementRef) -> Option<String> {
+        let title = select_one(document, "post.board")?
+            .text()
+            .collect::<String>();
+        let (_, board) = title.rsplit_once('@')?;
+
+        board.split(' ').next().map(String::from)
+    }
+
+    fn try_tags_from_html(document: &ElementRef) -> Option<Vec<String>> {
+        let tags = select(document, "post.tag")
+            .into_iter()
+            .map(|el| el.text().collect::<String>().trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+
+        Some(tags)
+    }
+
+    fn try_moderators_from_html(document: &ElementRef) -> Option<Vec<String>> {
+        let mut moderators: Vec<String> = vec![];
+        select(document, "post.moderator")
+            .into_iter()
+            .filter_map(|el| {
+                let href = el.value().attr("href")?;
+                let url = Url::parse(href).ok()?;
+                let id = url
+                    .query_pairs()
+                    .find(|(k, _)| k == "owner")
+                    .map(|(_, v)| v.to_string());
+                id.or_else(|| Some(el.text().collect::<String>().trim().to_string()))
+            })
+            .for_each(|id| {
+                if !id.is_empty() && !moderators.contains(&id) {
+                    moderators.push(id);
+                }
+            });
+
+        Some(moderators)
+    }
+
+    // the reply floors of the page against the ones tnum says it has, unknown when tnum is 0
+    pub fn coverage(&self, url: &str, page: u16) -> Option<ParseCoverage> {
+        if self.floor == 0 {
+            return None;
+        }
+
+        let page = page.max(1);
+        let before = (page - 1) as usize * FLOORS_PER_PAGE;
+        let expected = (self.floor as usize)
+            .saturating_sub(before)
+            .min(FLOORS_PER_PAGE);
+        let detected = self
+            .posts
+            .iter()
+            .filter(|p| p.kind == FloorKind::Reply)
+            .count();
+        Some(ParseCoverage {
+            url: url.to_owned(),
+            page,
+            detected,
+            expected,
+        })
+    }
+
+    // author and date of the first floor
+    pub fn try_starter_from_posts(posts: &[Arc<PostContent>]) -> Option<(Arc<User>, String)> {
+        let first = posts.iter().find(|p| p.floor == 1)?;
+        Some((first.user.clone(), first.date.to_owned()))
+    }
 }
 
 impl TryFrom<WebSite> for Post {
@@ -69,14 +292,23 @@ impl TryFrom<WebSite> for Post {
 
     fn try_from(web: WebSite) -> Result<Self, Self::Error> {
         let WebSite { url, document } = web;
-        let selector = Post::get_root_elm_selector();
-        let top_post_elm = document.select(&selector).next().unwrap();
+        let top_post_elm =
+            select_one(&document.root_element(), "post.floor").ok_or("post floor not found")?;
+
+        let root = document.root_element();
+        let posts = Post::posts(&root);
+        let (author, created) = Post::try_starter_from_posts(&posts).unzip();
 
         let post = Post {
             id: Post::try_id_from_url(&url).ok_or("can't get id")?,
             floor: Post::try_last_floor_from_url(&url).ok_or("can't get last floor")?,
             title: Post::try_title_from_html(&top_post_elm).ok_or("post title invalid")?,
-            posts: Post::posts(&document.root_element()),
+            board: Post::try_board_from_html(&root).unwrap_or_default(),
+            tags: Post::try_tags_from_html(&root).unwrap_or_default(),
+            moderators: Post::try_moderators_from_html(&root).unwrap_or_default(),
+            posts,
+            author,
+            created: created.unwrap_or_default(),
         };
 
         Ok(post)
@@ -0,0 +1,45 @@
+use crate::api::CachedPage;
+
+use super::{page::PostPage, post::Post};
+
+// continuation threads read as one thread, pages are numbered across all parts
+pub struct PostSeries {
+    pub parts: Vec<PostPage>,
+}
+
+impl PostSeries {
+    pub fn new(parts: Vec<PostPage>) -> PostSeries {
+        PostSeries { parts }
+    }
+
+    pub fn init(&mut self) {
+        self.parts.iter_mut().for_each(|part| part.init());
+    }
+
+    pub fn max(&self) -> u16 {
+        self.parts.iter().map(|part| part.max.max(1)).sum()
+    }
+
+    // map a page of the series to (part index, page of the part)
+    pub fn locate(&self, page: u16) -> Option<(usize, u16)> {
+        let mut page = page.max(1);
+        for (i, part) in self.parts.iter().enumerate() {
+            let max = part.max.max(1);
+            if page <= max {
+                return Some((i, page));
+            }
+            page -= max;
+        }
+
+        None
+    }
+
+    pub fn get_and_cache(&mut self, page: u16, ignore_cache: bool) -> Option<Post> {
+        let (i, page) = self.locate(page)?;
+        let mut post = self.parts.get_mut(i)?.get_and_cache(page, ignore_cache)?;
+        // the whole series is started by the first part
+        post.author = None;
+        self.parts.first()?.fill_starter(&mut post);
+        Some(post)
+    }
+}
@@ -0,0 +1,91 @@
+use serde::Serialize;
+use url::Url;
+
+use crate::api::{client::BahamutClient, selector::select_one};
+
+// title and description of an external page, shown under its link
+#[derive(Clone, Default, Serialize)]
+pub struct LinkPreview {
+    pub title: String,
+    pub description: String,
+}
+
+impl LinkPreview {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_empty() && self.description.is_empty()
+    }
+
+    // opengraph first, then the plain <title> and description meta
+    pub async fn fetch(client: &BahamutClient, url: &str) -> Option<LinkPreview> {
+        let url = Url::parse(url).ok()?;
+        let document = client.get_document(&url).await.ok()?;
+        let root = document.root_element();
+
+        let meta = |key: &str| {
+            select_one(&root, key)
+                .and_then(|el| el.value().attr("content"))
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        let title = meta("preview.og.title").or_else(|| {
+            select_one(&root, "preview.title")
+                .map(|el| el.text().collect::<String>().trim().to_string())
+        })?;
+        let description = meta("preview.og.description")
+            .or_else(|| meta("preview.description"))
+            .unwrap_or_default();
+
+        Some(LinkPreview { title, description })
+    }
+}
+
+// http(s) links in a text block
+pub fn find_links(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '「' | '」'))
+        .filter_map(|word| word.find("http").map(|i| &word[i..]))
+        .map(|link| link.trim_end_matches(['.', ',', ')', '，', '。']))
+        .filter(|link| link.starts_with("https://") || link.starts_with("http://"))
+}
+
+// host matches one of the domains or their subdomains
+pub fn is_allowed(link: &str, domains: &[String]) -> bool {
+    let host = match Url::parse(link)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+    {
+        Some(host) => host,
+        None => return false,
+    };
+
+    domains
+        .iter()
+        .any(|d| host == *d || host.ends_with(format!(".{}", d).as_str()))
+}
+
+// gif stickers can't be drawn in the terminal, so they are only marked and opened outside
+pub fn is_animated(link: &str) -> bool {
+    Url::parse(link.trim()).is_ok_and(|url| {
+        let path = url.path().to_lowercase();
+        path.ends_with(".gif") || path.ends_with(".gifv") || path.ends_with(".apng")
+    })
+}
+
+// image blocks of a floor are the links parsed from its <img>
+pub fn is_image(link: &str) -> bool {
+    Url::parse(link.trim()).is_ok_and(|url| {
+        let path = url.path().to_lowercase();
+        [".jpg", ".jpeg", ".png", ".gif", ".webp"]
+            .iter()
+            .any(|ext| path.ends_with(ext))
+    })
+}
+
+// raw bytes of an image, decoded by the viewer
+pub async fn fetch_image(
+    client: &BahamutClient,
+    link: &str,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let url = Url::parse(link.trim())?;
+    client.get_bytes(&url).await
+}
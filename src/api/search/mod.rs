@@ -1,8 +1,11 @@
 use futures::executor::block_on;
-use scraper::{ElementRef, Selector};
+use scraper::ElementRef;
 use serde::Serialize;
 
-use crate::api::get_document;
+use crate::api::{
+    client::BahamutClient,
+    selector::{select, select_one},
+};
 
 use super::{UrlWithId, DN};
 
@@ -15,16 +18,13 @@ impl UrlWithId<&str> for BoardSearch {
 }
 
 impl BoardSearch {
-    pub fn get_search_result(query: &str) -> Vec<SearchResult> {
+    pub fn get_search_result(client: &BahamutClient, query: &str) -> Vec<SearchResult> {
         let url = BoardSearch::url(query);
-        let selector = Selector::parse(".BH-table tr").unwrap();
-        let td_selector = Selector::parse("td").unwrap();
-
-        if let Ok(document) = block_on(get_document(&url)) {
-            document
-                .select(&selector)
+        if let Ok(document) = block_on(client.get_document(&url)) {
+            select(&document.root_element(), "search.row")
+                .into_iter()
                 .filter_map(|dom| {
-                    let td = dom.select(&td_selector).nth(2)?;
+                    let td = select(&dom, "search.cell").into_iter().nth(2)?;
                     SearchResult::try_from(td).ok()
                 })
                 .collect::<Vec<SearchResult>>()
@@ -43,24 +43,18 @@ pub struct SearchResult {
 
 impl SearchResult {
     fn try_id_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse("a").unwrap();
-        let id = document
-            .select(&selector)
-            .next()?
+        let id = select_one(document, "search.link")?
             .value()
             .attr("href")?
             .split('=')
-            .last()?
+            .next_back()?
             .to_string();
 
         Some(id)
     }
 
     fn try_name_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse("a").unwrap();
-        let name = document
-            .select(&selector)
-            .next()?
+        let name = select_one(document, "search.link")?
             .text()
             .skip(1)
             .collect::<String>();
@@ -69,10 +63,7 @@ impl SearchResult {
     }
 
     fn try_platform_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse("a").unwrap();
-        let platform = document
-            .select(&selector)
-            .next()?
+        let platform = select_one(document, "search.link")?
             .text()
             .next()?
             .to_string();
@@ -0,0 +1,198 @@
+use lazy_static::lazy_static;
+
+use std::{collections::HashMap, sync::RwLock};
+
+use scraper::{ElementRef, Selector};
+
+pub static PROFILES: &[&str] = &["current", "legacy"];
+
+pub struct SelectorDef {
+    pub key: &'static str,
+    pub page: &'static str,
+    // (profile, css) in the order to try
+    pub candidates: &'static [(&'static str, &'static str)],
+}
+
+macro_rules! selectors {
+    ($($key:expr, $page:expr => [$($profile:expr => $css:expr),* $(,)?]);* $(;)?) => {
+        &[$(SelectorDef { key: $key, page: $page, candidates: &[$(($profile, $css)),*] }),*]
+    };
+}
+
+pub static SELECTORS: &[SelectorDef] = selectors![
+    "board.row", "board" => ["current" => ".b-list__row", "legacy" => ".b-list-item, .FM-blist3 tr"];
+    "board.name", "board" => ["current" => "head title"];
+    "board.tag", "board" => ["current" => ".b-tags__item a", "legacy" => ".BH-tabA a"];
+    "board.post.link", "board" => ["current" => ".b-list__main a", "legacy" => "a.b-list-item__title, .FM-blist3A a"];
+    "board.post.title", "board" => ["current" => ".b-list__main__title", "legacy" => ".b-list-item__title, .FM-blist3A a"];
+    "board.post.brief", "board" => ["current" => ".b-list__brief", "legacy" => ".b-list-item__brief"];
+    "board.post.gp", "board" => ["current" => ".b-list__summary__gp", "legacy" => ".b-list-item__gp"];
+    "board.post.count", "board" => ["current" => ".b-list__count__number span", "legacy" => ".b-list-item__count span"];
+    "board.post.author", "board" => ["current" => ".b-list__count__user a", "legacy" => ".b-list-item__count__user a"];
+    "board.post.time", "board" => ["current" => ".b-list__time__edittime a", "legacy" => ".b-list-item__time a"];
+    "board.post.deleted", "board" => ["current" => ".b-list__row--delete", "legacy" => ".b-list-item--delete, .FM-blist3 tr.del"];
+    "board.post.pinned", "board" => ["current" => ".b-list__row--sticky", "legacy" => ".b-list-item--sticky, .FM-blist3 tr.top"];
+    "board.post.category", "board" => ["current" => ".b-list__summary__sort a", "legacy" => ".b-list-item__sort a"];
+    "moderation.row", "moderation" => ["current" => ".BH-table tr", "legacy" => ".FM-blist3 tr, table tr"];
+    "moderation.cell", "moderation" => ["current" => "td"];
+    "page.button", "page" => ["current" => ".BH-pagebtnA a", "legacy" => ".pagenow a, .BH-pagebtnB a"];
+    "post.floor", "post" => ["current" => ".c-section[id]", "legacy" => "section[id^=\"post_\"], .FM-P2"];
+    "post.board", "post" => ["current" => "head title"];
+    "post.tag", "post" => ["current" => ".c-post__header__tag a", "legacy" => ".c-post__header .tag a"];
+    "post.moderator", "post" => ["current" => ".BH-menu__moderator a", "legacy" => ".BH-menuE a[href*=\"owner=\"]"];
+    "post.title", "post" => ["current" => ".c-post__header__title", "legacy" => "h1.c-post__header__title, .FM-P2A h1"];
+    "post.article", "post" => ["current" => ".c-article", "legacy" => ".FM-P2B"];
+    "post.content", "post" => ["current" => ".c-article__content", "legacy" => ".FM-P2B article, .FM-P2B"];
+    "post.content.block", "post" => ["current" => "div"];
+    "post.emphasis.block", "post" => ["current" => ".c-article__content div", "legacy" => ".FM-P2B div"];
+    "post.emphasis", "post" => ["current" => "b, strong, h1, h2, h3, h4, h5, h6, font[size]"];
+    "post.notice", "post" => ["current" => ".c-post__system, .c-section__notice", "legacy" => ".FM-P2 .systemmsg"];
+    "post.floor.number", "post" => ["current" => ".floor", "legacy" => ".FM-P2A [data-floor]"];
+    "post.youtube", "post" => ["current" => ".video-youtube iframe", "legacy" => "iframe[data-src*=\"youtube\"]"];
+    "post.image", "post" => ["current" => "a img"];
+    "post.attachment", "post" => ["current" => ".c-article__content a[download], .c-article__attach a[href]", "legacy" => ".FM-P2B a[href*=\"attach\"]"];
+    "post.edittime", "post" => ["current" => ".edittime", "legacy" => ".FM-P2A .ST1"];
+    "search.row", "search" => ["current" => ".BH-table tr", "legacy" => ".BH-lbox table tr"];
+    "search.cell", "search" => ["current" => "td"];
+    "search.link", "search" => ["current" => "a"];
+    "user.level", "user" => ["current" => ".userlevel"];
+    "user.avatar", "user" => ["current" => ".c-user__avatar", "legacy" => "[data-gamercard-userid]"];
+    "user.avatar.image", "user" => ["current" => ".c-user__avatar img", "legacy" => "[data-gamercard-userid] img"];
+    "user.title", "user" => ["current" => ".usertitle", "legacy" => ".c-user__title"];
+    "user.name", "user" => ["current" => ".username"];
+    "user.race", "user" => ["current" => ".userrace img"];
+    "user.career", "user" => ["current" => ".usercareer img"];
+    "anime.new", "anime" => ["current" => ".newanime-block .newanime-date-area"];
+    "anime.new.link", "anime" => ["current" => "a.anime-card-block"];
+    "anime.new.name", "anime" => ["current" => ".anime-name p"];
+    "anime.new.episode", "anime" => ["current" => ".anime-episode"];
+    "anime.new.date", "anime" => ["current" => ".anime-date-info"];
+    "anime.day", "anime" => ["current" => ".programlist-wrap .programlist-block"];
+    "anime.day.title", "anime" => ["current" => ".day-title"];
+    "anime.day.item", "anime" => ["current" => "a.text-anime-info"];
+    "anime.day.name", "anime" => ["current" => ".text-anime-name"];
+    "anime.day.episode", "anime" => ["current" => ".text-anime-number"];
+    "anime.day.time", "anime" => ["current" => ".text-anime-time"];
+    "news.item", "news" => ["current" => ".GN-lbox2B"];
+    "news.item.link", "news" => ["current" => ".GN-lbox2D a"];
+    "news.item.summary", "news" => ["current" => ".GN-lbox2C"];
+    "news.title", "news" => ["current" => ".GN-lbox3 h1", "legacy" => "h1"];
+    "news.date", "news" => ["current" => ".GN-lbox3 .GN-lbox3C", "legacy" => ".GN-lbox3C"];
+    "news.content", "news" => ["current" => ".GN-lbox3B"];
+    "news.content.block", "news" => ["current" => "div, p, img"];
+    "news.board", "news" => ["current" => "a[href*=\"bsn=\"]"];
+    "creation.item", "creation" => ["current" => ".HOME-mainbox1"];
+    "creation.item.link", "creation" => ["current" => ".HOME-mainbox1b h1 a", "legacy" => "h1 a"];
+    "creation.item.date", "creation" => ["current" => ".ST1"];
+    "creation.title", "creation" => ["current" => ".TS1", "legacy" => "h1"];
+    "creation.date", "creation" => ["current" => ".ST1"];
+    "creation.author", "creation" => ["current" => ".MSG-list8 .ST1 a", "legacy" => ".ST1 a"];
+    "creation.content", "creation" => ["current" => ".MSG-list8C", "legacy" => ".article-content"];
+    "creation.content.block", "creation" => ["current" => "div, p, img"];
+    "gate.age", "gate" => ["current" => "#BH-adult, .forum-adult", "legacy" => "form[action*=\"adult\"]"];
+    "preview.og.title", "preview" => ["current" => "meta[property=\"og:title\"]"];
+    "preview.og.description", "preview" => ["current" => "meta[property=\"og:description\"]"];
+    "preview.title", "preview" => ["current" => "head title"];
+    "preview.description", "preview" => ["current" => "meta[name=\"description\"]"];
+];
+
+lazy_static! {
+    // profile that worked last time for each page type
+    static ref ACTIVE: RwLock<HashMap<&'static str, &'static str>> = RwLock::new(HashMap::new());
+    // user defined css by selector key, tried before every profile
+    static ref OVERRIDES: RwLock<HashMap<&'static str, String>> = RwLock::new(HashMap::new());
+}
+
+static OVERRIDE_PROFILE: &str = "override";
+
+fn find(key: &str) -> Option<&'static SelectorDef> {
+    SELECTORS.iter().find(|def| def.key == key)
+}
+
+fn candidates(def: &'static SelectorDef) -> Vec<(&'static str, String)> {
+    let active = ACTIVE.read().ok().and_then(|a| a.get(def.page).copied());
+    let mut candidates: Vec<(&'static str, String)> = def
+        .candidates
+        .iter()
+        .map(|(profile, css)| (*profile, css.to_string()))
+        .collect();
+    candidates.sort_by_key(|(profile, _)| Some(*profile) != active);
+
+    if let Some(css) = OVERRIDES.read().ok().and_then(|o| o.get(def.key).cloned()) {
+        candidates.insert(0, (OVERRIDE_PROFILE, css));
+    }
+
+    candidates
+}
+
+// replace the css of a selector key, used to hotfix parsers when the site changed
+pub fn set_override(key: &str, css: &str) -> Result<(), String> {
+    let def = find(key).ok_or(format!("unknown selector: {}", key))?;
+    Selector::parse(css).map_err(|_| format!("invalid selector of {}: {}", key, css))?;
+
+    if let Ok(mut o) = OVERRIDES.write() {
+        o.insert(def.key, css.to_string());
+    }
+
+    Ok(())
+}
+
+fn record(page: &'static str, profile: &'static str) {
+    let changed = ACTIVE
+        .read()
+        .map_or(true, |a| a.get(page) != Some(&profile));
+
+    if changed {
+        if let Ok(mut a) = ACTIVE.write() {
+            a.insert(page, profile);
+        }
+    }
+}
+
+// select by the named selector, retry with the fallback profiles when nothing matched
+pub fn select<'a>(document: &ElementRef<'a>, key: &str) -> Vec<ElementRef<'a>> {
+    let def = match find(key) {
+        Some(def) => def,
+        None => return vec![],
+    };
+
+    for (profile, css) in candidates(def) {
+        if let Ok(selector) = Selector::parse(css.as_ref()) {
+            let result: Vec<ElementRef> = document.select(&selector).collect();
+            if !result.is_empty() {
+                // override is not a site layout, keep the profile that worked before
+                if profile != OVERRIDE_PROFILE {
+                    record(def.page, profile);
+                }
+                return result;
+            }
+        }
+    }
+
+    vec![]
+}
+
+// whether the element itself matches, like a class on a row
+pub fn matches(element: &ElementRef, key: &str) -> bool {
+    let def = match find(key) {
+        Some(def) => def,
+        None => return false,
+    };
+
+    candidates(def).into_iter().any(|(_, css)| {
+        Selector::parse(css.as_ref()).is_ok_and(|selector| selector.matches(element))
+    })
+}
+
+pub fn select_one<'a>(document: &ElementRef<'a>, key: &str) -> Option<ElementRef<'a>> {
+    select(document, key).into_iter().next()
+}
+
+// profiles recorded for each page type
+pub fn active_profiles() -> Vec<(&'static str, &'static str)> {
+    let mut profiles: Vec<(&'static str, &'static str)> = ACTIVE
+        .read()
+        .map_or(vec![], |a| a.iter().map(|(k, v)| (*k, *v)).collect());
+    profiles.sort();
+    profiles
+}
@@ -1,9 +1,11 @@
 use std::fmt::Display;
 
-use scraper::{ElementRef, Selector};
+use scraper::ElementRef;
 use serde::Serialize;
 
-#[derive(Clone, Serialize)]
+use crate::api::{creation::HOME_DN, selector::select_one};
+
+#[derive(Clone, PartialEq, Serialize)]
 pub enum UserRace {
     Human,
     Elf,
@@ -24,7 +26,7 @@ impl Display for UserRace {
     }
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, PartialEq, Serialize)]
 pub enum UserCareer {
     Noob,
     Sword,
@@ -53,13 +55,17 @@ impl Display for UserCareer {
     }
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, PartialEq, Serialize)]
 pub struct User {
     pub id: String,
     pub race: UserRace,
     pub carrer: UserCareer,
     pub name: String,
     pub lv: u8,
+    // 稱號 shown under the name, empty when not set
+    pub title: String,
+    // image url, empty when the floor has none
+    pub avatar: String,
 }
 
 impl Default for User {
@@ -71,16 +77,34 @@ impl Default for User {
             carrer: UserCareer::Noob,
             name: empty.to_string(),
             lv: 1,
+            title: empty.to_string(),
+            avatar: empty.to_string(),
         }
     }
 }
 
 impl User {
+    pub fn home_url(&self) -> String {
+        format!("{}homeindex.php?owner={}", HOME_DN, self.id)
+    }
+
+    // level and career in one short line, like LV.12 劍士
+    pub fn badge(&self) -> String {
+        format!("LV.{} {}", self.lv, self.carrer)
+    }
+
+    fn try_title_from_html(document: &ElementRef) -> Option<String> {
+        let title = select_one(document, "user.title")?
+            .text()
+            .collect::<String>()
+            .trim()
+            .to_string();
+
+        Some(title)
+    }
+
     fn try_level_from_html(document: &ElementRef) -> Option<u8> {
-        let selector = Selector::parse(".userlevel").unwrap();
-        let lv = document
-            .select(&selector)
-            .next()?
+        let lv = select_one(document, "user.level")?
             .text()
             .last()?
             .to_string();
@@ -89,10 +113,7 @@ impl User {
     }
 
     fn try_id_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".c-user__avatar").unwrap();
-        let id = document
-            .select(&selector)
-            .next()?
+        let id = select_one(document, "user.avatar")?
             .value()
             .attr("data-gamercard-userid")?
             .to_string();
@@ -100,11 +121,23 @@ impl User {
         Some(id)
     }
 
+    // lazy loaded images keep the url in data-src
+    fn try_avatar_from_html(document: &ElementRef) -> Option<String> {
+        let img = select_one(document, "user.avatar.image")?;
+        let src = img
+            .value()
+            .attr("data-src")
+            .or_else(|| img.value().attr("src"))?;
+        let src = match src.strip_prefix("//") {
+            Some(rest) => format!("https://{}", rest),
+            None => src.to_string(),
+        };
+
+        Some(src)
+    }
+
     fn try_name_from_html(document: &ElementRef) -> Option<String> {
-        let selector = Selector::parse(".username").unwrap();
-        let name = document
-            .select(&selector)
-            .next()?
+        let name = select_one(document, "user.name")?
             .text()
             .next()?
             .to_string();
@@ -113,14 +146,11 @@ impl User {
     }
 
     fn try_race_from_html(document: &ElementRef) -> Option<UserRace> {
-        let selector = Selector::parse(".userrace img").unwrap();
-        let race = document
-            .select(&selector)
-            .next()?
+        let race = select_one(document, "user.race")?
             .value()
             .attr("src")?
             .split('/')
-            .last()?;
+            .next_back()?;
 
         let race = match race {
             "human.png" => UserRace::Human,
@@ -134,14 +164,11 @@ impl User {
     }
 
     fn try_crarrer_from_html(document: &ElementRef) -> Option<UserCareer> {
-        let selector = Selector::parse(".usercareer img").unwrap();
-        let crarrer = document
-            .select(&selector)
-            .next()?
+        let crarrer = select_one(document, "user.career")?
             .value()
             .attr("src")?
             .split('/')
-            .last()?;
+            .next_back()?;
 
         let crarrer = match crarrer {
             "noob.png" => UserCareer::Noob,
@@ -169,6 +196,8 @@ impl TryFrom<&ElementRef<'_>> for User {
             name: User::try_name_from_html(document).ok_or("user name invalid")?,
             race: User::try_race_from_html(document).map_or(UserRace::Unknown, |x| x),
             carrer: User::try_crarrer_from_html(document).map_or(UserCareer::Unknown, |x| x),
+            title: User::try_title_from_html(document).unwrap_or_default(),
+            avatar: User::try_avatar_from_html(document).unwrap_or_default(),
         };
 
         Ok(user)
@@ -0,0 +1,23 @@
+use std::{
+    io,
+    process::{Command, Stdio},
+};
+
+pub fn open_url(url: &str) -> io::Result<()> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("open");
+        cmd.arg(url);
+        cmd
+    } else if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", "", url]);
+        cmd
+    } else {
+        let mut cmd = Command::new("xdg-open");
+        cmd.arg(url);
+        cmd
+    };
+
+    cmd.stdout(Stdio::null()).stderr(Stdio::null()).spawn()?;
+    Ok(())
+}
@@ -1,9 +1,19 @@
 use bahamut::api::{
-    board::BoardPost,
-    post::{Post, PostComment},
+    anime::AnimeHome,
+    board::{BoardKeyword, BoardPost, ModerationEntry},
+    creation::{CreationArticle, CreationItem},
+    news::{NewsArticle, NewsHeadline},
+    post::{Attachment, Post, PostComment},
+    preview::LinkPreview,
     search::SearchResult,
 };
 
+use std::{path::PathBuf, sync::Arc};
+
+use image::DynamicImage;
+
+use crate::config::{Config, SummaryConfig};
+
 pub struct PageData<T> {
     pub page: u16,
     pub max: u16,
@@ -13,14 +23,83 @@ pub struct PageData<T> {
 pub enum FetchDataMsg {
     SearchResult(Vec<SearchResult>),
     BoardPage(PageData<Vec<BoardPost>>),
+    BoardKeywords(Vec<BoardKeyword>),
     PostPage(PageData<Post>),
     CommentPage(Vec<PostComment>),
+    FloorComment(String, Vec<PostComment>),
+    // summary of the thread or the error
+    Summary(Result<String, String>),
+    // attachment name, floor, saved path and hash of the file or the error
+    Download(String, u16, Result<(PathBuf, u64), String>),
+    // thread url, first floor snippet
+    ThreadPreview(String, String),
+    // link, empty when failed
+    LinkPreview(String, LinkPreview),
+    // image link, hash of the bytes and the decoded image or the error
+    Image(String, Result<(u64, Arc<DynamicImage>), String>),
+    // user id, shrunk avatar or the error
+    Avatar(String, Result<Arc<DynamicImage>, String>),
+    // titles, translated in the same order or the error
+    Translation(Vec<String>, Result<Vec<String>, String>),
+    // saved search key, post urls of the first result page
+    SavedSearch(String, Vec<String>),
+    AnimeHome(AnimeHome),
+    NewsList(Vec<NewsHeadline>),
+    NewsArticle(Option<NewsArticle>),
+    CreationList(Vec<CreationItem>),
+    CreationArticle(Option<CreationArticle>),
+    Activity(Vec<CreationItem>),
+    ModerationLog(Vec<ModerationEntry>),
+    // board name and thread of every subscribed board, whether it's a refresh
+    Timeline(Vec<(String, BoardPost)>, bool),
+    // tag of the newer release
+    Update(Option<String>),
+    // the page asked for the 18+ confirmation, with the request to retry
+    AgeGate(DataRequestMsg),
+    // config.toml changed on disk, the error when it failed to parse
+    Config(Result<Box<Config>, String>),
 }
 
 pub enum DataRequestMsg {
     SearchResult(String),
     BoardPage(String, u16, bool),
+    // board id, query, page, use cache
+    BoardSearch(String, String, u16, bool),
+    // board id, pages, limit
+    BoardKeywords(String, u16, usize),
     PostPage(String, u16, bool),
     CommentPage(String, String),
+    FloorComment(String, String),
+    // fetched aside, not blocking the other requests
+    LinkPreview(String),
+    // image of the viewer, fetched aside too
+    Image(String),
+    // user id, avatar url
+    Avatar(String, String),
+    // thread text for the summary command or endpoint
+    Summary(SummaryConfig, String),
+    // list titles for the translation backend
+    Translation(SummaryConfig, Vec<String>),
+    // attachment, download directory, floor
+    Download(Attachment, PathBuf, u16),
+    // thread url, max length
+    ThreadPreview(String, usize),
+    // board id, query, for the new result count
+    SavedSearch(String, String),
+    // linked continuation threads, each fetched as one thread by the first url
+    ThreadSeries(Vec<Vec<String>>),
+    AnimeHome,
+    NewsList,
+    NewsArticle(String),
+    CreationList(String),
+    CreationArticle(String),
+    // owner, pages
+    Activity(String, u16),
+    // board id
+    ModerationLog(String),
+    // board id, thread id, pages to look through
+    ParentBoard(String, String, u16),
+    // board id and name of the subscribed boards, whether it's a refresh in the background
+    Timeline(Vec<(String, String)>, bool),
     End,
 }
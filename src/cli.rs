@@ -0,0 +1,151 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use clap_mangen::{
+    roff::{bold, roman, Roff},
+    Man,
+};
+
+use crate::{cmd::PageRange, ui::keymap::KEYBINDINGS};
+
+#[derive(Parser)]
+#[command(name = "bahamut", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// 唯讀模式，停用所有會變更資料的操作
+    #[arg(long, global = true)]
+    pub read_only: bool,
+    /// 低記憶體模式，不預先讀取、不處理圖片，離開的頁面不保留在記憶體中
+    #[arg(long, global = true)]
+    pub low_memory: bool,
+    /// 使用另一組設定與資料，例如分開工作與個人的帳號、書籤與主題
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// 產生 shell 自動補全腳本
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// 產生 man page
+    Man,
+    /// 列出看板的貼文
+    Board {
+        /// 看板 id (bsn)
+        bsn: String,
+        /// 頁數範圍，例如 3 或 1..3
+        #[arg(long, default_value = "1")]
+        pages: PageRange,
+        /// 以 JSON 格式輸出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 將看板前幾頁的所有貼文完整封存到本地，中斷後重新執行會從上次的進度繼續
+    ArchiveBoard {
+        /// 看板 id (bsn)
+        bsn: String,
+        /// 要封存的看板頁數
+        #[arg(long, default_value_t = 1)]
+        pages: u16,
+        /// 一併封存每一樓的留言，存成每頁的 <頁數>.comments.json
+        #[arg(long)]
+        comments: bool,
+    },
+    /// 不開啟 TUI，定時檢查貼文與看板是否有更新，未指定 --thread/--board 時使用已訂閱的項目
+    Watch {
+        /// 貼文網址，可重複指定
+        #[arg(long = "thread")]
+        threads: Vec<String>,
+        /// 看板 id (bsn)，可重複指定
+        #[arg(long = "board")]
+        boards: Vec<String>,
+        /// 檢查間隔秒數
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+        /// 發送桌面通知
+        #[arg(long)]
+        notify: bool,
+    },
+    /// 以 TUI 相同的畫面將貼文頁面輸出成純文字或 ANSI 彩色文字，可用於分享片段或比對畫面
+    Render {
+        /// 貼文網址
+        url: String,
+        /// 頁數
+        #[arg(long, default_value_t = 1)]
+        page: u16,
+        /// 顯示的樓層，預設為頁面的第一樓
+        #[arg(long)]
+        floor: Option<u16>,
+        /// 畫面寬度
+        #[arg(long, default_value_t = 80)]
+        width: u16,
+        /// 畫面高度
+        #[arg(long, default_value_t = 24)]
+        height: u16,
+        /// 輸出 ANSI 顏色與樣式
+        #[arg(long)]
+        ansi: bool,
+    },
+    /// 儲存瀏覽器中的登入 Cookie，優先使用系統鑰匙圈，否則以密碼加密存檔
+    Login,
+    /// 清除儲存的登入資訊
+    Logout,
+    /// 將設定、閱讀紀錄、訂閱等資料匯出成單一檔案
+    ExportData {
+        /// 匯出的檔案路徑
+        file: PathBuf,
+    },
+    /// 從 export-data 匯出的檔案匯入資料，會覆蓋現有資料
+    ImportData {
+        /// 匯入的檔案路徑
+        file: PathBuf,
+    },
+    /// 顯示最近幾個版本的更新內容
+    Changelog {
+        /// 顯示的版本數量
+        #[arg(long, default_value_t = 5)]
+        count: u8,
+    },
+}
+
+pub fn print_completions(shell: Shell) {
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, name, &mut io::stdout());
+}
+
+pub fn print_man() -> io::Result<()> {
+    let man = Man::new(Cli::command());
+    let mut out = io::stdout();
+
+    man.render_title(&mut out)?;
+    man.render_name_section(&mut out)?;
+    man.render_synopsis_section(&mut out)?;
+    man.render_description_section(&mut out)?;
+    man.render_options_section(&mut out)?;
+    man.render_subcommands_section(&mut out)?;
+
+    let mut roff = Roff::default();
+    roff.control("SH", ["KEYBINDINGS"]);
+    for page in KEYBINDINGS {
+        roff.control("SS", [tr!(page.page)]);
+        for key in page.keys {
+            roff.control("TP", []);
+            roff.text([bold(tr!(key.keys))]);
+            roff.text([roman(tr!(key.desc))]);
+        }
+    }
+    roff.to_writer(&mut out)?;
+
+    man.render_version_section(&mut out)?;
+    man.render_authors_section(&mut out)?;
+    out.flush()
+}
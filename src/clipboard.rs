@@ -0,0 +1,142 @@
+use std::{
+    env,
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+use bahamut::api::post::PostPageUrlParameter;
+use url::Url;
+
+static BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - i * 8));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - i * 6)) as usize & 0x3f] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// tools putting stdin to the clipboard of this machine
+fn copy_commands() -> &'static [&'static [&'static str]] {
+    if cfg!(target_os = "macos") {
+        &[&["pbcopy"]]
+    } else if cfg!(windows) {
+        &[&["clip"]]
+    } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+        &[&["wl-copy"]]
+    } else {
+        &[&["xclip", "-selection", "clipboard"], &["xsel", "-b", "-i"]]
+    }
+}
+
+fn copy_with(args: &[&str], data: &[u8]) -> bool {
+    let child = Command::new(args[0])
+        .args(&args[1..])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(_) => return false,
+    };
+
+    let written = child
+        .stdin
+        .take()
+        .is_some_and(|mut stdin| stdin.write_all(data).is_ok());
+    child.wait().is_ok_and(|status| status.success()) && written
+}
+
+// clip reads the console code page, unless the text is utf-16 with a bom
+fn clip_data(text: &str) -> Vec<u8> {
+    if cfg!(windows) {
+        [0xFF, 0xFE]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect()
+    } else {
+        text.as_bytes().to_vec()
+    }
+}
+
+// the system tools also work in terminals without OSC 52 like conhost, over ssh they'd
+// copy on the remote side, so there and without any tool the terminal is asked with OSC 52
+pub fn copy(text: &str) -> io::Result<()> {
+    let remote = env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some();
+    if !remote {
+        let data = clip_data(text);
+        if copy_commands().iter().any(|args| copy_with(args, &data)) {
+            return Ok(());
+        }
+    }
+
+    let mut out = io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    out.flush()
+}
+
+// OSC 52 can be read back only by a few terminals, so the system tools are asked
+pub fn paste() -> Option<String> {
+    let commands: &[&[&str]] = if cfg!(target_os = "macos") {
+        &[&["pbpaste"]]
+    } else if cfg!(windows) {
+        &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard"]]
+    } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+        &[&["wl-paste", "--no-newline"]]
+    } else {
+        &[
+            &["xclip", "-selection", "clipboard", "-o"],
+            &["xsel", "-b", "-o"],
+        ]
+    };
+
+    commands.iter().find_map(|args| {
+        let output = Command::new(args[0])
+            .args(&args[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    })
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CopiedLink {
+    Thread(String),
+    // board id
+    Board(String),
+}
+
+// a thread (C.php) or board (B.php) url of the forum in the clipboard
+pub fn copied_link() -> Option<CopiedLink> {
+    let text = paste()?;
+    let url = Url::parse(text.split_whitespace().next()?).ok()?;
+    if !url.host_str()?.ends_with("gamer.com.tw") {
+        return None;
+    }
+
+    let param = PostPageUrlParameter::try_from(url.clone()).ok()?;
+    match url.path() {
+        "/C.php" if !param.board_id.is_empty() && !param.id.is_empty() => {
+            Some(CopiedLink::Thread(url.to_string()))
+        }
+        "/B.php" if !param.board_id.is_empty() => Some(CopiedLink::Board(param.board_id)),
+        _ => None,
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bahamut::api::{
+    board::BoardPage,
+    client::BahamutClient,
+    post::{CommentReadable, Post, PostComment, PostPage, PostPageUrlParameter},
+    CachedPage,
+};
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::data_dir, store::write_atomic};
+
+use super::{CmdError, ErrorKind};
+
+static INDEX_FILE: &str = "index.json";
+static ZSTD_LEVEL: i32 = 3;
+
+#[derive(Default, Serialize, Deserialize)]
+struct ArchivedThread {
+    title: String,
+    pages: u16,
+    // floor count from the board list when archived
+    floors: u16,
+    done: bool,
+}
+
+// progress of a board, kept to resume an interrupted archive
+#[derive(Default, Serialize, Deserialize)]
+struct ArchiveIndex {
+    threads: HashMap<String, ArchivedThread>,
+}
+
+fn archive_dir(bsn: &str) -> Result<PathBuf, Box<dyn Error>> {
+    // names a directory, so no separator or drive of any platform
+    if bsn.is_empty() || !bsn.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("invalid board id: {}", bsn).into());
+    }
+    let dir = data_dir().ok_or("can't find data directory")?;
+    Ok(dir.join("archive").join(bsn))
+}
+
+fn load_index(dir: &Path) -> ArchiveIndex {
+    fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(s.as_ref()).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(dir: &Path, index: &ArchiveIndex) -> Result<(), Box<dyn Error>> {
+    write_atomic(&dir.join(INDEX_FILE), serde_json::to_string(index)?)?;
+    Ok(())
+}
+
+fn write_page(path: PathBuf, html: &str) -> Result<(), Box<dyn Error>> {
+    fs::write(path, zstd::encode_all(html.as_bytes(), ZSTD_LEVEL)?)?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct FloorComments {
+    floor: u16,
+    id: String,
+    url: String,
+    comments: Vec<PostComment>,
+}
+
+// comments of every floor on the page, nested under the floor
+fn write_comments(
+    client: &BahamutClient,
+    dir: &Path,
+    bsn: &str,
+    page: u16,
+    html: &str,
+) -> Result<(), Box<dyn Error>> {
+    let document = Html::parse_document(html);
+    let floors = Post::posts(&document.root_element())
+        .iter()
+        .map(|content| {
+            let comments = content.comment(client, bsn).map_err(|_| {
+                CmdError::fetch(client, format!("comments of floor {}", content.floor))
+            })?;
+            Ok(FloorComments {
+                floor: content.floor,
+                id: content.id.to_owned(),
+                url: content.permalink(bsn),
+                comments,
+            })
+        })
+        .collect::<Result<Vec<FloorComments>, CmdError>>()?;
+
+    let path = dir.join(format!("{}.comments.json", page));
+    write_atomic(&path, serde_json::to_string(&floors)?)?;
+    Ok(())
+}
+
+// archive every page of a thread, pages already archived are skipped except the last one
+fn archive_thread(
+    client: &Arc<BahamutClient>,
+    dir: PathBuf,
+    url: &str,
+    from: u16,
+    comments: bool,
+) -> Result<u16, Box<dyn Error>> {
+    let param = PostPageUrlParameter::try_from(url.to_string())?;
+    let bsn = param.board_id.to_owned();
+    let mut post = PostPage::from_param(param, Arc::clone(client));
+    let first = post
+        .fetch_page_text(1)
+        .ok_or_else(|| CmdError::fetch(client, format!("{} page 1", url)))?;
+
+    fs::create_dir_all(&dir)?;
+    write_page(dir.join("1.html.zst"), first.as_ref())?;
+    if comments {
+        write_comments(client, &dir, &bsn, 1, first.as_ref())?;
+    }
+    post.first_page(Html::parse_document(first.as_ref()));
+
+    let max = post.max.max(1);
+    for page in from.max(2)..=max {
+        let html = post
+            .fetch_page_text(page)
+            .ok_or_else(|| CmdError::fetch(client, format!("{} page {}", url, page)))?;
+        write_page(dir.join(format!("{}.html.zst", page)), html.as_ref())?;
+        if comments {
+            write_comments(client, &dir, &bsn, page, html.as_ref())?;
+        }
+    }
+
+    Ok(max)
+}
+
+pub fn run(
+    client: Arc<BahamutClient>,
+    bsn: &str,
+    pages: u16,
+    comments: bool,
+) -> Result<(), Box<dyn Error>> {
+    let dir = archive_dir(bsn)?;
+    fs::create_dir_all(&dir)?;
+    let mut index = load_index(&dir);
+
+    let mut board = BoardPage::new(bsn, Arc::clone(&client));
+    board.init();
+    let pages = if board.max == 0 {
+        pages
+    } else {
+        pages.min(board.max)
+    };
+
+    // the kind of the last failure decides the exit code
+    let mut failed = 0;
+    let mut kind = ErrorKind::Other;
+    for page in 1..=pages {
+        let posts = match board.get(page, false) {
+            Some(board) => board.post(),
+            None => {
+                let err = CmdError::fetch(&client, format!("board {} page {}", bsn, page));
+                eprintln!("[{}/{}] {}", page, pages, tr!("無法讀取看板頁面"));
+                err.report();
+                kind = err.kind;
+                failed += 1;
+                continue;
+            }
+        };
+
+        for (i, post) in posts.iter().enumerate() {
+            let prefix = format!("[{}/{}] ({}/{})", page, pages, i + 1, posts.len());
+            let archived = index.threads.entry(post.id.to_owned()).or_default();
+
+            // finished threads are archived again only when having new floors
+            if archived.done && post.floor == archived.floors {
+                println!("{} {}", prefix, tr!("略過 {title}", title = post.title));
+                continue;
+            }
+
+            // resume from the last archived page, it may have new floors
+            let from = archived.pages.max(1);
+            match archive_thread(
+                &client,
+                dir.join(&post.id),
+                post.url.as_ref(),
+                from,
+                comments,
+            ) {
+                Ok(max) => {
+                    println!(
+                        "{} {}",
+                        prefix,
+                        tr!(
+                            "已封存 {title} ({pages} 頁)",
+                            title = post.title,
+                            pages = max
+                        )
+                    );
+                    archived.title = post.title.to_owned();
+                    archived.pages = max;
+                    archived.floors = post.floor;
+                    archived.done = true;
+                }
+                Err(e) => {
+                    let err = CmdError::from_error(e.as_ref());
+                    err.report();
+                    kind = err.kind;
+                    eprintln!(
+                        "{} {}",
+                        prefix,
+                        tr!("封存失敗 {title}: {error}", title = post.title, error = e)
+                    );
+                    archived.done = false;
+                    failed += 1;
+                }
+            }
+
+            save_index(&dir, &index)?;
+        }
+    }
+
+    println!("{}", tr!("封存完成，存放於 {dir}", dir = dir.display()));
+    if failed > 0 {
+        let message = tr!("{count} 篇貼文封存失敗，重新執行可繼續封存", count = failed);
+        return Err(CmdError { kind, message }.into());
+    }
+
+    Ok(())
+}
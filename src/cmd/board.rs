@@ -0,0 +1,56 @@
+use std::{error::Error, sync::Arc};
+
+use bahamut::api::{
+    board::{Board, BoardPage, BoardPostRef},
+    client::BahamutClient,
+    CachedPage,
+};
+
+use super::{CmdError, ErrorKind, PageRange};
+
+pub fn run(
+    client: Arc<BahamutClient>,
+    bsn: &str,
+    pages: PageRange,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut board = BoardPage::new(bsn, Arc::clone(&client));
+    board.init();
+
+    let mut boards: Vec<Board> = vec![];
+    for page in pages
+        .0
+        .take_while(|page| board.max == 0 || *page <= board.max)
+    {
+        match board.get(page, false) {
+            Some(b) => boards.push(b),
+            // pages past the end of a board without page buttons are not an error
+            None => {
+                let err = CmdError::fetch(&client, format!("board {} page {}", bsn, page));
+                if boards.is_empty() || !matches!(err.kind, ErrorKind::Parse) {
+                    return Err(err.into());
+                }
+                break;
+            }
+        }
+    }
+
+    // printed once, no need to own the text
+    let posts = boards
+        .iter()
+        .flat_map(|board| board.post_refs())
+        .collect::<Vec<BoardPostRef>>();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&posts)?);
+    } else {
+        posts.iter().for_each(|post| {
+            println!(
+                "[{:>4}GP {:>4}回覆] {} {}",
+                post.gp, post.reply, post.title, post.url
+            )
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::{collections::BTreeMap, error::Error, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{data_dir, Config},
+    store::write_atomic,
+};
+
+static ARCHIVE_VERSION: u16 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct DataArchive {
+    version: u16,
+    config: Option<String>,
+    data: BTreeMap<String, String>,
+}
+
+pub fn export(file: &Path) -> Result<(), Box<dyn Error>> {
+    let config = Config::path().and_then(|path| fs::read_to_string(path).ok());
+
+    let mut data = BTreeMap::new();
+    if let Some(dir) = data_dir().filter(|dir| dir.is_dir()) {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|n| n.to_str()).map(String::from);
+            if let Some(name) = name.filter(|n| n.ends_with(".json")) {
+                data.insert(name, fs::read_to_string(&path)?);
+            }
+        }
+    }
+
+    let archive = DataArchive {
+        version: ARCHIVE_VERSION,
+        config,
+        data,
+    };
+
+    fs::write(file, serde_json::to_string_pretty(&archive)?)?;
+    println!(
+        "exported {} data files to {}",
+        archive.data.len(),
+        file.display()
+    );
+    Ok(())
+}
+
+pub fn import(file: &Path) -> Result<(), Box<dyn Error>> {
+    let archive: DataArchive = serde_json::from_str(fs::read_to_string(file)?.as_ref())?;
+    if archive.version > ARCHIVE_VERSION {
+        return Err(format!("unsupported archive version {}", archive.version).into());
+    }
+
+    if let (Some(config), Some(path)) = (archive.config, Config::path()) {
+        toml::from_str::<Config>(config.as_ref())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        write_atomic(&path, config)?;
+        println!("imported {}", path.display());
+    }
+
+    let dir = data_dir().ok_or("can't find data directory")?;
+    fs::create_dir_all(&dir)?;
+    for (name, content) in archive.data {
+        // ignore anything that isn't a plain file name, with the separators of every
+        // platform since the archive may come from another one
+        if name.contains(['/', '\\', ':'])
+            || Path::new(&name).file_name().and_then(|n| n.to_str()) != Some(name.as_str())
+        {
+            continue;
+        }
+
+        let path = dir.join(&name);
+        write_atomic(&path, content)?;
+        println!("imported {}", path.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use std::{error::Error, fmt, io, ops::RangeInclusive, process, str::FromStr};
+
+use bahamut::api::client::BahamutClient;
+use serde_json::json;
+
+pub mod archive;
+pub mod board;
+pub mod data;
+pub mod render;
+pub mod session;
+pub mod watch;
+
+#[derive(Clone)]
+pub struct PageRange(pub RangeInclusive<u16>);
+
+impl FromStr for PageRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |v: &str| {
+            v.trim()
+                .parse::<u16>()
+                .ok()
+                .filter(|v| *v > 0)
+                .ok_or(format!("invalid page: {}", v))
+        };
+
+        let range = match s.split_once("..") {
+            Some((start, end)) => {
+                let end = end.strip_prefix('=').unwrap_or(end);
+                parse(start)?..=parse(end)?
+            }
+            None => parse(s)?..=parse(s)?,
+        };
+
+        if range.is_empty() {
+            Err(format!("invalid page range: {}", s))
+        } else {
+            Ok(PageRange(range))
+        }
+    }
+}
+
+// failure types of the batch commands, the exit code and the `error` field on stderr
+#[derive(Clone, Copy, Debug)]
+pub enum ErrorKind {
+    Other,
+    Network,
+    Parse,
+    Auth,
+    Io,
+}
+
+impl ErrorKind {
+    // 2 is taken by clap for invalid arguments
+    pub fn code(&self) -> i32 {
+        match self {
+            ErrorKind::Other => 1,
+            ErrorKind::Network => 3,
+            ErrorKind::Parse => 4,
+            ErrorKind::Auth => 5,
+            ErrorKind::Io => 6,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::Other => "other",
+            ErrorKind::Network => "network",
+            ErrorKind::Parse => "parse",
+            ErrorKind::Auth => "auth",
+            ErrorKind::Io => "io",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CmdError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl CmdError {
+    // after a page came back empty: the request failed, the age gate was shown, or the html didn't parse
+    pub fn fetch(client: &BahamutClient, what: String) -> CmdError {
+        let (kind, message) = if let Some(e) = client.take_network_error() {
+            (ErrorKind::Network, format!("{}: {}", what, e))
+        } else if client.take_gated() {
+            (
+                ErrorKind::Auth,
+                format!("{}: 18+ confirmation required", what),
+            )
+        } else {
+            (ErrorKind::Parse, what)
+        };
+
+        CmdError { kind, message }
+    }
+
+    // a plain error of a command, io errors keep their type
+    pub fn from_error(err: &(dyn Error + 'static)) -> CmdError {
+        let kind = if let Some(e) = err.downcast_ref::<CmdError>() {
+            e.kind
+        } else if err.is::<io::Error>() {
+            ErrorKind::Io
+        } else {
+            ErrorKind::Other
+        };
+
+        CmdError {
+            kind,
+            message: err.to_string(),
+        }
+    }
+
+    // one json line on stderr, also used by watch for errors it keeps running after
+    pub fn report(&self) {
+        let line = json!({ "error": self.kind.name(), "message": self.message });
+        eprintln!("{}", line);
+    }
+}
+
+impl fmt::Display for CmdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for CmdError {}
+
+// end a batch command with its error on stderr and the matching exit code
+pub fn exit(res: Result<(), Box<dyn Error>>) -> ! {
+    match res {
+        Ok(()) => process::exit(0),
+        Err(err) => {
+            let err = CmdError::from_error(err.as_ref());
+            err.report();
+            process::exit(err.kind.code())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(s: &str) -> Result<RangeInclusive<u16>, String> {
+        s.parse::<PageRange>().map(|r| r.0)
+    }
+
+    #[test]
+    fn single_page() {
+        assert_eq!(range("3"), Ok(3..=3));
+        assert_eq!(range(" 3 "), Ok(3..=3));
+    }
+
+    #[test]
+    fn page_ranges() {
+        assert_eq!(range("2..5"), Ok(2..=5));
+        assert_eq!(range("2..=5"), Ok(2..=5));
+        assert_eq!(range("4..4"), Ok(4..=4));
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        assert!(range("0").is_err());
+        assert!(range("5..2").is_err());
+        assert!(range("a..3").is_err());
+        assert!(range("2..").is_err());
+        assert!(range("").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    error::Error,
+    io::{self, Write},
+    sync::Arc,
+};
+
+use bahamut::api::{
+    client::BahamutClient,
+    post::{PostPage, PostPageUrlParameter},
+    CachedPage,
+};
+use crossterm::{
+    queue,
+    style::{Attribute, Color as CColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+};
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    style::{Color, Modifier},
+    text::Span,
+    Terminal,
+};
+
+use crate::{
+    config::Config,
+    store::ReadPosition,
+    ui::{
+        self,
+        state::{AppState, Page},
+    },
+};
+
+use super::CmdError;
+
+static ATTRIBUTES: &[(Modifier, Attribute)] = &[
+    (Modifier::BOLD, Attribute::Bold),
+    (Modifier::DIM, Attribute::Dim),
+    (Modifier::ITALIC, Attribute::Italic),
+    (Modifier::UNDERLINED, Attribute::Underlined),
+    (Modifier::REVERSED, Attribute::Reverse),
+    (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+];
+
+pub struct RenderOptions {
+    pub page: u16,
+    pub floor: Option<u16>,
+    pub width: u16,
+    pub height: u16,
+    pub ansi: bool,
+}
+
+// draw a thread page with the same widgets as the TUI into a buffer of a fixed size
+pub fn run(
+    client: Arc<BahamutClient>,
+    url: &str,
+    config: Config,
+    options: RenderOptions,
+) -> Result<(), Box<dyn Error>> {
+    let param = PostPageUrlParameter::try_from(url.to_string())
+        .map_err(|_| format!("invalid thread url: {}", url))?;
+    let mut post_page = PostPage::from_param(param, Arc::clone(&client));
+    post_page.init();
+
+    let mut post = post_page
+        .get_and_cache(options.page, false)
+        .filter(|post| !post.posts.is_empty())
+        .ok_or_else(|| CmdError::fetch(&client, format!("thread {} page {}", url, options.page)))?;
+    post_page.fill_starter(&mut post);
+
+    let mut app = AppState::new(config, client);
+    // nothing is sent to a terminal, so no image protocol, and colors only when asked
+    app.graphics = false;
+    app.color = options.ansi;
+    app.post.url = url.to_string();
+    app.post.data(options.page, post);
+    app.post.index(0);
+    app.post.last_page(post_page.max);
+    app.post.restore = options.floor.map(|floor| ReadPosition {
+        page: options.page,
+        floor,
+        offset: 0,
+    });
+    app.post.apply_restore();
+    app.page = Page::Post;
+
+    let mut terminal = Terminal::new(TestBackend::new(options.width, options.height))?;
+    terminal.draw(|f| ui::ui(f, &mut app))?;
+
+    let buffer = terminal.backend().buffer();
+    let mut out = io::stdout().lock();
+    if options.ansi {
+        write_ansi(&mut out, buffer)?;
+    } else {
+        write_plain(&mut out, buffer)?;
+    }
+    out.flush()?;
+
+    Ok(())
+}
+
+// symbols of a row, the cells covered by a wide char before them are skipped
+fn row_cells(buffer: &Buffer, y: u16) -> Vec<&ratatui::buffer::Cell> {
+    let mut cells = vec![];
+    let mut skip = 0;
+    for x in buffer.area.left()..buffer.area.right() {
+        let cell = buffer.get(x, y);
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+        skip = Span::raw(cell.symbol.as_str()).width().saturating_sub(1);
+        cells.push(cell);
+    }
+    cells
+}
+
+fn write_plain(out: &mut impl Write, buffer: &Buffer) -> io::Result<()> {
+    for y in buffer.area.top()..buffer.area.bottom() {
+        let line: String = row_cells(buffer, y)
+            .iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect();
+        writeln!(out, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+fn write_ansi(out: &mut impl Write, buffer: &Buffer) -> io::Result<()> {
+    for y in buffer.area.top()..buffer.area.bottom() {
+        let mut style = None;
+        for cell in row_cells(buffer, y) {
+            let current = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(current) {
+                queue!(out, SetAttribute(Attribute::Reset))?;
+                for (modifier, attribute) in ATTRIBUTES {
+                    if cell.modifier.contains(*modifier) {
+                        queue!(out, SetAttribute(*attribute))?;
+                    }
+                }
+                if cell.fg != Color::Reset {
+                    queue!(out, SetForegroundColor(CColor::from(cell.fg)))?;
+                }
+                if cell.bg != Color::Reset {
+                    queue!(out, SetBackgroundColor(CColor::from(cell.bg)))?;
+                }
+                style = Some(current);
+            }
+            write!(out, "{}", cell.symbol)?;
+        }
+        queue!(out, SetAttribute(Attribute::Reset))?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
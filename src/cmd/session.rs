@@ -0,0 +1,41 @@
+use std::error::Error;
+
+use bahamut::api::client::BahamutClient;
+
+use crate::credential::{self, CredentialBackend};
+
+pub fn login() -> Result<(), Box<dyn Error>> {
+    let cookie = rpassword::prompt_password(tr!(
+        "請貼上瀏覽器中 gamer.com.tw 的 Cookie (例如 BAHARUNE=...; BAHAENUR=...): "
+    ))?;
+    let cookie = cookie.trim();
+    if cookie.is_empty() {
+        return Err("cookie is empty".into());
+    }
+
+    match credential::save(cookie)? {
+        CredentialBackend::Keyring => println!("{}", tr!("登入資訊已儲存到系統鑰匙圈")),
+        CredentialBackend::File(path) => println!(
+            "{}",
+            tr!("登入資訊已加密儲存到 {path}", path = path.display())
+        ),
+    }
+    Ok(())
+}
+
+pub fn logout() -> Result<(), Box<dyn Error>> {
+    credential::clear()?;
+    println!("{}", tr!("已清除登入資訊"));
+    Ok(())
+}
+
+// load saved session before any request is made, fallback to guest on failure
+pub fn restore(client: &BahamutClient) {
+    match credential::load() {
+        Ok(cookie) => client.set_cookie(cookie),
+        Err(e) => eprintln!(
+            "{}",
+            tr!("無法讀取登入資訊，以訪客身份繼續: {error}", error = e)
+        ),
+    }
+}
@@ -0,0 +1,91 @@
+use std::{error::Error, sync::Arc, thread, time::Duration};
+
+use bahamut::api::{self, client::BahamutClient};
+
+use crate::{
+    config::Config,
+    hook,
+    notify::desktop_notify,
+    store::{MuteStore, Store},
+    subscription::{BoardSubscription, Subscription, SubscriptionEvent, SubscriptionStore},
+    webhook,
+};
+
+use super::{CmdError, ErrorKind};
+
+pub fn run(
+    client: Arc<BahamutClient>,
+    threads: Vec<String>,
+    boards: Vec<String>,
+    interval: u64,
+    notify: bool,
+) -> Result<(), Box<dyn Error>> {
+    let config = Config::load()?;
+    let mut watched = SubscriptionStore::default();
+    threads.iter().for_each(|url| {
+        watched
+            .threads
+            .insert(url.to_owned(), Subscription::new(url));
+    });
+    boards.iter().for_each(|id| {
+        watched
+            .boards
+            .insert(id.to_owned(), BoardSubscription::new(id, ""));
+    });
+    let use_store = threads.is_empty() && boards.is_empty();
+
+    loop {
+        let events: Vec<SubscriptionEvent> = if use_store {
+            // reload every round so subscriptions changed in TUI are picked up,
+            // polled without the lock and merged, the TUI may change it during the poll
+            let before = SubscriptionStore::load();
+            let mut polled = before.clone();
+            let events = api::background(|| polled.poll(&client));
+            SubscriptionStore::update(|store| store.merge_polled(&before, &polled))?;
+            events
+        } else {
+            api::background(|| watched.poll(&client))
+        };
+
+        // failed polls are retried next round
+        if let Some(message) = client.take_network_error() {
+            let kind = ErrorKind::Network;
+            CmdError { kind, message }.report();
+        }
+
+        let muted = MuteStore::load();
+        let events = events.into_iter().filter(|event| match event {
+            SubscriptionEvent::NewFloor { url, .. }
+            | SubscriptionEvent::FloorEdited { url, .. } => !muted.is_muted(url),
+            SubscriptionEvent::NewThread { .. } => true,
+        });
+
+        for event in events {
+            println!("[{}] {} {}", event.title(), event.message(), event.url());
+
+            if notify {
+                desktop_notify(event.title(), event.message().as_ref()).unwrap_or(());
+            }
+
+            if let Err(e) = hook::run(&config.hooks, &event) {
+                let message = format!("hook error: {}", e);
+                CmdError {
+                    kind: ErrorKind::Io,
+                    message,
+                }
+                .report();
+            }
+
+            if let Err(e) = webhook::send(&config.webhook, &event) {
+                let message = format!("webhook error: {}", e);
+                CmdError {
+                    kind: ErrorKind::Network,
+                    message,
+                }
+                .report();
+            }
+        }
+
+        thread::sleep(Duration::from_secs(interval.max(1)));
+    }
+}
@@ -0,0 +1,798 @@
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fs,
+    path::PathBuf,
+    sync::{Mutex, RwLock},
+    time::Duration,
+};
+
+use bahamut::api::Timeouts;
+use lazy_static::lazy_static;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::terminal::ColorMode;
+
+static APP_NAME: &str = "bahamut";
+
+lazy_static! {
+    // --profile, config and data of a profile are under profiles/<name> of the default directories
+    static ref PROFILE: RwLock<Option<String>> = RwLock::new(None);
+}
+
+pub fn set_profile(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("invalid profile name: {}", name));
+    }
+
+    if let Ok(mut p) = PROFILE.write() {
+        *p = Some(name.to_string());
+    }
+    Ok(())
+}
+
+pub fn profile() -> Option<String> {
+    PROFILE.read().ok()?.clone()
+}
+
+fn in_profile(dir: PathBuf) -> PathBuf {
+    match profile() {
+        Some(name) => dir.join("profiles").join(name),
+        None => dir,
+    }
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("BAHAMUT_CONFIG_DIR") {
+        return Some(in_profile(PathBuf::from(dir)));
+    }
+
+    portable_dir()
+        .or_else(|| dirs::config_dir().map(|dir| dir.join(APP_NAME)))
+        .map(in_profile)
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("BAHAMUT_DATA_DIR") {
+        return Some(in_profile(PathBuf::from(dir)));
+    }
+
+    portable_dir()
+        .map(|dir| dir.join("data"))
+        .or_else(|| dirs::data_dir().map(|dir| dir.join(APP_NAME)))
+        .map(in_profile)
+}
+
+// portable install: config.toml placed next to the executable
+fn portable_dir() -> Option<PathBuf> {
+    let dir = env::current_exe().ok()?.parent()?.to_path_buf();
+    if cfg!(windows) && dir.join("config.toml").is_file() {
+        Some(dir)
+    } else {
+        None
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub tts: TtsConfig,
+    pub summary: SummaryConfig,
+    pub translate: TranslateConfig,
+    pub layout: LayoutConfig,
+    pub confirm: ConfirmConfig,
+    pub terminal: TerminalConfig,
+    pub webhook: WebhookConfig,
+    pub hooks: HookConfig,
+    pub cache: CacheConfig,
+    pub keyword: KeywordConfig,
+    pub list_preview: ListPreviewConfig,
+    pub header: HeaderConfig,
+    pub session: SessionConfig,
+    pub navigation: NavigationConfig,
+    pub filter: FloorFilterConfig,
+    pub activity: ActivityConfig,
+    pub timeline: TimelineConfig,
+    pub update: UpdateConfig,
+    pub locale: LocaleConfig,
+    pub preview: PreviewConfig,
+    pub theme: ThemeConfig,
+    pub network: NetworkConfig,
+    pub download: DownloadConfig,
+    pub reply: ReplyConfig,
+    // selector key -> css
+    pub selectors: HashMap<String, String>,
+    // key -> action name
+    pub keymap: HashMap<String, String>,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("config.toml"))
+    }
+
+    pub fn load() -> Result<Config, Box<dyn Error>> {
+        let path = match Config::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Config::default()),
+        };
+
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(content.as_ref()).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    // call `changed` with the new config each time config.toml is saved, until the watcher is dropped.
+    // the directory is watched since editors may replace the file instead of writing it
+    pub fn watch<F>(changed: F) -> Option<RecommendedWatcher>
+    where
+        F: Fn(Result<Config, String>) + Send + 'static,
+    {
+        let path = Config::path()?;
+        let dir = path.parent()?.to_path_buf();
+        // editors write several events for one save, only a different content is reloaded
+        let last = Mutex::new(fs::read_to_string(&path).ok());
+
+        let file = path.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let event = match res {
+                Ok(event) => event,
+                Err(_) => return,
+            };
+            let modified = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+            if !modified
+                || !event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name() == file.file_name())
+            {
+                return;
+            }
+
+            let content = fs::read_to_string(&file).ok();
+            match last.lock() {
+                Ok(mut last) if *last != content => *last = content,
+                _ => return,
+            }
+            changed(Config::load().map_err(|e| e.to_string()));
+        })
+        .ok()?;
+
+        watcher.watch(&dir, RecursiveMode::NonRecursive).ok()?;
+        Some(watcher)
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct TtsConfig {
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl Default for TtsConfig {
+    fn default() -> Self {
+        let (command, args): (&str, Vec<&str>) = if cfg!(target_os = "macos") {
+            ("say", vec![])
+        } else if cfg!(target_os = "windows") {
+            (
+                "powershell",
+                vec![
+                    "-NoProfile",
+                    "-Command",
+                    "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+                ],
+            )
+        } else {
+            ("espeak", vec!["--stdin"])
+        };
+
+        TtsConfig {
+            command: command.to_string(),
+            args: args.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    pub dual_pane_width: u16,
+    pub footer: bool,
+    // numbers of the content blocks in post page
+    pub line_numbers: bool,
+    // redraws are capped to this rate
+    pub max_fps: u32,
+    // avatar of the floor author, an initial badge without the graphics protocol
+    pub avatars: bool,
+    // the newest comments of the floor under its content, when there's no comment pane
+    pub inline_comments: bool,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        LayoutConfig {
+            dual_pane_width: 160,
+            footer: true,
+            line_numbers: false,
+            max_fps: 30,
+            avatars: false,
+            inline_comments: false,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ConfirmConfig {
+    pub quit: bool,
+    pub clear_cache: bool,
+}
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        ConfirmConfig {
+            quit: false,
+            clear_cache: true,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct TerminalConfig {
+    pub color: ColorMode,
+    // clickable links, auto / always / never like color
+    pub hyperlinks: ColorMode,
+    // image viewer of the post page, auto / always / never like color
+    pub graphics: ColorMode,
+    // low resolution half block images when there's no graphics protocol, like over ssh
+    pub mosaic: bool,
+    // emoji and glyphs outside the basic plane are drawn as the placeholder
+    pub replace_glyphs: bool,
+    pub placeholder: String,
+    // glyph -> columns this terminal draws it in, replaced too when the layout counts another width
+    pub glyph_widths: HashMap<String, u16>,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        TerminalConfig {
+            color: ColorMode::default(),
+            hyperlinks: ColorMode::default(),
+            graphics: ColorMode::default(),
+            mosaic: false,
+            replace_glyphs: false,
+            placeholder: "?".to_string(),
+            glyph_widths: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    #[default]
+    Discord,
+    Slack,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub format: WebhookFormat,
+    pub new_floor: String,
+    pub new_thread: String,
+    pub floor_edited: String,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        WebhookConfig {
+            url: String::new(),
+            format: WebhookFormat::default(),
+            new_floor: "{title} 有 {count} 則新回覆 {url}".to_string(),
+            new_thread: "[{board}] 新貼文 {title} {url}".to_string(),
+            floor_edited: "{title} 的 {floors} 樓被編輯 {url}".to_string(),
+        }
+    }
+}
+
+// terminal bell and command run on an event, args can use the webhook placeholders
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EventHook {
+    pub bell: bool,
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HookConfig {
+    pub new_floor: EventHook,
+    pub new_thread: EventHook,
+    pub floor_edited: EventHook,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    pub max_size_mb: u64,
+    pub ttl_minutes: u64,
+    pub prune_days: u64,
+    // no prefetch or images and only the open page kept in memory, for routers and SBCs
+    pub low_memory: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            enabled: true,
+            max_size_mb: 100,
+            ttl_minutes: 30,
+            prune_days: 7,
+            low_memory: false,
+        }
+    }
+}
+
+// cap of the disk cache in the low memory mode, its index is kept in memory
+static LOW_MEMORY_CACHE_MB: u64 = 10;
+
+impl CacheConfig {
+    pub fn max_size(&self) -> u64 {
+        let mb = if self.low_memory {
+            self.max_size_mb.min(LOW_MEMORY_CACHE_MB)
+        } else {
+            self.max_size_mb
+        };
+        mb * 1024 * 1024
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct KeywordConfig {
+    pub pages: u16,
+    pub limit: usize,
+}
+
+impl Default for KeywordConfig {
+    fn default() -> Self {
+        KeywordConfig {
+            pages: 3,
+            limit: 30,
+        }
+    }
+}
+
+// first floor snippet under each thread of the board list, one request per thread
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ListPreviewConfig {
+    pub enabled: bool,
+    // chars kept of the first floor
+    pub max_length: usize,
+}
+
+impl Default for ListPreviewConfig {
+    fn default() -> Self {
+        ListPreviewConfig {
+            enabled: false,
+            max_length: 80,
+        }
+    }
+}
+
+// collapse floors like 推 or +1 in post page
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct FloorFilterConfig {
+    pub enabled: bool,
+    // floors with fewer chars are hidden
+    pub min_chars: usize,
+    // floors with nothing else than these are hidden
+    pub phrases: Vec<String>,
+    // system messages and notices inside threads, even when the filter is disabled
+    pub hide_notices: bool,
+    // tombstones of deleted threads in board lists
+    pub hide_deleted: bool,
+}
+
+impl Default for FloorFilterConfig {
+    fn default() -> Self {
+        FloorFilterConfig {
+            enabled: false,
+            min_chars: 5,
+            phrases: ["推", "+1", "朝聖", "卡位", "先推"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            hide_notices: false,
+            hide_deleted: false,
+        }
+    }
+}
+
+impl FloorFilterConfig {
+    // the first floor is never hidden
+    pub fn is_low_content(&self, floor: u16, desc: &[String]) -> bool {
+        if !self.enabled || floor <= 1 {
+            return false;
+        }
+
+        let text: String = desc
+            .iter()
+            .flat_map(|line| line.chars())
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        if text.chars().count() < self.min_chars {
+            return true;
+        }
+
+        let rest = self
+            .phrases
+            .iter()
+            .filter(|p| !p.is_empty())
+            .fold(text, |text, phrase| text.replace(phrase.as_str(), ""));
+        !rest.chars().any(char::is_alphanumeric)
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct UpdateConfig {
+    // check the github releases on startup
+    pub check: bool,
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        UpdateConfig { check: true }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    // pages opened by a key
+    pub timeout_secs: u64,
+    // previews, comment prefetch and the watch polling
+    pub background_timeout_secs: u64,
+    pub background_retries: u32,
+    // least milliseconds between two requests, to go easy on the forum
+    pub request_interval_ms: u64,
+    // no images, videos or previews, for metered connections
+    pub data_saver: bool,
+    // probe the forum and the saved session before the first screen
+    pub startup_check: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            timeout_secs: 5,
+            background_timeout_secs: 20,
+            background_retries: 1,
+            request_interval_ms: 0,
+            data_saver: false,
+            startup_check: false,
+        }
+    }
+}
+
+impl NetworkConfig {
+    // pages are parsed without images and videos
+    pub fn text_only(&self, cache: &CacheConfig) -> bool {
+        self.data_saver || cache.low_memory
+    }
+
+    pub fn request_interval(&self) -> Duration {
+        Duration::from_millis(self.request_interval_ms)
+    }
+
+    pub fn timeouts(&self) -> Timeouts {
+        Timeouts {
+            foreground: Duration::from_secs(self.timeout_secs.max(1)),
+            background: Duration::from_secs(self.background_timeout_secs.max(1)),
+            background_retries: self.background_retries,
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PreviewConfig {
+    // domains of the links to fetch a preview card for, empty to disable
+    pub domains: Vec<String>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct SummaryConfig {
+    // reads the thread on stdin and prints the summary, used before the endpoint
+    pub command: String,
+    pub args: Vec<String>,
+    // OpenAI compatible api, like https://api.openai.com/v1
+    pub endpoint: String,
+    pub model: String,
+    // name of the environment variable with the api key, so it's not in the config
+    pub api_key_env: String,
+    pub prompt: String,
+    // the first floor and the latest ones that fit
+    pub max_chars: usize,
+    pub timeout_secs: u64,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        SummaryConfig {
+            command: String::new(),
+            args: vec![],
+            endpoint: String::new(),
+            model: "gpt-4o-mini".to_string(),
+            api_key_env: "OPENAI_API_KEY".to_string(),
+            prompt: "以繁體中文條列這篇討論串的重點、結論與主要的不同意見".to_string(),
+            max_chars: 20000,
+            timeout_secs: 120,
+        }
+    }
+}
+
+impl SummaryConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.command.is_empty() || !self.endpoint.is_empty()
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct TranslateConfig {
+    // language the titles are translated into
+    pub language: String,
+    // reads the titles one a line on stdin and prints them translated the same way
+    pub command: String,
+    pub args: Vec<String>,
+    // {language} is replaced, the endpoint, model and key are the ones of [summary]
+    pub prompt: String,
+}
+
+impl Default for TranslateConfig {
+    fn default() -> Self {
+        TranslateConfig {
+            language: "English".to_string(),
+            command: String::new(),
+            args: vec![],
+            prompt: "Translate each line into {language}. Reply with only the translations, one a line in the same order".to_string(),
+        }
+    }
+}
+
+impl TranslateConfig {
+    // the summary backend with the command and prompt of the translation,
+    // None when neither a command nor an endpoint is set
+    pub fn backend(&self, summary: &SummaryConfig) -> Option<SummaryConfig> {
+        let config = SummaryConfig {
+            command: self.command.to_owned(),
+            args: self.args.to_owned(),
+            prompt: self.prompt.replace("{language}", &self.language),
+            ..summary.clone()
+        };
+        config.is_enabled().then_some(config)
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DownloadConfig {
+    // where attachments are saved, empty for the system download directory
+    pub dir: String,
+}
+
+impl DownloadConfig {
+    pub fn dir(&self) -> Option<PathBuf> {
+        if self.dir.is_empty() {
+            dirs::download_dir().or_else(dirs::home_dir)
+        } else {
+            Some(expand_home(&self.dir))
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ReplyConfig {
+    // picked on the post page, {author}, {id}, {floor} and {title} are the ones of the current floor
+    pub templates: Vec<String>,
+    // open the reply page of the thread after the template is copied
+    pub open_browser: bool,
+}
+
+// `~` from config.toml, and from cmd.exe or powershell that leave it to the program
+pub fn expand_home(path: &str) -> PathBuf {
+    let rest = path
+        .strip_prefix('~')
+        .filter(|rest| rest.is_empty() || rest.starts_with(['/', '\\']));
+    match (rest, dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches(['/', '\\'])),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct LocaleConfig {
+    // "auto" follows LANG, or one of zh-TW, en
+    pub language: String,
+}
+
+impl Default for LocaleConfig {
+    fn default() -> Self {
+        LocaleConfig {
+            language: "auto".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ActivityConfig {
+    // pages of the 創作 list counted in the heatmap
+    pub pages: u16,
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        ActivityConfig { pages: 5 }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct TimelineConfig {
+    // seconds between refreshes while it's open, 0 to only refresh with r
+    pub refresh_secs: u64,
+}
+
+impl Default for TimelineConfig {
+    fn default() -> Self {
+        TimelineConfig { refresh_secs: 300 }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    // disable every action that changes data, even when logged in
+    pub read_only: bool,
+    // skip the prompt of 18+ boards and threads
+    pub age_confirmed: bool,
+    // sent with every request after the age was confirmed
+    pub age_gate_cookie: String,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig {
+            read_only: false,
+            age_confirmed: false,
+            age_gate_cookie: "ckFORUM_R18=1".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NavigationConfig {
+    // Tab jumps to the next subscription with unread content
+    pub follow_unread: bool,
+    // offer to open a thread or board url in the clipboard on launch
+    pub open_copied: bool,
+    // list the updates of the subscriptions found by watch on launch
+    pub digest: bool,
+    // load the next board page when scrolling to the bottom of the list
+    pub infinite_scroll: bool,
+    // threads with unread replies first in board lists, toggled by u
+    pub unread_first: bool,
+}
+
+// segments of the thread header in post page
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct HeaderConfig {
+    pub board: bool,
+    pub author: bool,
+    pub date: bool,
+    pub reply: bool,
+    pub page: bool,
+    pub subscription: bool,
+    pub tags: bool,
+}
+
+impl Default for HeaderConfig {
+    fn default() -> Self {
+        HeaderConfig {
+            board: true,
+            author: true,
+            date: true,
+            reply: true,
+            page: true,
+            subscription: true,
+            tags: true,
+        }
+    }
+}
+
+// colors of the floor header by author role, empty to disable
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub starter: String,
+    pub moderator: String,
+    pub me: String,
+    pub followed: String,
+    // system messages and notices inside threads
+    pub notice: String,
+    // title prefix -> color, the others pick one by the name
+    pub categories: HashMap<String, String>,
+    // the other posters of a thread pick one by the user id, empty to disable
+    pub posters: Vec<String>,
+}
+
+impl ThemeConfig {
+    // the same user has the same color in every thread
+    pub fn poster_color(&self, user_id: &str) -> Option<Color> {
+        if self.posters.is_empty() || user_id.is_empty() {
+            return None;
+        }
+
+        let hash = user_id.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+            (h ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+        self.posters[(hash % self.posters.len() as u64) as usize]
+            .parse()
+            .ok()
+    }
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        ThemeConfig {
+            starter: "yellow".to_string(),
+            moderator: "green".to_string(),
+            me: "cyan".to_string(),
+            followed: "magenta".to_string(),
+            notice: "darkgray".to_string(),
+            categories: [
+                ("情報", "cyan"),
+                ("問題", "yellow"),
+                ("心得", "green"),
+                ("討論", "magenta"),
+                ("閒聊", "gray"),
+            ]
+            .into_iter()
+            .map(|(name, color)| (name.to_string(), color.to_string()))
+            .collect(),
+            posters: [
+                "lightred",
+                "lightgreen",
+                "lightyellow",
+                "lightblue",
+                "lightmagenta",
+                "lightcyan",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+        }
+    }
+}
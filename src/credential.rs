@@ -0,0 +1,127 @@
+use std::{
+    error::Error,
+    fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    thread,
+};
+
+use argon2::Argon2;
+use bahamut::api::file::write_atomic;
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+
+use crate::config::{data_dir, profile};
+
+static SERVICE: &str = "bahamut";
+static USER: &str = "session";
+static SALT_LEN: usize = 16;
+static NONCE_LEN: usize = 24;
+
+pub enum CredentialBackend {
+    Keyring,
+    File(PathBuf),
+}
+
+// each profile logs in separately
+fn user() -> String {
+    profile().map_or(USER.to_string(), |name| format!("{}@{}", USER, name))
+}
+
+fn file_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("credential.enc"))
+}
+
+// keyring backends may drive their own async runtime, keep them off the tokio threads
+fn with_keyring<T: Send + 'static>(
+    f: impl FnOnce(keyring::Entry) -> keyring::Result<T> + Send + 'static,
+) -> keyring::Result<T> {
+    thread::spawn(move || f(keyring::Entry::new(SERVICE, user().as_ref())?))
+        .join()
+        .unwrap_or(Err(keyring::Error::NoEntry))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], Box<dyn Error>> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+fn encrypt(passphrase: &str, plain: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, &salt)?.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let encrypted = cipher
+        .encrypt(&nonce, plain.as_bytes())
+        .map_err(|_| "encrypt credential fail")?;
+
+    Ok([salt.as_ref(), nonce.as_ref(), encrypted.as_ref()].concat())
+}
+
+fn decrypt(passphrase: &str, data: &[u8]) -> Result<String, Box<dyn Error>> {
+    if data.len() < SALT_LEN + NONCE_LEN {
+        return Err("credential file is corrupted".into());
+    }
+
+    let (salt, rest) = data.split_at(SALT_LEN);
+    let (nonce, encrypted) = rest.split_at(NONCE_LEN);
+    let cipher = XChaCha20Poly1305::new(&derive_key(passphrase, salt)?.into());
+    let plain = cipher
+        .decrypt(XNonce::from_slice(nonce), encrypted)
+        .map_err(|_| "wrong passphrase")?;
+
+    Ok(String::from_utf8(plain)?)
+}
+
+pub fn save(cookie: &str) -> Result<CredentialBackend, Box<dyn Error>> {
+    let value = cookie.to_string();
+    if with_keyring(move |entry| entry.set_password(value.as_ref())).is_ok() {
+        return Ok(CredentialBackend::Keyring);
+    }
+
+    let passphrase = rpassword::prompt_password(tr!("系統鑰匙圈無法使用，請設定加密用的密碼: "))?;
+    let confirm = rpassword::prompt_password(tr!("再次輸入密碼: "))?;
+    if passphrase.is_empty() || passphrase != confirm {
+        return Err("passphrase mismatch".into());
+    }
+
+    let path = file_path().ok_or("can't find data directory")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    write_atomic(&path, encrypt(passphrase.as_ref(), cookie)?)?;
+    Ok(CredentialBackend::File(path))
+}
+
+pub fn load() -> Result<Option<String>, Box<dyn Error>> {
+    if let Ok(cookie) = with_keyring(|entry| entry.get_password()) {
+        return Ok(Some(cookie));
+    }
+
+    let data = match file_path().and_then(|path| fs::read(path).ok()) {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+
+    // batch commands from cron or a pipe would wait on the prompt forever
+    if !io::stdin().is_terminal() {
+        return Err(tr!("沒有終端機可以輸入登入資訊的密碼").into());
+    }
+
+    let passphrase = rpassword::prompt_password(tr!("請輸入登入資訊的密碼: "))?;
+    decrypt(passphrase.as_ref(), data.as_ref()).map(Some)
+}
+
+pub fn clear() -> Result<(), Box<dyn Error>> {
+    with_keyring(|entry| entry.delete_credential()).unwrap_or(());
+    if let Some(path) = file_path().filter(|path| path.exists()) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum DiffKind {
+    Same,
+    Added,
+    Removed,
+}
+
+// upper bound of the lcs table, larger text is compared line by line
+static MAX_CELLS: usize = 4_000_000;
+
+// ascii words are kept together, other characters like CJK are one word each
+fn words(text: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start: Option<usize> = None;
+
+    for (i, c) in text.char_indices() {
+        if c.is_ascii_alphanumeric() {
+            start.get_or_insert(i);
+            continue;
+        }
+
+        if let Some(s) = start.take() {
+            words.push(&text[s..i]);
+        }
+        words.push(&text[i..i + c.len_utf8()]);
+    }
+
+    if let Some(s) = start {
+        words.push(&text[s..]);
+    }
+
+    words
+}
+
+fn lcs_diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffKind, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+    let mut table = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[at(i, j)] = if old[i] == new[j] {
+                table[at(i + 1, j + 1)] + 1
+            } else {
+                table[at(i + 1, j)].max(table[at(i, j + 1)])
+            };
+        }
+    }
+
+    let mut result = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            result.push((DiffKind::Same, old[i]));
+            i += 1;
+            j += 1;
+        } else if table[at(i + 1, j)] >= table[at(i, j + 1)] {
+            result.push((DiffKind::Removed, old[i]));
+            i += 1;
+        } else {
+            result.push((DiffKind::Added, new[j]));
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|w| (DiffKind::Removed, *w)));
+    result.extend(new[j..].iter().map(|w| (DiffKind::Added, *w)));
+    result
+}
+
+// word level diff of two texts, as lines of (kind, text) segments
+pub fn word_diff(old: &str, new: &str) -> Vec<Vec<(DiffKind, String)>> {
+    let (old_words, new_words) = (words(old), words(new));
+    let diff = if (old_words.len() + 1) * (new_words.len() + 1) <= MAX_CELLS {
+        lcs_diff(&old_words, &new_words)
+    } else {
+        let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+        lcs_diff(&old_lines, &new_lines)
+    };
+
+    let mut lines: Vec<Vec<(DiffKind, String)>> = vec![vec![]];
+    for (kind, text) in diff {
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(vec![]);
+            }
+            if part.is_empty() {
+                continue;
+            }
+
+            let line = lines.last_mut().unwrap();
+            match line.last_mut() {
+                Some((k, s)) if *k == kind => s.push_str(part),
+                _ => line.push((kind, part.to_string())),
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &[(DiffKind, String)], kind: DiffKind) -> String {
+        line.iter()
+            .filter(|(k, _)| *k == kind)
+            .map(|(_, s)| s.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn words_keep_ascii_together() {
+        assert_eq!(
+            words("abc 中文 x1"),
+            vec!["abc", " ", "中", "文", " ", "x1"]
+        );
+    }
+
+    #[test]
+    fn same_text() {
+        let lines = word_diff("hello world", "hello world");
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].iter().all(|(k, _)| *k == DiffKind::Same));
+        assert_eq!(text(&lines[0], DiffKind::Same), "hello world");
+    }
+
+    #[test]
+    fn changed_word() {
+        let lines = word_diff("hello world", "hello there");
+        assert_eq!(text(&lines[0], DiffKind::Same), "hello ");
+        assert_eq!(text(&lines[0], DiffKind::Removed), "world");
+        assert_eq!(text(&lines[0], DiffKind::Added), "there");
+    }
+
+    #[test]
+    fn cjk_by_char() {
+        let lines = word_diff("今天天氣好", "今天天氣差");
+        assert_eq!(text(&lines[0], DiffKind::Same), "今天天氣");
+        assert_eq!(text(&lines[0], DiffKind::Removed), "好");
+        assert_eq!(text(&lines[0], DiffKind::Added), "差");
+    }
+
+    #[test]
+    fn split_into_lines() {
+        let lines = word_diff("a\nb", "a\nb\nc");
+        assert_eq!(lines.len(), 3);
+        assert_eq!(text(&lines[0], DiffKind::Same), "a");
+        assert_eq!(text(&lines[2], DiffKind::Added), "c");
+    }
+}
@@ -0,0 +1,91 @@
+use std::ops::Range;
+
+use bahamut::api::post::PostContent;
+use regex::{Regex, RegexBuilder};
+
+// search in the loaded floors, qualifiers filter the floors and the text is highlighted
+//   author:<id or name>  floor:>N  floor:<N  floor:>=N  floor:<=N  floor:N
+pub struct FindQuery {
+    pub source: String,
+    pattern: Option<Regex>,
+    author: Option<String>,
+    floors: Range<u16>,
+}
+
+impl FindQuery {
+    pub fn parse(source: &str, regex: bool, case_sensitive: bool) -> Result<FindQuery, String> {
+        let mut author = None;
+        let mut floors = 0..u16::MAX;
+        let mut words = vec![];
+
+        for word in source.split_whitespace() {
+            if let Some(name) = word.strip_prefix("author:") {
+                author = Some(name.to_lowercase());
+            } else if let Some(floor) = word.strip_prefix("floor:") {
+                let range =
+                    floor_range(floor).ok_or(tr!("樓層條件格式錯誤: {floor}", floor = floor))?;
+                floors = floors.start.max(range.start)..floors.end.min(range.end);
+            } else {
+                words.push(word);
+            }
+        }
+
+        let text = words.join(" ");
+        let pattern = if text.is_empty() {
+            None
+        } else {
+            let text = if regex { text } else { regex::escape(&text) };
+            let pattern = RegexBuilder::new(&text)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .map_err(|e| tr!("正規表示式錯誤: {error}", error = e))?;
+            Some(pattern)
+        };
+
+        Ok(FindQuery {
+            source: source.to_string(),
+            pattern,
+            author,
+            floors,
+        })
+    }
+
+    pub fn matches(&self, post: &PostContent) -> bool {
+        let author = self.author.as_ref().is_none_or(|author| {
+            post.user.id.to_lowercase() == *author || post.user.name.to_lowercase().contains(author)
+        });
+        let text = self
+            .pattern
+            .as_ref()
+            .is_none_or(|p| post.desc.iter().any(|block| p.is_match(block)));
+
+        author && text && self.floors.contains(&post.floor)
+    }
+
+    // byte ranges of the text to highlight
+    pub fn ranges(&self, text: &str) -> Vec<Range<usize>> {
+        self.pattern.as_ref().map_or(vec![], |p| {
+            p.find_iter(text)
+                .map(|m| m.range())
+                .filter(|r| !r.is_empty())
+                .collect()
+        })
+    }
+}
+
+fn floor_range(floor: &str) -> Option<Range<u16>> {
+    let (op, number) = match floor.find(|c: char| c.is_ascii_digit()) {
+        Some(i) => floor.split_at(i),
+        None => return None,
+    };
+    let n: u16 = number.parse().ok()?;
+
+    match op {
+        "" | "=" => Some(n..n.saturating_add(1)),
+        ">" => Some(n.saturating_add(1)..u16::MAX),
+        ">=" => Some(n..u16::MAX),
+        "<" => Some(0..n),
+        "<=" => Some(0..n.saturating_add(1)),
+        _ => None,
+    }
+}
@@ -0,0 +1,40 @@
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+use crate::{
+    config::{EventHook, HookConfig},
+    subscription::SubscriptionEvent,
+    template::fill_template,
+};
+
+// ring the terminal bell and run the command configured for the event
+pub fn run(config: &HookConfig, event: &SubscriptionEvent) -> io::Result<()> {
+    let hook = match event {
+        SubscriptionEvent::NewFloor { .. } => &config.new_floor,
+        SubscriptionEvent::NewThread { .. } => &config.new_thread,
+        SubscriptionEvent::FloorEdited { .. } => &config.floor_edited,
+    };
+
+    trigger(hook, &event.fields())
+}
+
+fn trigger(hook: &EventHook, fields: &[(&str, String)]) -> io::Result<()> {
+    if hook.bell {
+        let mut stdout = io::stdout();
+        stdout.write_all(b"\x07")?;
+        stdout.flush()?;
+    }
+
+    if !hook.command.is_empty() {
+        Command::new(&hook.command)
+            .args(hook.args.iter().map(|arg| fill_template(arg, fields)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+    }
+
+    Ok(())
+}
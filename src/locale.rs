@@ -0,0 +1,68 @@
+use std::{collections::HashMap, env, sync::OnceLock};
+
+use lazy_static::lazy_static;
+
+use crate::template::fill_template;
+
+// the strings in code are zh-TW, a locale maps them to the translation
+static LOCALE_FILES: &[(&str, &str)] = &[
+    ("zh-TW", include_str!("../locales/zh-TW.toml")),
+    ("en", include_str!("../locales/en.toml")),
+];
+
+static DEFAULT_LOCALE: &str = "zh-TW";
+
+lazy_static! {
+    static ref LOCALES: HashMap<&'static str, HashMap<String, String>> = LOCALE_FILES
+        .iter()
+        .map(|(name, content)| (*name, toml::from_str(content).unwrap_or_default()))
+        .collect();
+}
+
+static ACTIVE: OnceLock<&'static str> = OnceLock::new();
+
+// LC_ALL, LC_MESSAGES then LANG, like gettext
+fn from_env() -> &'static str {
+    let lang = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|key| env::var(key).ok().filter(|v| !v.is_empty()))
+        .unwrap_or_default();
+
+    if lang.starts_with("en") {
+        "en"
+    } else {
+        DEFAULT_LOCALE
+    }
+}
+
+// "auto" or a shipped locale name, only the first call takes effect
+pub fn init(language: &str) {
+    let name = LOCALE_FILES
+        .iter()
+        .map(|(name, _)| *name)
+        .find(|name| name.eq_ignore_ascii_case(language))
+        .unwrap_or_else(from_env);
+    ACTIVE.set(name).unwrap_or(());
+}
+
+pub fn tr(text: &'static str) -> &'static str {
+    let name = ACTIVE.get().copied().unwrap_or(DEFAULT_LOCALE);
+    LOCALES
+        .get(name)
+        .and_then(|locale| locale.get(text))
+        .map_or(text, String::as_str)
+}
+
+pub fn trf(text: &'static str, fields: &[(&str, String)]) -> String {
+    fill_template(tr(text), fields)
+}
+
+// tr!("text") or tr!("text {name}", name = value)
+macro_rules! tr {
+    ($text:expr) => {
+        $crate::locale::tr($text)
+    };
+    ($text:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::locale::trf($text, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
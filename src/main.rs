@@ -2,42 +2,202 @@ extern crate lazy_static;
 
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{hash_map::Entry, HashMap, HashSet},
     error::Error,
     io,
-    sync::mpsc::{channel, Receiver, Sender},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc,
+    },
     thread::{self, JoinHandle},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use bahamut::api::{
-    board::BoardPage,
-    post::{Post, PostComment, PostPage, PostPageUrlParameter},
+    self,
+    anime::AnimeHome,
+    board::{trending_keywords, Board, BoardPage, ModerationLog},
+    cache::Cache,
+    client::BahamutClient,
+    creation::CreationArticle,
+    hash::bytes_hash,
+    health::{self, Health},
+    metrics,
+    news::{News, NewsArticle},
+    post::{CommentReadable, PostContent, PostPage, PostPageUrlParameter, PostSeries},
+    preview::{self, LinkPreview},
     search::BoardSearch,
+    selector,
+    user::User,
     CachedPage,
 };
 use channel::{DataRequestMsg, FetchDataMsg, PageData};
+use clap::Parser;
+use cli::{Cli, Command};
+use clipboard::CopiedLink;
+use config::Config;
 use crossterm::{
     event::{self, DisableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use image::DynamicImage;
 use ratatui::{
     backend::{Backend, CrosstermBackend},
     Terminal,
 };
 use tokio::runtime::Builder;
 use ui::{
-    key::handle_key,
+    confirm::{Confirm, ConfirmAction},
+    graphics,
+    hyperlink::{self, Hyperlink},
+    key::{board_request, handle_key, KeyBindEvent, PARENT_BOARD_PAGES},
     state::{AppState, ListStateInit, Page},
     ui,
 };
 
+#[macro_use]
+mod locale;
+
+mod browser;
 mod channel;
+mod cli;
+mod clipboard;
+mod cmd;
+mod config;
+mod credential;
+mod diff;
+mod find;
+mod hook;
+mod notify;
+mod settings;
+mod store;
+mod subscription;
+mod summary;
+mod template;
+mod terminal;
+mod tts;
 mod ui;
+mod update;
+mod webhook;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    if let Some(name) = cli.profile.as_ref() {
+        config::set_profile(name)?;
+    }
+    // before the commands so their output is translated too
+    let language = Config::load().map_or("auto".to_string(), |c| c.locale.language);
+    locale::init(&language);
+
+    match cli.command {
+        Some(Command::Completions { shell }) => {
+            cli::print_completions(shell);
+            return Ok(());
+        }
+        Some(Command::Man) => {
+            cli::print_man()?;
+            return Ok(());
+        }
+        Some(Command::Login) => return cmd::session::login(),
+        Some(Command::Logout) => return cmd::session::logout(),
+        Some(Command::ExportData { file }) => return cmd::data::export(&file),
+        Some(Command::ImportData { file }) => return cmd::data::import(&file),
+        Some(Command::Changelog { count }) => return update::print_changelog(count).await,
+        _ => (),
+    }
+
+    // every request of the commands and the TUI goes through it
+    let client = BahamutClient::default();
+    cmd::session::restore(&client);
+    let mut config = Config::load()?;
+    config.session.read_only |= cli.read_only;
+    config.cache.low_memory |= cli.low_memory;
+    if config.session.age_confirmed {
+        client.confirm_age(&config.session.age_gate_cookie);
+    }
+    for (key, css) in config.selectors.iter() {
+        selector::set_override(key, css)?;
+    }
+    client.set_timeouts(config.network.timeouts());
+    client.set_interval(config.network.request_interval());
+    client.set_text_only(config.network.text_only(&config.cache));
+
+    match cli.command {
+        Some(Command::Board { bsn, pages, json }) => {
+            cmd::exit(cmd::board::run(Arc::new(client), bsn.as_ref(), pages, json));
+        }
+        Some(Command::ArchiveBoard {
+            bsn,
+            pages,
+            comments,
+        }) => {
+            cmd::exit(cmd::archive::run(
+                Arc::new(client),
+                bsn.as_ref(),
+                pages,
+                comments,
+            ));
+        }
+        Some(Command::Watch {
+            threads,
+            boards,
+            interval,
+            notify,
+        }) => {
+            cmd::exit(cmd::watch::run(
+                Arc::new(client),
+                threads,
+                boards,
+                interval,
+                notify,
+            ));
+        }
+        Some(Command::Render {
+            url,
+            page,
+            floor,
+            width,
+            height,
+            ansi,
+        }) => {
+            let options = cmd::render::RenderOptions {
+                page,
+                floor,
+                width,
+                height,
+                ansi,
+            };
+            cmd::exit(cmd::render::run(
+                Arc::new(client),
+                url.as_ref(),
+                config,
+                options,
+            ));
+        }
+        _ => (),
+    }
+
+    let cache = config::data_dir()
+        .filter(|_| config.cache.enabled)
+        .map(|dir| {
+            Cache::open(
+                dir.join("cache"),
+                config.cache.max_size(),
+                Duration::from_secs(config.cache.ttl_minutes * 60),
+            )
+        });
+    let client = Arc::new(match cache {
+        Some(cache) => client.with_cache(cache),
+        None => client,
+    });
+    let health = if config.network.startup_check {
+        println!("{}", tr!("檢查連線中..."));
+        Some(health::check(&client).await)
+    } else {
+        None
+    };
     let (tx_req, rx_req) = channel::<DataRequestMsg>();
     let (tx_rev, rx_rev) = channel::<FetchDataMsg>();
 
@@ -48,16 +208,86 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    // checked aside so the first page isn't blocked by github
+    let checking_update = config.update.check;
+    if checking_update {
+        let tx = tx_rev.clone();
+        tokio::spawn(async move {
+            let tag = update::check().await.map(|release| release.tag_name);
+            tx.send(FetchDataMsg::Update(tag)).unwrap_or(());
+        });
+    }
+
+    // theme and filter changes in config.toml are applied without restarting
+    let reload = tx_rev.clone();
+    let watcher = Config::watch(move |config| {
+        reload
+            .send(FetchDataMsg::Config(config.map(Box::new)))
+            .unwrap_or(());
+    });
+
     // fetch thread
-    let fetcher = run_fetcher(tx_rev, rx_req);
+    let fetcher = run_fetcher(tx_rev, rx_req, config.cache.low_memory, Arc::clone(&client));
+
+    // a second TUI only reads, the data is written by the first one
+    let instance = store::lock_instance();
+    let shared = instance
+        .as_ref()
+        .is_err_and(|e| e.kind() == io::ErrorKind::WouldBlock);
+    config.session.read_only |= shared;
 
     // ui thread
-    let app = AppState::new();
-    let res = run_app(&mut terminal, app, tx_req.clone(), rx_rev);
+    let mut app = AppState::new(config, Arc::clone(&client));
+    app.checking_update = checking_update;
+    let config = app.config.clone();
+    app.apply_keymap(&config);
+    if shared {
+        app.status(tr!("另一個 bahamut 正在執行，以唯讀模式開啟").to_string());
+    }
+    if app.config.navigation.digest {
+        app.open_digest();
+    }
+    if app.config.navigation.open_copied {
+        let message = match clipboard::copied_link() {
+            Some(link @ CopiedLink::Thread(_)) => Some((link, tr!("開啟剪貼簿中的貼文嗎?"))),
+            Some(link @ CopiedLink::Board(_)) => Some((link, tr!("開啟剪貼簿中的看板嗎?"))),
+            None => None,
+        };
+        if let Some((link, message)) = message {
+            app.confirm = Some(Confirm::new(ConfirmAction::OpenCopied(link), message));
+        }
+    }
+    match (health, client.cache()) {
+        (Some(Health::Unreachable(e)), Some(cache)) => {
+            cache.set_offline(true);
+            app.status(tr!(
+                "無法連線到巴哈姆特 ({error})，以離線模式開啟，只能瀏覽快取中的頁面",
+                error = e
+            ));
+        }
+        (Some(Health::Unreachable(e)), None) => app.status(tr!(
+            "無法連線到巴哈姆特 ({error})，開啟 cache.enabled 可在離線時瀏覽快取中的頁面",
+            error = e
+        )),
+        (Some(Health::SessionExpired), _) => {
+            app.status(tr!("登入已過期，請重新執行 bahamut login").to_string())
+        }
+        _ => (),
+    }
+    tx_req
+        .send(DataRequestMsg::ThreadSeries(app.thread_link.series.clone()))
+        .unwrap_or(());
+    let res = run_app(&mut terminal, &mut app, tx_req.clone(), rx_rev);
+    drop(watcher);
+    app.save_read_position();
 
     // close fetch thread
     tx_req.send(DataRequestMsg::End).unwrap_or(());
     fetcher.join().unwrap_or(());
+    if let Some(cache) = client.cache() {
+        cache.flush();
+    }
+    drop(instance);
 
     // restore terminal
     disable_raw_mode()?;
@@ -75,55 +305,300 @@ async fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+// true to quit, suspending and the subshell hand the terminal over until they return
+fn after_key<B: Backend>(terminal: &mut Terminal<B>, event: KeyBindEvent) -> io::Result<bool> {
+    let outside: fn() = match event {
+        KeyBindEvent::None => return Ok(false),
+        KeyBindEvent::Quit => return Ok(true),
+        KeyBindEvent::Suspend => terminal::suspend,
+        KeyBindEvent::Shell => || {
+            terminal::shell().ok();
+        },
+    };
+
+    terminal::release()?;
+    outside();
+    terminal::resume()?;
+    // the screen is changed outside, so nothing drawn before can be diffed against
+    terminal.clear()?;
+    Ok(false)
+}
+
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
-    mut app: AppState,
+    app: &mut AppState,
     tx: Sender<DataRequestMsg>,
     rx: Receiver<FetchDataMsg>,
 ) -> io::Result<()> {
+    let frame = Duration::from_secs(1) / app.config.layout.max_fps.max(1);
+    let mut last_draw: Option<Instant> = None;
+    let mut dirty = true;
+    // image drawn over the viewer, replaced only when the view changed
+    let mut shown = None;
+    let mut shown_avatar = None;
+
     loop {
-        terminal.draw(|f| ui(f, &mut app))?;
+        // only redrawn after a change, at most once a frame
+        let wait = last_draw.map_or(Duration::ZERO, |t| frame.saturating_sub(t.elapsed()));
+        if dirty && wait.is_zero() {
+            let frame = terminal.draw(|f| ui(f, app))?;
+            if app.hyperlinks {
+                let mut links = page_links(app);
+                links.extend(hyperlink::find_urls(frame.buffer));
+                hyperlink::write(&mut io::stdout(), frame.buffer, &links)?;
+            }
+            if app.graphics {
+                graphics::sync(&mut io::stdout(), app, &mut shown)?;
+                graphics::sync_avatar(&mut io::stdout(), app, &mut shown_avatar)?;
+            }
+            last_draw = Some(Instant::now());
+            dirty = false;
+        }
+        request_floor_comment(app, &tx);
+        request_link_preview(app, &tx);
+        request_image(app, &tx);
+        request_avatar(app, &tx);
+        request_thread_preview(app, &tx);
+        request_more_threads(app, &tx);
+        request_saved_search(app, &tx);
+        request_timeline(app, &tx);
+        request_translation(app, &tx);
 
-        let poll_sec: f32 = if app.loading { 0.1 } else { 1000.0 };
-        if let Ok(true) = event::poll(Duration::from_secs_f32(poll_sec)) {
-            if let Event::Key(event) = event::read()? {
-                if handle_key(&mut app, event, tx.clone()).is_quit() {
+        if !app.loading {
+            if let Some(event) = app.macros.queue.pop_front() {
+                if after_key(terminal, handle_key(app, event, tx.clone()))? {
+                    return Ok(());
+                }
+                dirty = true;
+                continue;
+            }
+        }
+
+        // the metrics overlay changes with the requests in flight
+        dirty |= app.show_metrics && app.is_waiting();
+        let timeout = if dirty {
+            wait
+        } else if app.is_waiting() {
+            Duration::from_millis(100)
+        } else {
+            Duration::from_secs(1000)
+        };
+        // woken up for the next refresh of the timeline
+        let timeout = match app.timeline_refresh_in() {
+            Some(left) => timeout.min(left),
+            None => timeout,
+        };
+        if let Ok(true) = event::poll(timeout) {
+            // any event may change the screen
+            dirty = true;
+            let event = event::read()?;
+            // wide chars cut by the new width are left behind by the diff, so repaint all
+            if let Event::Resize(_, _) = event {
+                terminal.clear()?;
+            }
+            if let Event::Key(event) = event {
+                if after_key(terminal, handle_key(app, event, tx.clone()))? {
                     return Ok(());
                 }
             };
         };
 
         if let Ok(v) = rx.try_recv() {
+            dirty = true;
             match v {
                 FetchDataMsg::SearchResult(v) => {
                     app.search.items(v);
                     app.search.init_select();
                     app.page = Page::Search;
                 }
+                FetchDataMsg::BoardPage(v) if app.board.more == Some(v.page) => {
+                    app.board.more = None;
+                    app.board.extend_items(v.page, v.items);
+                    app.board.reorder(&app.read_position);
+                    app.board.last_page(v.max);
+                    continue;
+                }
                 FetchDataMsg::BoardPage(v) => {
-                    app.board.items(v.items);
+                    app.board.items(v.page, v.items);
+                    app.board.reorder(&app.read_position);
                     app.board.init_select();
+                    if let Some(i) = app.board.restore.take() {
+                        if i < app.board.items.len() {
+                            app.board.state.select(Some(i));
+                        }
+                    }
+                    if let Some(id) = app.board.restore_thread.take() {
+                        match app.board.items.iter().position(|item| item.id == id) {
+                            Some(i) => app.board.state.select(Some(i)),
+                            None => app.status(
+                                tr!(
+                                    "看板的前 {pages} 頁沒有這篇貼文",
+                                    pages = PARENT_BOARD_PAGES
+                                )
+                                .to_string(),
+                            ),
+                        }
+                    }
                     app.board.last_page(v.max);
                     app.board.page(v.page);
+                    if v.page == 1 && app.board.query.is_none() {
+                        app.mark_board_read();
+                    } else if v.page == 1 {
+                        app.mark_search_read();
+                    }
                     app.page = Page::Board;
                 }
                 FetchDataMsg::PostPage(v) => {
-                    if v.page == 1 {
-                        app.post.data(v.items);
+                    // fewer floors than tnum tells are likely a change of the site
+                    let coverage = v.items.coverage(&app.post.url, v.page);
+                    if let Some(c) = coverage.clone() {
+                        metrics::record_coverage(c);
+                    }
+                    if let Some(c) = coverage.filter(|c| c.is_short()) {
+                        app.status(
+                            tr!(
+                                "第 {page} 頁只解析到 {detected}/{expected} 樓，網站可能改版，按 F12 查看",
+                                page = c.page,
+                                detected = c.detected,
+                                expected = c.expected
+                            )
+                            .to_string(),
+                        );
+                    }
+                    if v.page == 1 || app.post.restore.is_some() {
+                        app.post.data(v.page, v.items);
                         app.post.index(0);
-                        app.post.page(v.page);
                         app.post.last_page(v.max);
+                        app.post.apply_restore();
                         app.page = Page::Post;
+                    } else if v.page < app.post.start_page {
+                        app.post.prepend_posts(v.page, v.items.posts);
+                        app.post.previous();
                     } else {
-                        app.post.chain_posts(v.items.posts);
-                        app.post.page(v.page);
+                        app.post.chain_posts(v.page, v.items.posts);
                         app.post.next();
                     }
                 }
+                FetchDataMsg::BoardKeywords(v) => {
+                    if v.is_empty() {
+                        app.status(tr!("沒有找到熱門關鍵字").to_string());
+                    }
+                    app.board.keywords(v);
+                }
                 FetchDataMsg::CommentPage(v) => {
                     app.page = Page::Comment;
                     app.comment.items(v);
                 }
+                FetchDataMsg::AnimeHome(v) => {
+                    app.anime.home(v);
+                    app.page = Page::Anime;
+                }
+                FetchDataMsg::NewsList(v) => {
+                    app.news.items(v);
+                    app.page = Page::News;
+                }
+                FetchDataMsg::NewsArticle(v) => match v {
+                    Some(article) => {
+                        app.news.article(article);
+                        app.page = Page::Article;
+                    }
+                    None => app.status(tr!("無法讀取新聞內容").to_string()),
+                },
+                FetchDataMsg::CreationList(v) => {
+                    app.creation.items(v);
+                    app.page = Page::Creation;
+                }
+                FetchDataMsg::CreationArticle(v) => match v {
+                    Some(article) => {
+                        app.creation.article(article);
+                        app.page = Page::CreationArticle;
+                    }
+                    None => app.status(tr!("無法讀取創作內容").to_string()),
+                },
+                FetchDataMsg::Activity(v) => {
+                    app.activity.items(v);
+                    app.page = Page::Activity;
+                }
+                FetchDataMsg::ModerationLog(v) => {
+                    app.moderation.items(v);
+                    app.page = Page::Moderation;
+                }
+                // left in the list even when the page was changed meanwhile
+                FetchDataMsg::Timeline(v, true) => {
+                    app.timeline.items(v);
+                    continue;
+                }
+                FetchDataMsg::Timeline(v, false) => {
+                    app.timeline.items(v);
+                    app.page = Page::Timeline;
+                }
+                FetchDataMsg::Config(Ok(config)) => {
+                    app.apply_config(*config);
+                    app.status(tr!("已重新載入設定").to_string());
+                    continue;
+                }
+                FetchDataMsg::Config(Err(e)) => {
+                    app.status(tr!("設定檔有誤，未套用變更: {error}", error = e));
+                    continue;
+                }
+                FetchDataMsg::Update(tag) => {
+                    app.checking_update = false;
+                    if let Some(tag) = tag {
+                        app.status(tr!(
+                            "有新版本 {tag} 可以更新，執行 bahamut changelog 查看更新內容",
+                            tag = tag
+                        ));
+                    }
+                    continue;
+                }
+                FetchDataMsg::FloorComment(id, v) => {
+                    app.post.comments.insert(id, Some(v));
+                    continue;
+                }
+                FetchDataMsg::AgeGate(retry) => {
+                    app.age_gate = Some(retry);
+                    app.confirm = Some(Confirm::new(
+                        ConfirmAction::AgeGate,
+                        tr!("此看板或貼文限制 18 歲以上瀏覽，確定已年滿 18 歲嗎?"),
+                    ));
+                }
+                FetchDataMsg::ThreadPreview(url, snippet) => {
+                    app.board.previews.insert(url, Some(snippet));
+                    continue;
+                }
+                FetchDataMsg::LinkPreview(link, card) => {
+                    app.post.previews.insert(link, Some(card));
+                    continue;
+                }
+                FetchDataMsg::Download(name, floor, saved) => {
+                    app.downloaded(name, floor, saved);
+                    continue;
+                }
+                FetchDataMsg::Summary(summary) => {
+                    if let Some(state) = app.summary.as_mut() {
+                        state.result = Some(summary);
+                    }
+                    continue;
+                }
+                FetchDataMsg::Image(link, image) => {
+                    app.post.image_loaded(link, image);
+                    continue;
+                }
+                FetchDataMsg::Avatar(id, avatar) => {
+                    app.post.avatars.insert(id, Some(avatar));
+                    continue;
+                }
+                FetchDataMsg::Translation(titles, translated) => {
+                    app.translated(titles, translated);
+                    continue;
+                }
+                FetchDataMsg::SavedSearch(key, urls) => {
+                    let seen = app.searches.saved.iter().find(|s| s.key() == key);
+                    let count =
+                        seen.map_or(0, |s| urls.iter().filter(|u| !s.seen.contains(*u)).count());
+                    app.search.counts.insert(key, Some(count));
+                    continue;
+                }
             }
 
             app.loading = false;
@@ -131,10 +606,266 @@ fn run_app<B: Backend>(
     }
 }
 
-fn run_fetcher(tx: Sender<FetchDataMsg>, rx: Receiver<DataRequestMsg>) -> JoinHandle<()> {
+// pixels kept of an avatar, it only takes a few cells
+static AVATAR_SIZE: u32 = 128;
+
+// decoded off the runtime, shrunk to fit in max pixels when given,
+// with the hash of the bytes to tell reposts under another link
+async fn load_image(
+    client: &BahamutClient,
+    link: &str,
+    max: Option<u32>,
+) -> Result<(u64, Arc<DynamicImage>), String> {
+    let bytes = preview::fetch_image(client, link)
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || {
+        let image = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+        let image = match max {
+            Some(max) => image.thumbnail(max, max),
+            None => image,
+        };
+        Ok((bytes_hash(&bytes), Arc::new(image)))
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()))
+}
+
+// titles and authors, left out under a popup
+fn page_links(app: &AppState) -> Vec<Hyperlink> {
+    if app.has_overlay() {
+        return vec![];
+    }
+
+    match app.page {
+        Page::Board => app.board.links.clone(),
+        Page::Post => app.post.links.clone(),
+        _ => vec![],
+    }
+}
+
+fn request_floor_comment(app: &mut AppState, tx: &Sender<DataRequestMsg>) {
+    if app.loading
+        || app.config.cache.low_memory
+        || !matches!(app.page, Page::Post)
+        || !(app.post.dual_pane || app.config.layout.inline_comments)
+    {
+        return;
+    }
+
+    let Some(bsn) = app.post.board_id() else {
+        return;
+    };
+    if let Some(content) = app.post.current() {
+        let id = content.id.to_owned();
+        if !app.post.comments.contains_key(&id) {
+            app.post.comments.insert(id.to_owned(), None);
+            tx.send(DataRequestMsg::FloorComment(bsn, id)).unwrap_or(());
+        }
+    }
+}
+
+// links of the current floor in the allowed domains
+fn request_link_preview(app: &mut AppState, tx: &Sender<DataRequestMsg>) {
+    let domains = &app.config.preview.domains;
+    if domains.is_empty()
+        || app.config.network.text_only(&app.config.cache)
+        || app.loading
+        || !matches!(app.page, Page::Post)
+    {
+        return;
+    }
+
+    let links: Vec<String> = match app.post.current() {
+        Some(content) => content
+            .desc
+            .iter()
+            .flat_map(|block| preview::find_links(block))
+            .filter(|link| preview::is_allowed(link, domains))
+            .map(str::to_string)
+            .collect(),
+        None => return,
+    };
+
+    for link in links {
+        if !app.post.previews.contains_key(&link) {
+            app.post.previews.insert(link.to_owned(), None);
+            tx.send(DataRequestMsg::LinkPreview(link)).unwrap_or(());
+        }
+    }
+}
+
+fn request_image(app: &mut AppState, tx: &Sender<DataRequestMsg>) {
+    if let Some(viewer) = app.post.image.as_ref() {
+        let link = viewer.url().to_string();
+        if !app.post.images.contains_key(&link) {
+            app.post.images.insert(link.to_owned(), None);
+            tx.send(DataRequestMsg::Image(link)).unwrap_or(());
+        }
+    }
+}
+
+// avatar of the current floor author, only drawn with the graphics protocol
+fn request_avatar(app: &mut AppState, tx: &Sender<DataRequestMsg>) {
+    if !app.graphics
+        || !app.config.layout.avatars
+        || app.config.network.text_only(&app.config.cache)
+        || !matches!(app.page, Page::Post)
+    {
+        return;
+    }
+
+    if let Some(user) = app.post.current().map(|c| c.user.clone()) {
+        if !user.avatar.is_empty() && !app.post.avatars.contains_key(&user.id) {
+            app.post.avatars.insert(user.id.to_owned(), None);
+            tx.send(DataRequestMsg::Avatar(
+                user.id.to_owned(),
+                user.avatar.to_owned(),
+            ))
+            .unwrap_or(());
+        }
+    }
+}
+
+// first floor of the threads on screen, only once for each
+fn request_thread_preview(app: &mut AppState, tx: &Sender<DataRequestMsg>) {
+    let config = &app.config.list_preview;
+    if !config.enabled
+        || app.config.network.text_only(&app.config.cache)
+        || app.loading
+        || !matches!(app.page, Page::Board)
+    {
+        return;
+    }
+
+    let board = &mut app.board;
+    for item in board.items.get(board.visible.clone()).unwrap_or_default() {
+        if !item.deleted && !board.previews.contains_key(&item.url) {
+            board.previews.insert(item.url.to_owned(), None);
+            tx.send(DataRequestMsg::ThreadPreview(
+                item.url.to_owned(),
+                config.max_length,
+            ))
+            .unwrap_or(());
+        }
+    }
+}
+
+// items from the end of a board list that start loading the next page
+static SCROLL_MARGIN: usize = 3;
+
+// next page near the bottom of a board list, or the previous one near the top
+fn request_more_threads(app: &mut AppState, tx: &Sender<DataRequestMsg>) {
+    let board = &app.board;
+    if !app.config.navigation.infinite_scroll
+        || app.config.cache.low_memory
+        || app.loading
+        || board.more.is_some()
+        || !matches!(app.page, Page::Board)
+    {
+        return;
+    }
+
+    let (selected, (first, last)) = match (board.state.selected(), board.loaded_range()) {
+        (Some(selected), Some(range)) => (selected, range),
+        _ => return,
+    };
+    let page = if selected + SCROLL_MARGIN >= board.items.len() && last < board.last_page {
+        last + 1
+    } else if selected < SCROLL_MARGIN && first > 1 {
+        first - 1
+    } else {
+        return;
+    };
+
+    tx.send(board_request(app, page, true)).unwrap_or(());
+    app.board.more = Some(page);
+}
+
+fn request_saved_search(app: &mut AppState, tx: &Sender<DataRequestMsg>) {
+    if app.loading || app.search.saved_state.is_none() || !matches!(app.page, Page::Search) {
+        return;
+    }
+
+    let search = &mut app.search;
+    for saved in search.saved.iter() {
+        if let Entry::Vacant(count) = search.counts.entry(saved.key()) {
+            count.insert(None);
+            tx.send(DataRequestMsg::SavedSearch(
+                saved.board.to_owned(),
+                saved.query.to_owned(),
+            ))
+            .unwrap_or(());
+        }
+    }
+}
+
+// titles of the visible items in a list with the translation on
+fn request_translation(app: &mut AppState, tx: &Sender<DataRequestMsg>) {
+    if app.loading {
+        return;
+    }
+
+    let titles: Vec<String> = match app.page {
+        Page::Board if app.board.translate => app
+            .board
+            .items
+            .get(app.board.visible.clone())
+            .unwrap_or_default()
+            .iter()
+            .filter(|item| !item.deleted)
+            .map(|item| item.title.to_owned())
+            .collect(),
+        Page::Timeline if app.timeline.translate => app
+            .timeline
+            .items
+            .get(app.timeline.visible.clone())
+            .unwrap_or_default()
+            .iter()
+            .map(|item| item.post.title.to_owned())
+            .collect(),
+        _ => return,
+    };
+
+    let language = &app.config.translate.language;
+    let titles: Vec<String> = titles
+        .into_iter()
+        .filter(|title| {
+            app.translation.get(language, title).is_none() && !app.translating.contains(title)
+        })
+        .collect::<HashSet<String>>()
+        .into_iter()
+        .collect();
+    let config = match app.config.translate.backend(&app.config.summary) {
+        Some(config) if !titles.is_empty() => config,
+        _ => return,
+    };
+
+    app.translating.extend(titles.iter().cloned());
+    tx.send(DataRequestMsg::Translation(config, titles))
+        .unwrap_or(());
+}
+
+fn request_timeline(app: &mut AppState, tx: &Sender<DataRequestMsg>) {
+    if app.loading || !app.timeline_refresh_in().is_some_and(|left| left.is_zero()) {
+        return;
+    }
+
+    app.request_timeline(tx, true);
+}
+
+// only the page being opened is kept parsed in the low memory mode
+fn run_fetcher(
+    tx: Sender<FetchDataMsg>,
+    rx: Receiver<DataRequestMsg>,
+    low_memory: bool,
+    client: Arc<BahamutClient>,
+) -> JoinHandle<()> {
     thread::spawn(move || {
         let mut board_cache: HashMap<String, RefCell<BoardPage>> = HashMap::new();
         let mut post_cache: HashMap<String, RefCell<PostPage>> = HashMap::new();
+        let mut series_cache: HashMap<String, RefCell<PostSeries>> = HashMap::new();
+        let mut thread_series: HashMap<String, Vec<String>> = HashMap::new();
 
         let rt = Builder::new_multi_thread().enable_all().build().unwrap();
         rt.block_on(async {
@@ -142,8 +873,103 @@ fn run_fetcher(tx: Sender<FetchDataMsg>, rx: Receiver<DataRequestMsg>) -> JoinHa
                 if let Ok(msg) = rx.recv() {
                     match msg {
                         DataRequestMsg::End => return,
+                        DataRequestMsg::AnimeHome => {
+                            tx.send(FetchDataMsg::AnimeHome(AnimeHome::get(&client)))
+                                .unwrap_or(());
+                        }
+                        DataRequestMsg::NewsList => {
+                            tx.send(FetchDataMsg::NewsList(News::get_headlines(&client)))
+                                .unwrap_or(());
+                        }
+                        DataRequestMsg::NewsArticle(url) => {
+                            let article = NewsArticle::get(&client, url.as_ref());
+                            tx.send(FetchDataMsg::NewsArticle(article)).unwrap_or(());
+                        }
+                        DataRequestMsg::CreationList(owner) => {
+                            let items = User::creations(&client, owner.as_ref());
+                            tx.send(FetchDataMsg::CreationList(items)).unwrap_or(());
+                        }
+                        DataRequestMsg::Activity(owner, pages) => {
+                            let items = User::creation_history(&client, owner.as_ref(), pages);
+                            tx.send(FetchDataMsg::Activity(items)).unwrap_or(());
+                        }
+                        DataRequestMsg::ParentBoard(id, thread, pages) => {
+                            let mut board = BoardPage::new(id.as_ref(), Arc::clone(&client));
+                            board.init();
+
+                            // the first page when it's further down or gone
+                            let page = board.find_thread(thread.as_ref(), pages).unwrap_or(1);
+                            let items = match board.get_and_cache(page, false) {
+                                Some(board) => board.post(),
+                                None => vec![],
+                            };
+
+                            if items.is_empty() && client.take_gated() {
+                                let retry = DataRequestMsg::ParentBoard(id, thread, pages);
+                                tx.send(FetchDataMsg::AgeGate(retry)).unwrap_or(());
+                                continue;
+                            }
+
+                            let page_data = PageData {
+                                page,
+                                items,
+                                max: board.max,
+                            };
+                            if low_memory {
+                                board_cache.clear();
+                                post_cache.clear();
+                                series_cache.clear();
+                            }
+                            board_cache.insert(id, RefCell::new(board));
+                            tx.send(FetchDataMsg::BoardPage(page_data)).unwrap_or(());
+                        }
+                        DataRequestMsg::Timeline(boards, refresh) => {
+                            let tx = tx.clone();
+                            let client = Arc::clone(&client);
+                            tokio::task::spawn_blocking(move || {
+                                let fetch = || {
+                                    let mut items = vec![];
+                                    for (id, name) in boards {
+                                        let mut board =
+                                            BoardPage::new(id.as_ref(), Arc::clone(&client));
+                                        board.init();
+                                        let posts = board
+                                            .get_and_cache(1, true)
+                                            .map(|board| board.post())
+                                            .unwrap_or_default();
+                                        items.extend(
+                                            posts.into_iter().map(|post| (name.to_owned(), post)),
+                                        );
+                                    }
+                                    items
+                                };
+                                let items = if refresh {
+                                    api::background(fetch)
+                                } else {
+                                    fetch()
+                                };
+                                tx.send(FetchDataMsg::Timeline(items, refresh))
+                                    .unwrap_or(());
+                            });
+                        }
+                        DataRequestMsg::ModerationLog(id) => {
+                            let items = ModerationLog::get(&client, id.as_ref());
+                            tx.send(FetchDataMsg::ModerationLog(items)).unwrap_or(());
+                        }
+                        DataRequestMsg::CreationArticle(url) => {
+                            let article = CreationArticle::get(&client, url.as_ref());
+                            tx.send(FetchDataMsg::CreationArticle(article))
+                                .unwrap_or(());
+                        }
+                        DataRequestMsg::ThreadSeries(series) => {
+                            thread_series = series
+                                .into_iter()
+                                .filter_map(|urls| Some((urls.first()?.to_owned(), urls)))
+                                .collect();
+                            series_cache.clear();
+                        }
                         DataRequestMsg::SearchResult(query) => {
-                            let res = BoardSearch::get_search_result(query.as_ref());
+                            let res = BoardSearch::get_search_result(&client, query.as_ref());
                             if tx.send(FetchDataMsg::SearchResult(res)).is_err() {
                                 println!("get search result error")
                             };
@@ -161,79 +987,295 @@ fn run_fetcher(tx: Sender<FetchDataMsg>, rx: Receiver<DataRequestMsg>) -> JoinHa
                                             items,
                                             max: board_page.max,
                                         };
-                                        tx.send(FetchDataMsg::BoardPage(page_data))
-                                            .map_or((), |_| ());
+                                        tx.send(FetchDataMsg::BoardPage(page_data)).unwrap_or(());
                                         continue;
                                     }
                                 }
                             }
 
-                            let mut board = BoardPage::from_page(id.as_ref(), page);
+                            let mut board =
+                                BoardPage::from_page(id.as_ref(), page, Arc::clone(&client));
                             board.init();
 
-                            let items = match board.get_and_cache(page, false) {
+                            let items = match board.get_and_cache(page, !use_cache) {
                                 Some(board) => board.post(),
                                 None => vec![],
                             };
 
+                            if items.is_empty() && client.take_gated() {
+                                let retry = DataRequestMsg::BoardPage(id, page, false);
+                                tx.send(FetchDataMsg::AgeGate(retry)).unwrap_or(());
+                                continue;
+                            }
+
                             let page_data = PageData {
                                 page,
                                 items,
                                 max: board.max,
                             };
+                            if low_memory {
+                                board_cache.clear();
+                                post_cache.clear();
+                                series_cache.clear();
+                            }
                             board_cache.insert(id, RefCell::new(board));
-                            tx.send(FetchDataMsg::BoardPage(page_data))
-                                .map_or((), |_| ());
+                            tx.send(FetchDataMsg::BoardPage(page_data)).unwrap_or(());
+                        }
+
+                        // title search in board
+                        DataRequestMsg::BoardSearch(id, query, page, use_cache) => {
+                            let mut board = BoardPage::search(
+                                id.as_ref(),
+                                query.as_ref(),
+                                page,
+                                Arc::clone(&client),
+                            );
+                            board.init();
+
+                            let items = match board.get_and_cache(page, !use_cache) {
+                                Some(board) => board.post(),
+                                None => vec![],
+                            };
+
+                            if items.is_empty() && client.take_gated() {
+                                let retry = DataRequestMsg::BoardSearch(id, query, page, false);
+                                tx.send(FetchDataMsg::AgeGate(retry)).unwrap_or(());
+                                continue;
+                            }
+
+                            let page_data = PageData {
+                                page,
+                                items,
+                                max: board.max,
+                            };
+                            tx.send(FetchDataMsg::BoardPage(page_data)).unwrap_or(());
+                        }
+
+                        // keywords from titles of latest pages
+                        DataRequestMsg::BoardKeywords(id, pages, limit) => {
+                            let mut board = BoardPage::new(id.as_ref(), Arc::clone(&client));
+                            board.init();
+
+                            let boards: Vec<Board> = (1..=pages.min(board.max.max(1)))
+                                .filter_map(|page| board.get_and_cache(page, false))
+                                .collect();
+                            let titles: Vec<String> = boards
+                                .iter()
+                                .flat_map(|b| b.post_refs())
+                                .map(|p| p.title.into_owned())
+                                .collect();
+
+                            let keywords = trending_keywords(titles.as_ref(), limit);
+                            tx.send(FetchDataMsg::BoardKeywords(keywords)).unwrap_or(());
                         }
 
                         // post page request
                         DataRequestMsg::PostPage(url, page, use_cache) => {
                             let cache_key = url.to_owned();
 
+                            // linked continuation threads
+                            if let Some(urls) = thread_series.get(&url) {
+                                if !use_cache || !series_cache.contains_key(&url) {
+                                    let parts = urls
+                                        .iter()
+                                        .filter_map(|u| {
+                                            PostPageUrlParameter::try_from(u.to_owned()).ok()
+                                        })
+                                        .map(|param| {
+                                            PostPage::from_param(param, Arc::clone(&client))
+                                        })
+                                        .collect();
+                                    let mut series = PostSeries::new(parts);
+                                    series.init();
+                                    if low_memory {
+                                        board_cache.clear();
+                                        post_cache.clear();
+                                        series_cache.clear();
+                                    }
+                                    series_cache.insert(cache_key.to_owned(), RefCell::new(series));
+                                }
+
+                                if let Some(series) = series_cache.get(&cache_key) {
+                                    let mut series = series.borrow_mut();
+                                    let items =
+                                        series.get_and_cache(page, !use_cache).unwrap_or_default();
+                                    let page_data = PageData {
+                                        page,
+                                        items,
+                                        max: series.max(),
+                                    };
+                                    tx.send(FetchDataMsg::PostPage(page_data)).unwrap_or(());
+                                    continue;
+                                }
+                            }
+
                             if use_cache {
                                 if let Some(post_page) = post_cache.get(&cache_key) {
                                     let mut post_page = post_page.borrow_mut();
-                                    if let Some(post) = post_page.get_and_cache(page, false) {
+                                    if let Some(mut post) = post_page.get_and_cache(page, false) {
+                                        post_page.fill_starter(&mut post);
                                         let page_data = PageData {
                                             page,
                                             items: post,
                                             max: post_page.max,
                                         };
-                                        tx.send(FetchDataMsg::PostPage(page_data))
-                                            .map_or((), |_| ());
+                                        tx.send(FetchDataMsg::PostPage(page_data)).unwrap_or(());
                                         continue;
                                     }
                                 }
                             }
 
                             let param = PostPageUrlParameter::try_from(url).unwrap();
-                            let mut post_page = PostPage::try_from(param).unwrap();
+                            let mut post_page = PostPage::from_param(param, Arc::clone(&client));
                             post_page.init();
 
-                            let items = match post_page.get_and_cache(page, false) {
-                                None => Post::default(),
-                                Some(post) => post,
-                            };
+                            let mut items = post_page
+                                .get_and_cache(page, !use_cache)
+                                .unwrap_or_default();
+                            if items.posts.is_empty() && client.take_gated() {
+                                let retry = DataRequestMsg::PostPage(cache_key, page, false);
+                                tx.send(FetchDataMsg::AgeGate(retry)).unwrap_or(());
+                                continue;
+                            }
+                            post_page.fill_starter(&mut items);
 
                             let page_data = PageData {
                                 page,
                                 items,
                                 max: post_page.max,
                             };
+                            if low_memory {
+                                board_cache.clear();
+                                post_cache.clear();
+                                series_cache.clear();
+                            }
                             post_cache.insert(cache_key, RefCell::new(post_page));
-                            tx.send(FetchDataMsg::PostPage(page_data))
-                                .map_or((), |_| ())
+                            tx.send(FetchDataMsg::PostPage(page_data)).unwrap_or(())
                         }
 
                         // comment
                         DataRequestMsg::CommentPage(id, c_id) => {
-                            let res = match PostComment::get_comment(id.to_owned(), c_id.to_owned())
-                            {
-                                Ok(v) => v,
-                                Err(_) => vec![],
+                            // only the floor id is needed to read its comments
+                            let floor = PostContent {
+                                id: c_id,
+                                ..Default::default()
                             };
+                            let res = floor.comment(&client, &id).unwrap_or_default();
 
-                            tx.send(FetchDataMsg::CommentPage(res)).map_or((), |_| ());
+                            tx.send(FetchDataMsg::CommentPage(res)).unwrap_or(());
+                        }
+
+                        // comment of focused floor in dual pane
+                        DataRequestMsg::FloorComment(id, c_id) => {
+                            let floor = PostContent {
+                                id: c_id.to_owned(),
+                                ..Default::default()
+                            };
+                            let res =
+                                api::background(|| floor.comment(&client, &id)).unwrap_or_default();
+
+                            tx.send(FetchDataMsg::FloorComment(c_id, res)).unwrap_or(());
+                        }
+                        // parsed on a blocking thread, the page goes to the disk cache
+                        DataRequestMsg::ThreadPreview(url, max_length) => {
+                            let tx = tx.clone();
+                            let client = Arc::clone(&client);
+                            tokio::task::spawn_blocking(move || {
+                                api::background(|| {
+                                    let snippet = PostPageUrlParameter::try_from(url.to_owned())
+                                        .ok()
+                                        .map(|param| {
+                                            PostPage::from_param(param, Arc::clone(&client))
+                                        })
+                                        .and_then(|page| page.get(1, false))
+                                        .and_then(|post| post.posts.first().map(|p| p.desc.clone()))
+                                        .map(|desc| snippet_of(&desc, max_length))
+                                        .unwrap_or_default();
+                                    tx.send(FetchDataMsg::ThreadPreview(url, snippet))
+                                        .unwrap_or(());
+                                })
+                            });
+                        }
+                        DataRequestMsg::SavedSearch(id, query) => {
+                            let tx = tx.clone();
+                            let client = Arc::clone(&client);
+                            tokio::task::spawn_blocking(move || {
+                                api::background(|| {
+                                    let mut board = BoardPage::search(
+                                        id.as_ref(),
+                                        query.as_ref(),
+                                        1,
+                                        Arc::clone(&client),
+                                    );
+                                    let urls = board
+                                        .get_and_cache(1, true)
+                                        .map(|board| board.post())
+                                        .unwrap_or_default()
+                                        .into_iter()
+                                        .map(|post| post.url)
+                                        .collect();
+                                    let key = store::search_key(id.as_ref(), query.as_ref());
+                                    tx.send(FetchDataMsg::SavedSearch(key, urls)).unwrap_or(());
+                                })
+                            });
+                        }
+                        DataRequestMsg::LinkPreview(link) => {
+                            let tx = tx.clone();
+                            let client = Arc::clone(&client);
+                            tokio::spawn(api::background_async(async move {
+                                let card =
+                                    LinkPreview::fetch(&client, &link).await.unwrap_or_default();
+                                tx.send(FetchDataMsg::LinkPreview(link, card)).unwrap_or(());
+                            }));
+                        }
+                        DataRequestMsg::Download(attachment, dir, floor) => {
+                            let tx = tx.clone();
+                            let client = Arc::clone(&client);
+                            tokio::spawn(async move {
+                                let saved = async {
+                                    let path = attachment.download(&client, &dir).await?;
+                                    let hash = bytes_hash(&tokio::fs::read(&path).await?);
+                                    Ok::<_, Box<dyn Error>>((path, hash))
+                                };
+                                let saved = saved.await.map_err(|e| e.to_string());
+                                tx.send(FetchDataMsg::Download(attachment.name, floor, saved))
+                                    .unwrap_or(());
+                            });
+                        }
+                        DataRequestMsg::Translation(config, titles) => {
+                            let tx = tx.clone();
+                            tokio::spawn(api::background_async(async move {
+                                let translated =
+                                    summary::translate(config, titles.to_owned()).await;
+                                tx.send(FetchDataMsg::Translation(titles, translated))
+                                    .unwrap_or(());
+                            }));
+                        }
+                        DataRequestMsg::Summary(config, text) => {
+                            let tx = tx.clone();
+                            tokio::spawn(async move {
+                                let summary = summary::summarize(config, text).await;
+                                tx.send(FetchDataMsg::Summary(summary)).unwrap_or(());
+                            });
+                        }
+                        DataRequestMsg::Image(link) => {
+                            let tx = tx.clone();
+                            let client = Arc::clone(&client);
+                            // large images take longer than a page
+                            tokio::spawn(api::background_async(async move {
+                                let image = load_image(&client, &link, None).await;
+                                tx.send(FetchDataMsg::Image(link, image)).unwrap_or(());
+                            }));
+                        }
+                        DataRequestMsg::Avatar(id, link) => {
+                            let tx = tx.clone();
+                            let client = Arc::clone(&client);
+                            tokio::spawn(api::background_async(async move {
+                                let avatar = load_image(&client, &link, Some(AVATAR_SIZE))
+                                    .await
+                                    .map(|(_, avatar)| avatar);
+                                tx.send(FetchDataMsg::Avatar(id, avatar)).unwrap_or(());
+                            }));
                         }
                     };
                 };
@@ -241,3 +1283,15 @@ fn run_fetcher(tx: Sender<FetchDataMsg>, rx: Receiver<DataRequestMsg>) -> JoinHa
         })
     })
 }
+
+// text blocks of a floor in one line, images and videos are left out
+fn snippet_of(desc: &[String], max_length: usize) -> String {
+    desc.iter()
+        .filter(|block| !block.starts_with("http"))
+        .flat_map(|block| block.split_whitespace())
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .chars()
+        .take(max_length)
+        .collect()
+}
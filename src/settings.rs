@@ -0,0 +1,191 @@
+use std::fs;
+
+use ratatui::style::Color;
+use toml_edit::{Array, DocumentMut, Item, Table, Value};
+
+use crate::{config::Config, store::write_atomic, terminal::ColorMode};
+
+pub enum SettingKind {
+    Bool,
+    Number,
+    // color name or #rrggbb, empty to leave it unstyled
+    Color,
+    Text,
+    // comma separated in the editor
+    List,
+    Choice(&'static [&'static str]),
+}
+
+pub struct Setting {
+    // table.field in config.toml
+    pub key: &'static str,
+    pub desc: &'static str,
+    pub kind: SettingKind,
+    value: fn(&Config) -> String,
+}
+
+macro_rules! settings {
+    ($($key:expr, $kind:expr, $desc:expr => |$c:ident| $value:expr);* $(;)?) => {
+        &[$(Setting { key: $key, desc: $desc, kind: $kind, value: |$c| $value.to_string() }),*]
+    };
+}
+
+fn color_mode(mode: ColorMode) -> &'static str {
+    match mode {
+        ColorMode::Auto => "auto",
+        ColorMode::Always => "always",
+        ColorMode::Never => "never",
+    }
+}
+
+// options editable in the settings screen, the rest are only in config.toml
+pub static SETTINGS: &[Setting] = settings![
+    "layout.dual_pane_width", SettingKind::Number, "貼文頁面顯示右側留言的終端機寬度，0 關閉" => |c| c.layout.dual_pane_width;
+    "layout.footer", SettingKind::Bool, "畫面最下方顯示按鍵提示" => |c| c.layout.footer;
+    "layout.line_numbers", SettingKind::Bool, "貼文頁面預設顯示行號" => |c| c.layout.line_numbers;
+    "layout.max_fps", SettingKind::Number, "每秒最多重畫的次數" => |c| c.layout.max_fps;
+    "layout.avatars", SettingKind::Bool, "貼文頁面顯示樓層作者的頭像" => |c| c.layout.avatars;
+    "layout.inline_comments", SettingKind::Bool, "沒有右側留言時在樓層下方顯示最新的留言" => |c| c.layout.inline_comments;
+    "terminal.color", SettingKind::Choice(&["auto", "always", "never"]), "顏色顯示" => |c| color_mode(c.terminal.color);
+    "terminal.hyperlinks", SettingKind::Choice(&["auto", "always", "never"]), "可點擊的連結" => |c| color_mode(c.terminal.hyperlinks);
+    "terminal.graphics", SettingKind::Choice(&["auto", "always", "never"]), "以 kitty 圖片協定顯示圖片" => |c| color_mode(c.terminal.graphics);
+    "terminal.mosaic", SettingKind::Bool, "不支援圖片協定時以半格字元顯示圖片" => |c| c.terminal.mosaic;
+    "terminal.replace_glyphs", SettingKind::Bool, "以替代字元顯示 emoji 與罕見字，避免版面錯位" => |c| c.terminal.replace_glyphs;
+    "terminal.placeholder", SettingKind::Text, "取代 emoji 與罕見字的字元" => |c| c.terminal.placeholder;
+    "locale.language", SettingKind::Choice(&["auto", "zh-TW", "en"]), "介面語言(重新啟動後生效)" => |c| c.locale.language;
+    "theme.starter", SettingKind::Color, "樓主的顏色" => |c| c.theme.starter;
+    "theme.moderator", SettingKind::Color, "版主的顏色" => |c| c.theme.moderator;
+    "theme.me", SettingKind::Color, "自己的顏色" => |c| c.theme.me;
+    "theme.followed", SettingKind::Color, "追蹤的使用者的顏色" => |c| c.theme.followed;
+    "theme.notice", SettingKind::Color, "貼文中系統訊息與公告的顏色" => |c| c.theme.notice;
+    "filter.enabled", SettingKind::Bool, "跳過低內容的回覆" => |c| c.filter.enabled;
+    "filter.min_chars", SettingKind::Number, "少於這個字數的樓層視為低內容" => |c| c.filter.min_chars;
+    "filter.hide_notices", SettingKind::Bool, "隱藏貼文中的系統訊息與公告" => |c| c.filter.hide_notices;
+    "filter.hide_deleted", SettingKind::Bool, "隱藏看板列表中已刪除的貼文" => |c| c.filter.hide_deleted;
+    "filter.phrases", SettingKind::List, "只有這些字的樓層視為低內容" => |c| c.filter.phrases.join(", ");
+    "translate.language", SettingKind::Text, "看板列表按 t 翻譯標題的目標語言" => |c| c.translate.language;
+    "list_preview.enabled", SettingKind::Bool, "看板列表顯示首樓的開頭" => |c| c.list_preview.enabled;
+    "list_preview.max_length", SettingKind::Number, "首樓內容保留的字數" => |c| c.list_preview.max_length;
+    "navigation.follow_unread", SettingKind::Bool, "按 Tab 跳到有未讀內容的訂閱" => |c| c.navigation.follow_unread;
+    "navigation.infinite_scroll", SettingKind::Bool, "捲到看板列表底部時自動讀取下一頁" => |c| c.navigation.infinite_scroll;
+    "navigation.unread_first", SettingKind::Bool, "看板列表中有未讀回覆的貼文排在前面" => |c| c.navigation.unread_first;
+    "navigation.digest", SettingKind::Bool, "啟動時列出訂閱的貼文與看板的更新" => |c| c.navigation.digest;
+    "timeline.refresh_secs", SettingKind::Number, "看板動態自動重新整理的間隔秒數，0 關閉" => |c| c.timeline.refresh_secs;
+    "navigation.open_copied", SettingKind::Bool, "啟動時詢問是否開啟剪貼簿中的貼文或看板網址" => |c| c.navigation.open_copied;
+    "confirm.quit", SettingKind::Bool, "離開前確認" => |c| c.confirm.quit;
+    "confirm.clear_cache", SettingKind::Bool, "清除快取前確認" => |c| c.confirm.clear_cache;
+    "cache.low_memory", SettingKind::Bool, "低記憶體模式，不預先讀取與處理圖片(重新啟動後生效)" => |c| c.cache.low_memory;
+    "network.timeout_secs", SettingKind::Number, "按鍵開啟的頁面的逾時秒數" => |c| c.network.timeout_secs;
+    "network.background_timeout_secs", SettingKind::Number, "背景讀取的逾時秒數" => |c| c.network.background_timeout_secs;
+    "network.background_retries", SettingKind::Number, "背景讀取失敗時重試的次數" => |c| c.network.background_retries;
+    "network.request_interval_ms", SettingKind::Number, "兩次請求之間至少間隔的毫秒數" => |c| c.network.request_interval_ms;
+    "network.data_saver", SettingKind::Bool, "省流模式，不含圖片、影片與預覽" => |c| c.network.data_saver;
+    "download.dir", SettingKind::Text, "附件的下載目錄，留空使用系統的下載目錄" => |c| c.download.dir;
+    "reply.open_browser", SettingKind::Bool, "複製回覆範本後開啟貼文的回覆頁面" => |c| c.reply.open_browser;
+    "network.startup_check", SettingKind::Bool, "啟動時檢查連線與登入狀態" => |c| c.network.startup_check;
+    "preview.domains", SettingKind::List, "在背景讀取連結預覽的網域" => |c| c.preview.domains.join(", ");
+    "update.check", SettingKind::Bool, "啟動時檢查新版本" => |c| c.update.check;
+    "webhook.url", SettingKind::Text, "watch 偵測到更新時呼叫的 webhook" => |c| c.webhook.url;
+];
+
+impl Setting {
+    pub fn value(&self, config: &Config) -> String {
+        (self.value)(config)
+    }
+
+    // whether it's changed in place instead of typed
+    pub fn is_toggle(&self) -> bool {
+        matches!(self.kind, SettingKind::Bool | SettingKind::Choice(_))
+    }
+
+    // value after pressing enter on a bool or a choice
+    pub fn toggled(&self, config: &Config) -> String {
+        let current = self.value(config);
+        match self.kind {
+            SettingKind::Bool => (current != "true").to_string(),
+            SettingKind::Choice(choices) => {
+                let i = choices
+                    .iter()
+                    .position(|c| *c == current)
+                    .map_or(0, |i| i + 1);
+                choices[i % choices.len()].to_string()
+            }
+            _ => current,
+        }
+    }
+
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        let input = input.trim();
+        let value = match self.kind {
+            SettingKind::Bool => Value::from(input.parse::<bool>().map_err(|e| e.to_string())?),
+            SettingKind::Number => {
+                let n = input.parse::<u32>().map_err(|_| tr!("請輸入非負整數"))?;
+                Value::from(n as i64)
+            }
+            SettingKind::Color => {
+                if !input.is_empty() && input.parse::<Color>().is_err() {
+                    return Err(tr!("無效的顏色: {color}", color = input));
+                }
+                Value::from(input)
+            }
+            SettingKind::Text => Value::from(input),
+            SettingKind::List => {
+                let items: Array = input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                Value::Array(items)
+            }
+            SettingKind::Choice(choices) => {
+                if !choices.contains(&input) {
+                    return Err(tr!("可用的值: {choices}", choices = choices.join(", ")));
+                }
+                Value::from(input)
+            }
+        };
+
+        Ok(value)
+    }
+
+    // written into config.toml keeping its comments and layout, the new config is returned
+    pub fn save(&self, input: &str) -> Result<Config, String> {
+        let value = self.parse(input)?;
+        let path = Config::path().ok_or(tr!("找不到設定檔目錄"))?;
+        let content = if path.exists() {
+            fs::read_to_string(&path).map_err(|e| e.to_string())?
+        } else {
+            String::new()
+        };
+
+        let mut doc: DocumentMut = content
+            .parse()
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        let (table, field) = self.key.split_once('.').unwrap_or(("", self.key));
+        let entry = doc.entry(table).or_insert(Item::Table(Table::new()));
+        let table = entry
+            .as_table_like_mut()
+            .ok_or(tr!("{key} 不是表格", key = table))?;
+        match table.get_mut(field).and_then(Item::as_value_mut) {
+            // the comment before and after the old value is kept
+            Some(old) => {
+                let decor = old.decor().clone();
+                *old = value;
+                *old.decor_mut() = decor;
+            }
+            None => {
+                table.insert(field, Item::Value(value));
+            }
+        }
+
+        // checked by the real parser before writing
+        let content = doc.to_string();
+        let config: Config = toml::from_str(content.as_ref()).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        write_atomic(&path, content).map_err(|e| e.to_string())?;
+
+        Ok(config)
+    }
+}
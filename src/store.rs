@@ -0,0 +1,642 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+use bahamut::api::{file, hash::content_hash, post::PostPageUrlParameter};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::data_dir;
+
+pub trait Store: Serialize + DeserializeOwned + Default {
+    const NAME: &'static str;
+    // bumped when the format changes, with a step in migrate from the old one
+    const VERSION: u32 = 1;
+
+    // turn the json of `version` into `version + 1`
+    fn migrate(_version: u32, value: Value) -> Option<Value> {
+        Some(value)
+    }
+
+    fn decode(path: &Path, content: &str) -> Option<Self> {
+        let mut value: Value = serde_json::from_str(content).ok()?;
+        // files from before the versioning are version 1
+        let version = value
+            .get(VERSION_KEY)
+            .and_then(Value::as_u64)
+            .map_or(1, |v| v as u32);
+
+        // the original is kept once, a newer format may have fields unknown here
+        if version != Self::VERSION {
+            let copy = sibling(path, format!(".v{}", version).as_ref());
+            if !copy.exists() {
+                fs::copy(path, copy).unwrap_or(0);
+            }
+        }
+
+        for v in version..Self::VERSION {
+            value = Self::migrate(v, value)?;
+        }
+        serde_json::from_value(value).ok()
+    }
+
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join(format!("{}.json", Self::NAME)))
+    }
+
+    fn load() -> Self {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Self::default(),
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            // never saved, or a crash right between the backup and the rename
+            Err(_) => return Self::load_backup(&path),
+        };
+
+        match Self::decode(&path, content.as_ref()) {
+            Some(store) => store,
+            None => {
+                // keep the broken file aside, the next save would overwrite it
+                fs::rename(&path, sibling(&path, CORRUPT)).unwrap_or(());
+                Self::load_backup(&path)
+            }
+        }
+    }
+
+    fn load_backup(path: &Path) -> Self {
+        let backup = sibling(path, BACKUP);
+        fs::read_to_string(&backup)
+            .ok()
+            .and_then(|content| Self::decode(&backup, content.as_ref()))
+            .unwrap_or_default()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let path = Self::path().ok_or(io::ErrorKind::NotFound)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut value = serde_json::to_value(self)?;
+        if let Some(object) = value.as_object_mut() {
+            object.insert(VERSION_KEY.to_string(), Self::VERSION.into());
+        }
+        write_atomic(&path, serde_json::to_string(&value)?)
+    }
+
+    // another process may have changed the file since it was loaded,
+    // so the change is made on a fresh load under the lock
+    fn update<R>(f: impl FnOnce(&mut Self) -> R) -> io::Result<(Self, R)> {
+        with_lock(|| {
+            let mut store = Self::load();
+            let result = f(&mut store);
+            store.save()?;
+            Ok((store, result))
+        })
+    }
+}
+
+static VERSION_KEY: &str = "_version";
+
+// shared by every process using the data directory
+fn lock_path(name: &str) -> Option<PathBuf> {
+    let dir = data_dir()?;
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(name))
+}
+
+// held while a store is loaded, changed and saved
+pub fn with_lock<R>(f: impl FnOnce() -> R) -> R {
+    match lock_path("data.lock") {
+        Some(path) => file::with_lock(&path, f),
+        None => f(),
+    }
+}
+
+// only one TUI writes the data, WouldBlock when another one is running
+pub fn lock_instance() -> io::Result<File> {
+    let file = lock_path("instance.lock")
+        .and_then(|path| file::lock_file(&path))
+        .ok_or(io::ErrorKind::NotFound)?;
+    file.try_lock()?;
+    Ok(file)
+}
+
+static BACKUP: &str = ".bak";
+static CORRUPT: &str = ".corrupt";
+
+// suffix after the whole file name, so read_position.json.bak isn't picked up as a store
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+// written to a temp file and renamed, a crash never leaves a half written file,
+// the replaced file is kept as the last good backup
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if path.exists() {
+        fs::copy(path, sibling(path, BACKUP))?;
+    }
+    file::write_atomic(path, contents)
+}
+
+pub fn thread_key(url: &str) -> Option<String> {
+    let param = PostPageUrlParameter::try_from(url.to_string()).ok()?;
+    Some(format!("{}-{}", param.board_id, param.id))
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ReadPosition {
+    pub page: u16,
+    pub floor: u16,
+    pub offset: usize,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct ReadPositionStore {
+    pub positions: HashMap<String, ReadPosition>,
+}
+
+impl Store for ReadPositionStore {
+    const NAME: &'static str = "read_position";
+}
+
+impl ReadPositionStore {
+    pub fn get(&self, url: &str) -> Option<ReadPosition> {
+        self.positions.get(&thread_key(url)?).copied()
+    }
+
+    pub fn set(&mut self, url: &str, position: ReadPosition) {
+        if let Some(key) = thread_key(url) {
+            self.positions.insert(key, position);
+        }
+    }
+}
+
+// translated titles by a hash of the language and the title
+#[derive(Default, Serialize, Deserialize)]
+pub struct TranslationStore {
+    pub titles: HashMap<String, String>,
+}
+
+impl Store for TranslationStore {
+    const NAME: &'static str = "translation";
+    const VERSION: u32 = 2;
+
+    // version 1 keys came from a hasher that changes between rust releases, so
+    // they can't be found again, the titles are translated again when shown
+    fn migrate(version: u32, mut value: Value) -> Option<Value> {
+        if version == 1 {
+            value["titles"] = Value::Object(Default::default());
+        }
+        Some(value)
+    }
+}
+
+impl TranslationStore {
+    fn key(language: &str, title: &str) -> String {
+        let hash = content_hash(&[language.to_owned(), title.to_owned()]);
+        format!("{:016x}", hash)
+    }
+
+    pub fn get(&self, language: &str, title: &str) -> Option<&str> {
+        self.titles
+            .get(&TranslationStore::key(language, title))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, language: &str, title: &str, translated: String) {
+        self.titles
+            .insert(TranslationStore::key(language, title), translated);
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct ThreadLinkStore {
+    pub series: Vec<Vec<String>>,
+}
+
+impl Store for ThreadLinkStore {
+    const NAME: &'static str = "thread_link";
+}
+
+impl ThreadLinkStore {
+    fn position(&self, url: &str) -> Option<(usize, usize)> {
+        let key = thread_key(url)?;
+        self.series.iter().enumerate().find_map(|(i, urls)| {
+            urls.iter()
+                .position(|u| thread_key(u).as_ref() == Some(&key))
+                .map(|j| (i, j))
+        })
+    }
+
+    pub fn series_of(&self, url: &str) -> Option<&Vec<String>> {
+        self.position(url).map(|(i, _)| &self.series[i])
+    }
+
+    // first part of the series, or the url itself when it is not linked
+    pub fn head(&self, url: &str) -> String {
+        self.series_of(url)
+            .and_then(|urls| urls.first())
+            .map_or(url.to_string(), |u| u.to_owned())
+    }
+
+    // link next as the continuation of prev
+    pub fn link(&mut self, prev: &str, next: &str) -> bool {
+        if thread_key(prev).is_none() || thread_key(prev) == thread_key(next) {
+            return false;
+        }
+
+        self.unlink(next);
+        match self.position(prev) {
+            Some((i, j)) => self.series[i].insert(j + 1, next.to_string()),
+            None => self.series.push(vec![prev.to_string(), next.to_string()]),
+        }
+        true
+    }
+
+    pub fn unlink(&mut self, url: &str) -> bool {
+        let linked = match self.position(url) {
+            Some((i, j)) => {
+                self.series[i].remove(j);
+                true
+            }
+            None => false,
+        };
+
+        self.series.retain(|urls| urls.len() > 1);
+        linked
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub url: String,
+    pub board: String,
+    pub title: String,
+    pub author: String,
+    pub page: u16,
+    pub floor: u16,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct BookmarkStore {
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl Store for BookmarkStore {
+    const NAME: &'static str = "bookmark";
+}
+
+impl BookmarkStore {
+    fn position(&self, url: &str, floor: u16) -> Option<usize> {
+        let key = thread_key(url)?;
+        self.bookmarks
+            .iter()
+            .position(|b| b.floor == floor && thread_key(b.url.as_ref()).as_ref() == Some(&key))
+    }
+
+    pub fn contains(&self, url: &str, floor: u16) -> bool {
+        self.position(url, floor).is_some()
+    }
+
+    // return true if bookmarked after toggle, newest first
+    pub fn toggle(&mut self, bookmark: Bookmark) -> bool {
+        match self.position(bookmark.url.as_ref(), bookmark.floor) {
+            Some(i) => {
+                self.bookmarks.remove(i);
+                false
+            }
+            None => {
+                self.bookmarks.insert(0, bookmark);
+                true
+            }
+        }
+    }
+
+    pub fn remove(&mut self, url: &str, floor: u16) -> Option<Bookmark> {
+        let i = self.position(url, floor)?;
+        Some(self.bookmarks.remove(i))
+    }
+}
+
+// threads without unread marks and notifications, by thread key
+#[derive(Default, Serialize, Deserialize)]
+pub struct MuteStore {
+    pub threads: HashSet<String>,
+}
+
+impl Store for MuteStore {
+    const NAME: &'static str = "mute";
+}
+
+impl MuteStore {
+    pub fn is_muted(&self, url: &str) -> bool {
+        thread_key(url).is_some_and(|key| self.threads.contains(&key))
+    }
+
+    // return true if muted after toggle
+    pub fn toggle(&mut self, url: &str) -> bool {
+        let key = match thread_key(url) {
+            Some(key) => key,
+            None => return false,
+        };
+
+        if self.threads.remove(&key) {
+            return false;
+        }
+
+        self.threads.insert(key);
+        true
+    }
+}
+
+// board id of a search in the board, None for the board name search
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchQuery {
+    pub query: String,
+    pub board: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub query: String,
+    pub board: String,
+    pub name: String,
+    // post urls of the first result page when last opened
+    #[serde(default)]
+    pub seen: HashSet<String>,
+}
+
+impl SavedSearch {
+    pub fn key(&self) -> String {
+        search_key(self.board.as_ref(), self.query.as_ref())
+    }
+}
+
+pub fn search_key(board: &str, query: &str) -> String {
+    format!("{}/{}", board, query)
+}
+
+static SEARCH_HISTORY_LIMIT: usize = 50;
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct SearchStore {
+    // oldest first
+    pub history: Vec<SearchQuery>,
+    pub saved: Vec<SavedSearch>,
+}
+
+impl Store for SearchStore {
+    const NAME: &'static str = "search";
+}
+
+impl SearchStore {
+    // an old query is moved to the newest
+    pub fn record(&mut self, query: SearchQuery) {
+        self.history.retain(|q| *q != query);
+        self.history.push(query);
+        let over = self.history.len().saturating_sub(SEARCH_HISTORY_LIMIT);
+        self.history.drain(..over);
+    }
+
+    pub fn queries(&self, board: Option<&str>) -> Vec<String> {
+        self.history
+            .iter()
+            .filter(|q| q.board.as_deref() == board)
+            .map(|q| q.query.to_owned())
+            .collect()
+    }
+
+    pub fn is_saved(&self, board: &str, query: &str) -> bool {
+        self.saved
+            .iter()
+            .any(|s| s.board == board && s.query == query)
+    }
+
+    // return true if saved after toggle
+    pub fn toggle_saved(&mut self, search: SavedSearch) -> bool {
+        let len = self.saved.len();
+        self.saved.retain(|s| s.key() != search.key());
+        if self.saved.len() != len {
+            return false;
+        }
+
+        self.saved.push(search);
+        true
+    }
+
+    // return true if changed
+    pub fn mark_seen(&mut self, board: &str, query: &str, urls: HashSet<String>) -> bool {
+        match self
+            .saved
+            .iter_mut()
+            .find(|s| s.board == board && s.query == query && s.seen != urls)
+        {
+            Some(saved) => {
+                saved.seen = urls;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// boards hidden from the board search results, by board id
+#[derive(Default, Serialize, Deserialize)]
+pub struct BoardIgnoreStore {
+    pub boards: HashSet<String>,
+}
+
+impl Store for BoardIgnoreStore {
+    const NAME: &'static str = "board_ignore";
+}
+
+impl BoardIgnoreStore {
+    // return true if ignored after toggle
+    pub fn toggle(&mut self, id: &str) -> bool {
+        if self.boards.remove(id) {
+            return false;
+        }
+
+        self.boards.insert(id.to_string());
+        true
+    }
+}
+
+// user ids, floors of the blocked ones are hidden like the low content floors
+#[derive(Default, Serialize, Deserialize)]
+pub struct UserListStore {
+    pub follow: HashSet<String>,
+    pub block: HashSet<String>,
+}
+
+impl Store for UserListStore {
+    const NAME: &'static str = "user_list";
+}
+
+impl UserListStore {
+    // return true if in the list after toggle
+    fn toggle(list: &mut HashSet<String>, id: &str) -> bool {
+        if list.remove(id) {
+            return false;
+        }
+
+        list.insert(id.to_string());
+        true
+    }
+
+    pub fn toggle_follow(&mut self, id: &str) -> bool {
+        UserListStore::toggle(&mut self.follow, id)
+    }
+
+    pub fn toggle_block(&mut self, id: &str) -> bool {
+        UserListStore::toggle(&mut self.block, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a directory of its own for each test, decode keeps a copy of an old version next to it
+    fn store_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bahamut-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("store.json");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    // names were a list in version 1, by id in version 2 and renamed to users in version 3
+    #[derive(Default, Serialize, Deserialize)]
+    struct UserStore {
+        users: HashMap<String, String>,
+    }
+
+    impl Store for UserStore {
+        const NAME: &'static str = "user";
+        const VERSION: u32 = 3;
+
+        fn migrate(version: u32, mut value: Value) -> Option<Value> {
+            let object = value.as_object_mut()?;
+            match version {
+                1 => {
+                    let names: Vec<String> =
+                        serde_json::from_value(object.remove("names")?).ok()?;
+                    let names: HashMap<String, String> = names
+                        .into_iter()
+                        .map(|name| (name.to_lowercase(), name))
+                        .collect();
+                    object.insert("names".to_string(), serde_json::to_value(names).ok()?);
+                }
+                2 => {
+                    let names = object.remove("names")?;
+                    object.insert("users".to_string(), names);
+                }
+                _ => {}
+            }
+            Some(value)
+        }
+    }
+
+    #[test]
+    fn decode_unversioned() {
+        let content = r#"{"threads":["60076-1"]}"#;
+        let path = store_file("unversioned", content);
+
+        let decoded = MuteStore::decode(&path, content).unwrap();
+        assert!(decoded.threads.contains("60076-1"));
+        assert!(!sibling(&path, ".v1").exists());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn decode_migrates_every_step() {
+        let content = r#"{"names":["Alice"]}"#;
+        let path = store_file("steps", content);
+
+        let decoded = UserStore::decode(&path, content).unwrap();
+        assert_eq!(
+            decoded.users.get("alice").map(String::as_str),
+            Some("Alice")
+        );
+        assert_eq!(fs::read_to_string(sibling(&path, ".v1")).unwrap(), content);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn decode_migrates_from_version() {
+        let content = r#"{"_version":2,"names":{"bob":"Bob"}}"#;
+        let path = store_file("from-version", content);
+
+        let decoded = UserStore::decode(&path, content).unwrap();
+        assert_eq!(decoded.users.get("bob").map(String::as_str), Some("Bob"));
+        assert!(sibling(&path, ".v2").exists());
+        assert!(!sibling(&path, ".v1").exists());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn decode_failed_migration() {
+        let content = r#"{"users":{}}"#;
+        let path = store_file("failed", content);
+
+        assert!(UserStore::decode(&path, content).is_none());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn decode_newer_version() {
+        // fields of the newer format are ignored, the original stays next to it
+        let content = r#"{"_version":2,"threads":["60076-1"],"reasons":{}}"#;
+        let path = store_file("newer", content);
+
+        let decoded = MuteStore::decode(&path, content).unwrap();
+        assert!(decoded.threads.contains("60076-1"));
+        assert_eq!(fs::read_to_string(sibling(&path, ".v2")).unwrap(), content);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn decode_translation() {
+        let mut store = TranslationStore::default();
+        store.insert("en", "標題", "Title".to_string());
+        let mut value = serde_json::to_value(&store).unwrap();
+        value[VERSION_KEY] = TranslationStore::VERSION.into();
+        let content = value.to_string();
+        let path = store_file("translation", &content);
+
+        let decoded = TranslationStore::decode(&path, &content).unwrap();
+        assert_eq!(decoded.get("en", "標題"), Some("Title"));
+        assert!(!sibling(&path, ".v2").exists());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn decode_translation_version_1() {
+        // before the versioning, keys of the old hasher
+        let content = r#"{"titles":{"1234abcd":"Title"}}"#;
+        let path = store_file("translation-v1", content);
+
+        let decoded = TranslationStore::decode(&path, content).unwrap();
+        assert!(decoded.titles.is_empty());
+        assert_eq!(fs::read_to_string(sibling(&path, ".v1")).unwrap(), content);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn decode_invalid_translation() {
+        let path = store_file("translation-invalid", "{");
+        assert!(TranslationStore::decode(&path, "{").is_none());
+        assert!(TranslationStore::decode(&path, r#"{"_version":2,"titles":1}"#).is_none());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,474 @@
+use std::{collections::HashMap, sync::Arc};
+
+use bahamut::api::{
+    board::{BoardPage, BoardPost, BoardPostRef},
+    client::BahamutClient,
+    hash::content_hash,
+    post::{PostPage, PostPageUrlParameter},
+    CachedPage,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::store::{thread_key, MuteStore, ReadPositionStore, Store};
+
+static MAX_SEEN_THREADS: usize = 500;
+
+pub enum SubscriptionEvent {
+    NewFloor {
+        url: String,
+        title: String,
+        count: u16,
+        last_floor: u16,
+    },
+    NewThread {
+        board: String,
+        title: String,
+        url: String,
+    },
+    FloorEdited {
+        url: String,
+        title: String,
+        floors: Vec<u16>,
+    },
+}
+
+impl SubscriptionEvent {
+    pub fn title(&self) -> &str {
+        match self {
+            SubscriptionEvent::NewFloor { title, .. } => title,
+            SubscriptionEvent::NewThread { board, .. } => board,
+            SubscriptionEvent::FloorEdited { title, .. } => title,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            SubscriptionEvent::NewFloor {
+                count, last_floor, ..
+            } => tr!(
+                "{count} 則新回覆 ({floor}樓)",
+                count = count,
+                floor = last_floor
+            ),
+            SubscriptionEvent::NewThread { title, .. } => tr!("新貼文 {title}", title = title),
+            SubscriptionEvent::FloorEdited { floors, .. } => {
+                tr!("{floors} 樓被編輯", floors = floor_list(floors))
+            }
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            SubscriptionEvent::NewFloor { url, .. } => url,
+            SubscriptionEvent::NewThread { url, .. } => url,
+            SubscriptionEvent::FloorEdited { url, .. } => url,
+        }
+    }
+
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            SubscriptionEvent::NewFloor {
+                url,
+                title,
+                count,
+                last_floor,
+            } => vec![
+                ("title", title.to_owned()),
+                ("url", url.to_owned()),
+                ("count", count.to_string()),
+                ("floor", last_floor.to_string()),
+            ],
+            SubscriptionEvent::NewThread { board, title, url } => vec![
+                ("board", board.to_owned()),
+                ("title", title.to_owned()),
+                ("url", url.to_owned()),
+            ],
+            SubscriptionEvent::FloorEdited { url, title, floors } => vec![
+                ("title", title.to_owned()),
+                ("url", url.to_owned()),
+                ("floors", floor_list(floors)),
+            ],
+        }
+    }
+}
+
+fn floor_list(floors: &[u16]) -> String {
+    floors
+        .iter()
+        .map(u16::to_string)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// subscription with unread content
+#[derive(Clone, PartialEq)]
+pub enum Unread {
+    Thread(String),
+    Board(String, String),
+}
+
+// an unread subscription with what's new in it, listed on launch
+pub struct DigestEntry {
+    pub unread: Unread,
+    pub title: String,
+    // new floors of a thread or new threads of a board
+    pub count: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Subscription {
+    pub url: String,
+    pub title: String,
+    pub last_floor: u16,
+    // content hash of the floors on the last page, by floor
+    #[serde(default)]
+    pub hashes: HashMap<u16, u64>,
+}
+
+impl Subscription {
+    pub fn new(url: &str) -> Subscription {
+        Subscription {
+            url: url.to_string(),
+            title: String::new(),
+            last_floor: 0,
+            hashes: HashMap::new(),
+        }
+    }
+
+    pub fn poll(&mut self, client: &Arc<BahamutClient>) -> Vec<SubscriptionEvent> {
+        self.try_poll(client).unwrap_or_default()
+    }
+
+    // fetch the last page of the thread, report floors newer than last_floor
+    // and the floors of the page changed since the last poll
+    fn try_poll(&mut self, client: &Arc<BahamutClient>) -> Option<Vec<SubscriptionEvent>> {
+        let param = PostPageUrlParameter::try_from(self.url.to_owned()).ok()?;
+        let mut page = PostPage::from_param(param, Arc::clone(client));
+        page.init();
+
+        let post = page.get(page.max.max(1), false)?;
+        let last_floor = post.posts.iter().map(|p| p.floor).max()?;
+        if self.title.is_empty() {
+            self.title = post.title.to_owned();
+        }
+
+        let hashes: HashMap<u16, u64> = post
+            .posts
+            .iter()
+            .map(|p| (p.floor, content_hash(&p.desc)))
+            .collect();
+        let mut edited: Vec<u16> = hashes
+            .iter()
+            .filter(|(floor, hash)| self.hashes.get(floor).is_some_and(|old| old != *hash))
+            .map(|(floor, _)| *floor)
+            .collect();
+        edited.sort();
+        self.hashes = hashes;
+
+        let mut events = vec![];
+        let previous = self.last_floor;
+        self.last_floor = last_floor;
+        if previous > 0 && last_floor > previous {
+            events.push(SubscriptionEvent::NewFloor {
+                url: self.url.to_owned(),
+                title: self.title.to_owned(),
+                count: last_floor - previous,
+                last_floor,
+            });
+        }
+        if !edited.is_empty() {
+            events.push(SubscriptionEvent::FloorEdited {
+                url: self.url.to_owned(),
+                title: self.title.to_owned(),
+                floors: edited,
+            });
+        }
+
+        Some(events)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BoardSubscription {
+    pub id: String,
+    pub name: String,
+    pub seen: Vec<String>,
+    // new threads found by poll, cleared when the board is opened
+    #[serde(default)]
+    pub unread: Vec<String>,
+}
+
+impl BoardSubscription {
+    pub fn new(id: &str, name: &str) -> BoardSubscription {
+        BoardSubscription {
+            id: id.to_string(),
+            name: name.to_string(),
+            seen: vec![],
+            unread: vec![],
+        }
+    }
+
+    // fetch the first page of the board and report threads not seen before
+    pub fn poll(&mut self, client: &Arc<BahamutClient>) -> Vec<SubscriptionEvent> {
+        let board = match BoardPage::new(self.id.as_ref(), Arc::clone(client)).get(1, false) {
+            Some(board) => board,
+            None => return vec![],
+        };
+
+        if self.name.is_empty() {
+            self.name = board.name.to_owned();
+        }
+
+        let is_first = self.seen.is_empty();
+        // only the new threads are copied out of the page
+        let posts = board
+            .post_refs()
+            .into_iter()
+            .filter(|post| !self.seen.iter().any(|id| *id == post.id))
+            .map(BoardPostRef::into_owned)
+            .collect::<Vec<BoardPost>>();
+
+        let ids: Vec<String> = posts.iter().map(|post| post.id.to_owned()).collect();
+        self.seen.extend(ids.iter().cloned());
+        let events = posts
+            .into_iter()
+            .map(|post| SubscriptionEvent::NewThread {
+                board: self.name.to_owned(),
+                title: post.title,
+                url: post.url,
+            })
+            .collect::<Vec<SubscriptionEvent>>();
+
+        if self.seen.len() > MAX_SEEN_THREADS {
+            self.seen.drain(..self.seen.len() - MAX_SEEN_THREADS);
+        }
+
+        if is_first {
+            return vec![];
+        }
+
+        self.unread.extend(ids);
+        if self.unread.len() > MAX_SEEN_THREADS {
+            self.unread.drain(..self.unread.len() - MAX_SEEN_THREADS);
+        }
+        events
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionStore {
+    pub threads: HashMap<String, Subscription>,
+    #[serde(default)]
+    pub boards: HashMap<String, BoardSubscription>,
+}
+
+impl Store for SubscriptionStore {
+    const NAME: &'static str = "subscription";
+}
+
+impl SubscriptionStore {
+    pub fn is_subscribed(&self, url: &str) -> bool {
+        thread_key(url).is_some_and(|key| self.threads.contains_key(&key))
+    }
+
+    pub fn is_board_subscribed(&self, id: &str) -> bool {
+        self.boards.contains_key(id)
+    }
+
+    // return true if subscribed after toggle
+    pub fn toggle(&mut self, url: &str, title: &str, last_floor: u16) -> bool {
+        let key = match thread_key(url) {
+            Some(key) => key,
+            None => return false,
+        };
+
+        if self.threads.remove(&key).is_some() {
+            return false;
+        }
+
+        self.threads.insert(
+            key,
+            Subscription {
+                url: url.to_string(),
+                title: title.to_string(),
+                last_floor,
+                hashes: HashMap::new(),
+            },
+        );
+        true
+    }
+
+    // return true if subscribed after toggle
+    pub fn toggle_board(&mut self, id: &str, name: &str) -> bool {
+        if self.boards.remove(id).is_some() {
+            return false;
+        }
+
+        self.boards
+            .insert(id.to_string(), BoardSubscription::new(id, name));
+        true
+    }
+
+    pub fn mark_board_read(&mut self, id: &str) -> bool {
+        match self.boards.get_mut(id) {
+            Some(board) if !board.unread.is_empty() => {
+                board.unread.clear();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // followed threads with floors after the read position first, then boards with new threads
+    pub fn unread(&self, read: &ReadPositionStore, muted: &MuteStore) -> Vec<Unread> {
+        let mut threads: Vec<&Subscription> = self
+            .threads
+            .values()
+            .filter(|sub| !muted.is_muted(sub.url.as_ref()))
+            .filter(|sub| sub.last_floor > read.get(sub.url.as_ref()).map_or(0, |p| p.floor))
+            .collect();
+        threads.sort_by(|a, b| a.url.cmp(&b.url));
+
+        let mut boards: Vec<&BoardSubscription> = self
+            .boards
+            .values()
+            .filter(|board| !board.unread.is_empty())
+            .collect();
+        boards.sort_by(|a, b| a.id.cmp(&b.id));
+
+        threads
+            .into_iter()
+            .map(|sub| Unread::Thread(sub.url.to_owned()))
+            .chain(
+                boards
+                    .into_iter()
+                    .map(|board| Unread::Board(board.id.to_owned(), board.name.to_owned())),
+            )
+            .collect()
+    }
+
+    // everything found by watch since last read, in the order of unread
+    pub fn digest(&self, read: &ReadPositionStore, muted: &MuteStore) -> Vec<DigestEntry> {
+        self.unread(read, muted)
+            .into_iter()
+            .filter_map(|unread| {
+                let (title, count) = match &unread {
+                    Unread::Thread(url) => {
+                        let sub = self.threads.get(&thread_key(url)?)?;
+                        let floor = read.get(url.as_ref()).map_or(0, |p| p.floor);
+                        (
+                            sub.title.to_owned(),
+                            sub.last_floor.saturating_sub(floor) as usize,
+                        )
+                    }
+                    Unread::Board(id, name) => (name.to_owned(), self.boards.get(id)?.unread.len()),
+                };
+                Some(DigestEntry {
+                    unread,
+                    title,
+                    count,
+                })
+            })
+            .collect()
+    }
+
+    pub fn poll(&mut self, client: &Arc<BahamutClient>) -> Vec<SubscriptionEvent> {
+        let threads = self.threads.values_mut().flat_map(|sub| sub.poll(client));
+        let boards = self.boards.values_mut().flat_map(|sub| sub.poll(client));
+        threads.chain(boards).collect()
+    }
+
+    // results of a poll on the copy `before`, into the store changed by the TUI meanwhile,
+    // subscriptions removed meanwhile stay removed
+    pub fn merge_polled(&mut self, before: &SubscriptionStore, polled: &SubscriptionStore) {
+        for (key, thread) in self.threads.iter_mut() {
+            if let Some(p) = polled.threads.get(key) {
+                thread.last_floor = thread.last_floor.max(p.last_floor);
+                thread.hashes = p.hashes.clone();
+                if thread.title.is_empty() {
+                    thread.title = p.title.to_owned();
+                }
+            }
+        }
+
+        for (id, board) in self.boards.iter_mut() {
+            let p = match polled.boards.get(id) {
+                Some(p) => p,
+                None => continue,
+            };
+
+            if board.name.is_empty() {
+                board.name = p.name.to_owned();
+            }
+            board.seen = p.seen.clone();
+
+            // only the threads found by this poll, the board may be read meanwhile
+            let old = before.boards.get(id).map_or(&[][..], |b| b.unread.as_ref());
+            let found = p.unread.iter().filter(|t| !old.contains(t)).cloned();
+            board.unread.extend(found);
+            if board.unread.len() > MAX_SEEN_THREADS {
+                board.unread.drain(..board.unread.len() - MAX_SEEN_THREADS);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(threads: &[(&str, u16)], boards: &[(&str, &[&str])]) -> SubscriptionStore {
+        let mut store = SubscriptionStore::default();
+        for (url, last_floor) in threads {
+            let mut thread = Subscription::new(url);
+            thread.last_floor = *last_floor;
+            store.threads.insert(url.to_string(), thread);
+        }
+        for (id, unread) in boards {
+            let mut board = BoardSubscription::new(id, "");
+            board.unread = unread.iter().map(|t| t.to_string()).collect();
+            store.boards.insert(id.to_string(), board);
+        }
+        store
+    }
+
+    #[test]
+    fn merge_polled_floors() {
+        let before = store(&[("a", 10), ("b", 5)], &[]);
+        let mut polled = before.clone();
+        polled.threads.get_mut("a").unwrap().last_floor = 12;
+        polled.threads.get_mut("a").unwrap().title = "A".to_string();
+
+        // b read further by the TUI meanwhile
+        let mut current = store(&[("a", 10), ("b", 8)], &[]);
+        current.merge_polled(&before, &polled);
+        assert_eq!(current.threads["a"].last_floor, 12);
+        assert_eq!(current.threads["a"].title, "A");
+        assert_eq!(current.threads["b"].last_floor, 8);
+    }
+
+    #[test]
+    fn merge_polled_keeps_removed() {
+        let before = store(&[("a", 10)], &[("60076", &[])]);
+        let mut polled = before.clone();
+        polled.threads.get_mut("a").unwrap().last_floor = 12;
+        polled.boards.get_mut("60076").unwrap().unread = vec!["1".to_string()];
+
+        let mut current = SubscriptionStore::default();
+        current.merge_polled(&before, &polled);
+        assert!(current.threads.is_empty());
+        assert!(current.boards.is_empty());
+    }
+
+    #[test]
+    fn merge_polled_unread_threads() {
+        let before = store(&[], &[("60076", &["1"])]);
+        let mut polled = before.clone();
+        polled.boards.get_mut("60076").unwrap().unread = vec!["1".to_string(), "2".to_string()];
+
+        // the board was opened meanwhile, so 1 is read and only 2 is new
+        let mut current = store(&[], &[("60076", &[])]);
+        current.merge_polled(&before, &polled);
+        assert_eq!(current.boards["60076"].unread, vec!["2"]);
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    error::Error,
+    io::Write,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use bahamut::api::post::Post;
+use serde_json::{json, Value};
+
+use crate::config::SummaryConfig;
+
+// the topic and as many of the latest floors as fit, since catching up is about the recent ones
+pub fn thread_text(post: &Post, max_chars: usize) -> String {
+    let floors: Vec<String> = post
+        .posts
+        .iter()
+        .map(|content| format!("#{} {}: {}", content.floor, content.user.id, content.text()))
+        .collect();
+    let (first, rest) = match floors.split_first() {
+        Some(split) => split,
+        None => return String::new(),
+    };
+
+    let mut used = first.chars().count();
+    let mut latest = vec![];
+    for floor in rest.iter().rev() {
+        used += floor.chars().count();
+        if used > max_chars {
+            break;
+        }
+        latest.push(floor.as_str());
+    }
+    let skipped = rest.len() > latest.len();
+    latest.reverse();
+
+    let mut text = vec![format!("{}\n{}", post.title, first)];
+    if skipped {
+        text.push("...".to_string());
+    }
+    text.extend(latest.into_iter().map(str::to_string));
+    text.join("\n\n")
+}
+
+// the command reads the thread on stdin and prints the summary
+fn run_command(config: &SummaryConfig, text: &str) -> Result<String, Box<dyn Error>> {
+    let mut child = Command::new(&config.command)
+        .args(&config.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{}: {}", config.command, error.trim()).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// chat completions of an OpenAI compatible server, the key is read from the environment
+async fn request_endpoint(config: &SummaryConfig, text: &str) -> Result<String, Box<dyn Error>> {
+    let endpoint = config.endpoint.trim_end_matches('/');
+    let url = if endpoint.ends_with("/chat/completions") {
+        endpoint.to_string()
+    } else {
+        format!("{}/chat/completions", endpoint)
+    };
+    let body = json!({
+        "model": config.model,
+        "messages": [
+            { "role": "system", "content": config.prompt },
+            { "role": "user", "content": text },
+        ],
+    });
+
+    let mut req = reqwest::Client::new()
+        .post(url)
+        .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+        .json(&body);
+    if let Ok(key) = std::env::var(&config.api_key_env) {
+        req = req.bearer_auth(key);
+    }
+    let res: Value = req.send().await?.error_for_status()?.json().await?;
+    let summary = res["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or("no summary in the response")?;
+    Ok(summary.trim().to_string())
+}
+
+// titles one a line, the same number of lines is expected back
+pub async fn translate(config: SummaryConfig, titles: Vec<String>) -> Result<Vec<String>, String> {
+    let output = summarize(config, titles.join("\n")).await?;
+    let lines: Vec<String> = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    if lines.len() != titles.len() {
+        return Err(format!(
+            "expected {} translated lines, got {}",
+            titles.len(),
+            lines.len()
+        ));
+    }
+    Ok(lines)
+}
+
+pub async fn summarize(config: SummaryConfig, text: String) -> Result<String, String> {
+    if !config.command.is_empty() {
+        return tokio::task::spawn_blocking(move || {
+            run_command(&config, &text).map_err(|e| e.to_string())
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+    }
+
+    request_endpoint(&config, &text)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,8 @@
+// replace {name} placeholders in template with the given values
+pub fn fill_template(template: &str, fields: &[(&str, String)]) -> String {
+    fields
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(format!("{{{}}}", name).as_str(), value)
+        })
+}
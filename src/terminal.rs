@@ -0,0 +1,119 @@
+use std::{
+    env,
+    io::{self, stdout},
+    process::{Command, ExitStatus},
+};
+
+use crossterm::{
+    cursor::Show,
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+pub fn supports_color(mode: ColorMode) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+                return false;
+            }
+
+            if env::var("TERM").is_ok_and(|term| term == "dumb") {
+                return false;
+            }
+
+            supports_ansi()
+        }
+    }
+}
+
+// OSC 8 is ignored by most terminals without it, but some print it as text
+pub fn supports_hyperlinks(mode: ColorMode) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            let var = |key: &str| env::var(key).unwrap_or_default();
+            let program = var("TERM_PROGRAM");
+            let term = var("TERM");
+            matches!(
+                program.as_str(),
+                "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+            ) || ["kitty", "alacritty", "foot", "wezterm", "ghostty"]
+                .iter()
+                .any(|t| term.contains(t))
+                || var("VTE_VERSION").parse::<u32>().is_ok_and(|v| v >= 5000)
+                || env::var_os("WT_SESSION").is_some()
+                || env::var_os("KITTY_WINDOW_ID").is_some()
+        }
+    }
+}
+
+// images are drawn with the kitty graphics protocol, there's no way to ask without reading stdin
+pub fn supports_graphics(mode: ColorMode) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            let var = |key: &str| env::var(key).unwrap_or_default();
+            matches!(var("TERM_PROGRAM").as_str(), "WezTerm" | "ghostty")
+                || ["kitty", "ghostty"].iter().any(|t| var("TERM").contains(t))
+                || env::var_os("KITTY_WINDOW_ID").is_some()
+        }
+    }
+}
+
+#[cfg(windows)]
+fn supports_ansi() -> bool {
+    crossterm::ansi_support::supports_ansi()
+}
+
+#[cfg(not(windows))]
+fn supports_ansi() -> bool {
+    true
+}
+
+// give the terminal back to the shell, undone by `resume`
+pub fn release() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(stdout(), LeaveAlternateScreen, Show)
+}
+
+pub fn resume() -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)
+}
+
+// stop like a ctrl-z in the shell, which isn't sent as a signal in raw mode,
+// returns after SIGCONT
+#[cfg(unix)]
+pub fn suspend() {
+    // SAFETY: raise only sends the signal to this process
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn suspend() {}
+
+pub fn shell() -> io::Result<ExitStatus> {
+    #[cfg(unix)]
+    let shell = env::var("SHELL").unwrap_or("sh".to_string());
+    #[cfg(not(unix))]
+    let shell = env::var("COMSPEC").unwrap_or("cmd".to_string());
+
+    Command::new(shell).status()
+}
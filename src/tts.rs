@@ -0,0 +1,67 @@
+use std::{
+    io::{self, Write},
+    process::{Child, Command, Stdio},
+    thread,
+};
+
+use crate::config::TtsConfig;
+
+#[derive(Default)]
+pub struct Speaker {
+    child: Option<Child>,
+}
+
+impl Speaker {
+    pub fn is_playing(&mut self) -> bool {
+        match self.child.as_mut().map(|c| c.try_wait()) {
+            Some(Ok(None)) => true,
+            Some(_) => {
+                self.child = None;
+                false
+            }
+            None => false,
+        }
+    }
+
+    pub fn play(&mut self, config: &TtsConfig, text: &str) -> io::Result<()> {
+        self.stop();
+
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        // a long floor can fill the pipe before the speaker reads it, so don't block the ui on it
+        if let Some(mut stdin) = child.stdin.take() {
+            let text = text.to_owned();
+            thread::spawn(move || stdin.write_all(text.as_bytes()).unwrap_or(()));
+        }
+
+        self.child = Some(child);
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            child.kill().unwrap_or(());
+            child.wait().ok();
+        }
+    }
+
+    pub fn toggle(&mut self, config: &TtsConfig, text: &str) -> io::Result<()> {
+        if self.is_playing() {
+            self.stop();
+            Ok(())
+        } else {
+            self.play(config, text)
+        }
+    }
+}
+
+impl Drop for Speaker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
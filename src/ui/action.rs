@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::state::Page;
+
+// named actions for :map, :do and [keymap], the handler of the page runs them
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    Down,
+    Up,
+    Left,
+    Right,
+    Open,
+    Back,
+    Quit,
+    Refresh,
+    NextFloor,
+    PreviousFloor,
+    Top,
+    Comments,
+    Images,
+    Attachments,
+    Summary,
+    Speak,
+    Diff,
+    Outline,
+    LineNumbers,
+    Select,
+    Find,
+    ClearFind,
+    Participants,
+    Reply,
+    Conversation,
+    ShowHidden,
+    Subscribe,
+    Mute,
+    Bookmark,
+    ParentBoard,
+    Category,
+    Filter,
+    UnreadFirst,
+    ModerationLog,
+    Translate,
+    Keywords,
+    Cache,
+    Bookmarks,
+    Activity,
+    Anime,
+    News,
+    Timeline,
+    Metrics,
+    NextUnread,
+    Command,
+    Suspend,
+}
+
+struct ActionDef {
+    action: Action,
+    name: &'static str,
+    key: &'static str,
+    // pages having the action, every page when empty
+    pages: &'static [Page],
+}
+
+macro_rules! actions {
+    ($($action:ident, $name:expr => $key:literal $(on [$($page:ident),*])?);* $(;)?) => {
+        &[$(ActionDef {
+            action: Action::$action,
+            name: $name,
+            key: $key,
+            pages: &[$($(Page::$page),*)?],
+        }),*]
+    };
+}
+
+static ACTIONS: &[ActionDef] = actions![
+    Down, "down" => "j";
+    Up, "up" => "k";
+    Left, "left" => "h";
+    Right, "right" => "l";
+    Open, "open" => "<Enter>";
+    Back, "back" => "<Backspace>";
+    Quit, "quit" => "q";
+    Refresh, "refresh" => "r";
+    NextFloor, "next_floor" => "]" on [Post];
+    PreviousFloor, "previous_floor" => "[" on [Post];
+    Top, "top" => "<Home>" on [Post];
+    Comments, "comments" => "o" on [Post];
+    Images, "images" => "i" on [Post];
+    Attachments, "attachments" => "a" on [Post];
+    Summary, "summary" => "S" on [Post];
+    Speak, "speak" => "s" on [Post];
+    Diff, "diff" => "d" on [Post];
+    Outline, "outline" => "t" on [Post];
+    LineNumbers, "line_numbers" => "#" on [Post];
+    Select, "select" => "V" on [Post];
+    Find, "find" => "/" on [Post];
+    ClearFind, "clear_find" => "<Esc>" on [Post];
+    Participants, "participants" => "p" on [Post];
+    Reply, "reply" => "R" on [Post];
+    Conversation, "conversation" => "c" on [Post];
+    ShowHidden, "show_hidden" => "H" on [Post];
+    Subscribe, "subscribe" => "w" on [Board, Post];
+    Mute, "mute" => "M" on [Post];
+    Bookmark, "bookmark" => "b" on [Post];
+    ParentBoard, "parent_board" => "g" on [Post];
+    Category, "category" => "c" on [Board];
+    Filter, "filter" => "f" on [Board];
+    UnreadFirst, "unread_first" => "u" on [Board];
+    ModerationLog, "moderation_log" => "L" on [Board];
+    Translate, "translate" => "t" on [Board, Timeline];
+    Keywords, "keywords" => "K" on [Board];
+    Cache, "cache" => "C";
+    Bookmarks, "bookmarks" => "B";
+    Activity, "activity" => "P";
+    Anime, "anime" => "A";
+    News, "news" => "N";
+    Timeline, "timeline" => "T";
+    Metrics, "metrics" => "<F12>";
+    NextUnread, "next_unread" => "<Tab>";
+    Command, "command" => ":";
+    Suspend, "suspend" => "<ctrl-z>";
+];
+
+impl Action {
+    fn def(self) -> &'static ActionDef {
+        ACTIONS
+            .iter()
+            .find(|def| def.action == self)
+            .expect("action without a definition")
+    }
+
+    pub fn name(self) -> &'static str {
+        self.def().name
+    }
+
+    pub fn parse(name: &str) -> Option<Action> {
+        ACTIONS
+            .iter()
+            .find(|def| def.name == name)
+            .map(|def| def.action)
+    }
+
+    // the key it's bound to without any mapping
+    pub fn key(self) -> KeyEvent {
+        parse_key(self.def().key).expect("invalid default key of action")
+    }
+
+    pub fn is_on(self, page: Page) -> bool {
+        let pages = self.def().pages;
+        pages.is_empty() || pages.contains(&page)
+    }
+
+    // the action of an unmapped key, actions of other pages can share the key
+    pub fn at(event: &KeyEvent, page: Page) -> Option<Action> {
+        let id = key_id(event);
+        ACTIONS
+            .iter()
+            .filter(|def| def.action.is_on(page))
+            .find(|def| parse_key(def.key).is_some_and(|key| key_id(&key) == id))
+            .map(|def| def.action)
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        ACTIONS.iter().map(|def| def.name).collect()
+    }
+}
+
+impl Serialize for Action {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Action::parse(&name).ok_or_else(|| de::Error::custom(format!("unknown action: {}", name)))
+    }
+}
+
+static NAMED_KEYS: &[(&str, KeyCode)] = &[
+    ("Enter", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Tab", KeyCode::Tab),
+    ("BackTab", KeyCode::BackTab),
+    ("Backspace", KeyCode::Backspace),
+    ("Space", KeyCode::Char(' ')),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Delete", KeyCode::Delete),
+];
+
+// a char like J, or <Enter>, <F5>, <ctrl-f> and <alt-j> in the notation of the help
+pub fn parse_key(key: &str) -> Option<KeyEvent> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+
+    let inner = key.strip_prefix('<')?.strip_suffix('>')?;
+    let (modifiers, name) = match inner.split_once('-') {
+        Some(("ctrl", name)) => (KeyModifiers::CONTROL, name),
+        Some(("alt", name)) => (KeyModifiers::ALT, name),
+        _ => (KeyModifiers::NONE, inner),
+    };
+
+    let mut chars = name.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) if modifiers != KeyModifiers::NONE => KeyCode::Char(c),
+        _ => match name.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+            Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+            _ => NAMED_KEYS
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, code)| *code)?,
+        },
+    };
+
+    Some(KeyEvent::new(code, modifiers))
+}
+
+fn key_name(code: KeyCode, modifiers: KeyModifiers) -> String {
+    let name = match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) if modifiers == KeyModifiers::NONE => return c.to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        code => NAMED_KEYS
+            .iter()
+            .find(|(_, c)| *c == code)
+            .map_or("?".to_string(), |(name, _)| name.to_string()),
+    };
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        format!("<ctrl-{}>", name)
+    } else if modifiers.contains(KeyModifiers::ALT) {
+        format!("<alt-{}>", name)
+    } else {
+        format!("<{}>", name)
+    }
+}
+
+// shift is part of the char already, and not reported the same by every terminal
+fn key_id(event: &KeyEvent) -> (KeyCode, KeyModifiers) {
+    let modifiers = match event.code {
+        KeyCode::Char(_) => event.modifiers - KeyModifiers::SHIFT,
+        _ => event.modifiers,
+    };
+    (event.code, modifiers)
+}
+
+// keys mapped to actions, not recursive like noremap of vim
+#[derive(Default)]
+pub struct KeyMap {
+    keys: HashMap<(KeyCode, KeyModifiers), Action>,
+}
+
+impl KeyMap {
+    // invalid entries are returned as errors and the rest are still mapped
+    pub fn from_config(config: &HashMap<String, String>) -> (KeyMap, Vec<String>) {
+        let mut keymap = KeyMap::default();
+        let errors = config
+            .iter()
+            .filter_map(|(key, action)| keymap.map(key, action).err())
+            .collect();
+        (keymap, errors)
+    }
+
+    pub fn map(&mut self, key: &str, action: &str) -> Result<(), String> {
+        let event = parse_key(key).ok_or(tr!("無效的按鍵: {key}", key = key))?;
+        let action = Action::parse(action).ok_or(tr!("未知的動作: {action}", action = action))?;
+        self.keys.insert(key_id(&event), action);
+        Ok(())
+    }
+
+    pub fn unmap(&mut self, key: &str) -> bool {
+        parse_key(key).is_some_and(|event| self.keys.remove(&key_id(&event)).is_some())
+    }
+
+    // the mapped action with its default key, or the action the key has on the page
+    pub fn resolve(&self, event: KeyEvent, page: Page) -> (Option<Action>, KeyEvent) {
+        match self.keys.get(&key_id(&event)) {
+            Some(action) => (
+                Some(*action),
+                KeyEvent {
+                    kind: event.kind,
+                    ..action.key()
+                },
+            ),
+            None => (Action::at(&event, page), event),
+        }
+    }
+
+    // keys mapped to the action, in the order of the keys
+    pub fn keys_of(&self, action: Action) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .keys
+            .iter()
+            .filter(|(_, a)| **a == action)
+            .map(|((code, modifiers), _)| key_name(*code, *modifiers))
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    pub fn is_mapped(&self, event: &KeyEvent) -> bool {
+        self.keys.contains_key(&key_id(event))
+    }
+
+    // as key=action, in the order of the keys
+    pub fn list(&self) -> Vec<String> {
+        let mut list: Vec<String> = self
+            .keys
+            .iter()
+            .map(|((code, modifiers), action)| {
+                format!("{}={}", key_name(*code, *modifiers), action.name())
+            })
+            .collect();
+        list.sort();
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static PAGES: &[Page] = &[
+        Page::Search,
+        Page::Board,
+        Page::Post,
+        Page::Comment,
+        Page::Cache,
+        Page::Anime,
+        Page::News,
+        Page::Article,
+        Page::Creation,
+        Page::CreationArticle,
+        Page::Bookmark,
+        Page::Activity,
+        Page::Moderation,
+        Page::Timeline,
+        Page::Settings,
+    ];
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Option<KeyEvent> {
+        Some(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn parse_key_notation() {
+        assert_eq!(parse_key("J"), key(KeyCode::Char('J'), KeyModifiers::NONE));
+        assert_eq!(
+            parse_key("<Enter>"),
+            key(KeyCode::Enter, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("<enter>"),
+            key(KeyCode::Enter, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("<Space>"),
+            key(KeyCode::Char(' '), KeyModifiers::NONE)
+        );
+        assert_eq!(parse_key("<F5>"), key(KeyCode::F(5), KeyModifiers::NONE));
+        assert_eq!(
+            parse_key("<ctrl-f>"),
+            key(KeyCode::Char('f'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse_key("<alt-j>"),
+            key(KeyCode::Char('j'), KeyModifiers::ALT)
+        );
+        assert_eq!(
+            parse_key("<ctrl-Home>"),
+            key(KeyCode::Home, KeyModifiers::CONTROL)
+        );
+    }
+
+    #[test]
+    fn parse_key_invalid() {
+        assert_eq!(parse_key(""), None);
+        assert_eq!(parse_key("jk"), None);
+        assert_eq!(parse_key("<F13>"), None);
+        assert_eq!(parse_key("<Nope>"), None);
+        assert_eq!(parse_key("<ctrl-f"), None);
+    }
+
+    #[test]
+    fn default_keys_are_valid() {
+        for def in ACTIONS {
+            assert!(parse_key(def.key).is_some(), "invalid key of {}", def.name);
+        }
+    }
+
+    #[test]
+    fn default_keys_unique_on_page() {
+        for (i, page) in PAGES.iter().enumerate() {
+            let defs: Vec<&ActionDef> = ACTIONS.iter().filter(|d| d.action.is_on(*page)).collect();
+            for (j, a) in defs.iter().enumerate() {
+                for b in &defs[j + 1..] {
+                    assert!(
+                        key_id(&a.action.key()) != key_id(&b.action.key()),
+                        "{} and {} share {} on page {}",
+                        a.name,
+                        b.name,
+                        a.key,
+                        i
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_mapped_key() {
+        let mut keymap = KeyMap::default();
+        keymap.map("<ctrl-n>", "next_floor").unwrap();
+
+        let event = parse_key("<ctrl-n>").unwrap();
+        let (action, resolved) = keymap.resolve(event, Page::Post);
+        assert_eq!(action, Some(Action::NextFloor));
+        assert_eq!(resolved, Action::NextFloor.key());
+    }
+
+    #[test]
+    fn resolve_shared_key_by_page() {
+        let keymap = KeyMap::default();
+        let event = parse_key("c").unwrap();
+        assert_eq!(
+            keymap.resolve(event, Page::Post).0,
+            Some(Action::Conversation)
+        );
+        assert_eq!(keymap.resolve(event, Page::Board).0, Some(Action::Category));
+        assert_eq!(keymap.resolve(event, Page::Search).0, None);
+    }
+
+    #[test]
+    fn map_invalid_entries() {
+        let config = HashMap::from([
+            ("<ctrl-n>".to_string(), "next_floor".to_string()),
+            ("<Nope>".to_string(), "next_floor".to_string()),
+            ("x".to_string(), "nope".to_string()),
+        ]);
+        let (keymap, errors) = KeyMap::from_config(&config);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(keymap.keys_of(Action::NextFloor), vec!["<ctrl-n>"]);
+    }
+}
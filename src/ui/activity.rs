@@ -0,0 +1,154 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
+};
+
+use super::state::ActivityPageState;
+
+static WEEKDAYS: [&str; 7] = ["  ", "一", "  ", "三", "  ", "五", "  "];
+
+// dates of bahamut are in taiwan time
+static TZ_OFFSET: i64 = 8 * 60 * 60;
+
+// days since 1970-01-01 of a civil date
+pub fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+// 0 is sunday, 1970-01-01 is thursday
+pub fn weekday(days: i64) -> i64 {
+    (days + 4).rem_euclid(7)
+}
+
+// the day of dates like 2023-09-10 12:00 or 2023/09/10
+pub fn parse_day(date: &str) -> Option<i64> {
+    let mut numbers = date
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<i64>());
+
+    let y = numbers.next()?.ok()?;
+    let m = numbers.next()?.ok()?;
+    let d = numbers.next()?.ok()?;
+    if y < 1000 || !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+    Some(days_from_civil(y, m, d))
+}
+
+pub fn today() -> i64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    (secs + TZ_OFFSET).div_euclid(24 * 60 * 60)
+}
+
+fn level_style(count: usize) -> Style {
+    let color = match count {
+        0 => Color::DarkGray,
+        1 => Color::Green,
+        2..=3 => Color::LightGreen,
+        _ => Color::LightYellow,
+    };
+    Style::default().fg(color)
+}
+
+pub struct ActivityPageUI;
+
+impl ActivityPageUI {
+    fn heatmap(area: Rect, state: &ActivityPageState) -> Vec<Line<'static>> {
+        // two cells for each week after the weekday labels
+        let weeks = (area.width.saturating_sub(4) / 2).clamp(1, 53) as i64;
+        let first = state.today - weekday(state.today) - (weeks - 1) * 7;
+
+        (0..7)
+            .map(|row| {
+                let mut spans = vec![Span::raw(tr!(WEEKDAYS[row as usize])), Span::raw(" ")];
+                spans.extend((0..weeks).map(|col| {
+                    let day = first + col * 7 + row;
+                    if day > state.today {
+                        return Span::raw("  ");
+                    }
+
+                    let count = state.counts.get(&day).copied().unwrap_or(0);
+                    let mark = if count == 0 { "· " } else { "■ " };
+                    let mut style = level_style(count);
+                    if day == state.selected {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    Span::styled(mark, style)
+                }));
+                Line::from(spans)
+            })
+            .collect()
+    }
+}
+
+impl StatefulWidget for ActivityPageUI {
+    type State = ActivityPageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let layout = Layout::default()
+            .constraints([Constraint::Length(10), Constraint::Min(0)])
+            .horizontal_margin(1)
+            .split(area);
+
+        let block = Block::default()
+            .title(tr!(
+                "{owner} 的發文活動 - 共 {count} 篇創作",
+                owner = state.owner,
+                count = state.items.len()
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+        let inner = block.inner(layout[0]);
+        block.render(layout[0], buf);
+
+        let (y, m, d) = civil_from_days(state.selected);
+        let mut lines = ActivityPageUI::heatmap(inner, state);
+        lines.push(Line::from(tr!(
+            "{date}  {count} 篇",
+            date = format!("{}-{:02}-{:02}", y, m, d),
+            count = state.day_items().len()
+        )));
+        Paragraph::new(lines).render(inner, buf);
+
+        let items: Vec<ListItem> = state
+            .day_items()
+            .into_iter()
+            .map(|item| ListItem::new(Line::from(format!("{}  {}", item.title, item.date))))
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(tr!("當天的創作"))
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        StatefulWidget::render(list, layout[1], buf, &mut state.state);
+    }
+}
@@ -0,0 +1,57 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, StatefulWidget, Widget},
+};
+
+use super::state::{AnimePageState, AnimeTab};
+
+pub struct AnimePageUI;
+
+impl StatefulWidget for AnimePageUI {
+    type State = AnimePageState;
+
+    fn render(
+        self,
+        area: ratatui::layout::Rect,
+        buf: &mut ratatui::buffer::Buffer,
+        state: &mut Self::State,
+    ) {
+        let layout = Layout::default()
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .horizontal_margin(1)
+            .split(area);
+
+        let title = match state.tab {
+            AnimeTab::NewEpisode => tr!("動畫瘋 - 新上架"),
+            AnimeTab::Schedule => tr!("動畫瘋 - 每週新番"),
+        };
+        let block = Block::default()
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+
+        let items: Vec<ListItem> = state
+            .items
+            .iter()
+            .map(|item| {
+                ListItem::new(Line::from(format!(
+                    "[{}] {} {}",
+                    item.date, item.name, item.episode
+                )))
+            })
+            .collect();
+
+        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(selected_style);
+        StatefulWidget::render(list, layout[0], buf, &mut state.state);
+
+        Block::default()
+            .title(tr!("<Tab> 切換新上架/每週新番  <Enter> 在瀏覽器開啟"))
+            .title_alignment(Alignment::Center)
+            .render(layout[1], buf);
+    }
+}
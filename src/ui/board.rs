@@ -1,15 +1,88 @@
+use bahamut::api::board::{split_prefix, BoardPost};
 use ratatui::{
-    layout::{Alignment, Constraint, Layout},
-    style::{Modifier, Style},
-    text::Line,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, StatefulWidget, Widget},
 };
 
-use super::state::BoardPageState;
+use crate::{config::ThemeConfig, store::TranslationStore};
 
-pub struct BoardPageUI;
+use super::{hyperlink::Hyperlink, state::BoardPageState};
 
-impl StatefulWidget for BoardPageUI {
+static PREFIX_COLORS: &[Color] = &[
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+];
+
+pub struct BoardPageUI<'a> {
+    pub subscribed: bool,
+    pub theme: ThemeConfig,
+    // first floor snippet as the second row of an item
+    pub preview: bool,
+    // translations and their language when the titles are translated
+    pub translation: Option<(&'a TranslationStore, &'a str)>,
+}
+
+impl<'a> BoardPageUI<'a> {
+    // configured color of the prefix, or one picked by the name so it stays the same
+    fn prefix_color(&self, prefix: &str) -> Option<Color> {
+        match self.theme.categories.get(prefix) {
+            Some(color) => color.parse().ok(),
+            None => {
+                let hash = prefix.chars().map(|c| c as usize).sum::<usize>();
+                Some(PREFIX_COLORS[hash % PREFIX_COLORS.len()])
+            }
+        }
+    }
+
+    fn title<'b>(&self, item: &'b BoardPost) -> Line<'b>
+    where
+        'a: 'b,
+    {
+        if item.deleted {
+            let style = Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::CROSSED_OUT);
+            return Line::styled(item.title.as_str(), style);
+        }
+
+        let mut spans = vec![];
+        if item.pinned {
+            spans.push(Span::styled(
+                tr!("[置頂] "),
+                Style::default().fg(Color::LightRed),
+            ));
+        }
+
+        let translated = self
+            .translation
+            .and_then(|(store, language)| store.get(language, &item.title));
+        if let Some(translated) = translated {
+            spans.push(Span::raw(translated));
+            return Line::from(spans);
+        }
+
+        match split_prefix(item.title.as_ref()) {
+            Some((tag, name)) => {
+                let rest = &item.title.trim_start()[tag.len()..];
+                let style = self
+                    .prefix_color(name)
+                    .map_or(Style::default(), |color| Style::default().fg(color));
+                spans.push(Span::styled(tag, style));
+                spans.push(Span::raw(rest));
+            }
+            None => spans.push(Span::raw(item.title.as_str())),
+        }
+        Line::from(spans)
+    }
+}
+
+impl StatefulWidget for BoardPageUI<'_> {
     type State = BoardPageState;
 
     fn render(
@@ -23,22 +96,120 @@ impl StatefulWidget for BoardPageUI {
             .horizontal_margin(1)
             .split(area);
 
+        let mark = if self.subscribed { "★ " } else { "" };
+        let query = state
+            .query
+            .as_ref()
+            .map_or(String::new(), |q| tr!(" - 搜尋「{query}」", query = q));
+        let prefix = state
+            .prefix
+            .as_ref()
+            .map_or(String::new(), |p| tr!(" - 分類「{prefix}」", prefix = p));
+        let filter = state
+            .filter
+            .as_ref()
+            .map_or(String::new(), |f| tr!(" - 篩選「{filter}」", filter = f));
+        let order = if state.unread_first {
+            tr!(" - 未讀優先")
+        } else {
+            ""
+        };
+        let translated = if self.translation.is_some() {
+            tr!(" - 翻譯")
+        } else {
+            ""
+        };
         let block = Block::default()
-            .title(format!("{} - 第{}頁", state.name, state.page))
+            .title(format!(
+                "{}{}{}{}{}{}{} - {}",
+                mark,
+                state.name,
+                query,
+                prefix,
+                filter,
+                order,
+                translated,
+                tr!("第{page}頁", page = state.page)
+            ))
             .title_alignment(Alignment::Center)
             .borders(Borders::ALL);
 
+        let preview_style = Style::default().fg(Color::DarkGray);
         let items: Vec<ListItem> = state
             .items
             .iter()
-            .map(|item| ListItem::new(vec![Line::from(item.title.as_ref())]))
+            .map(|item| {
+                if !self.preview {
+                    return ListItem::new(vec![self.title(item)]);
+                }
+                // same height as the rest, links are placed by rows
+                if item.deleted {
+                    return ListItem::new(vec![self.title(item), Line::default()]);
+                }
+
+                let snippet = match state.previews.get(&item.url) {
+                    Some(Some(snippet)) => snippet.as_str(),
+                    _ => "...",
+                };
+                ListItem::new(vec![
+                    self.title(item),
+                    Line::styled(format!("  {}", snippet), preview_style),
+                ])
+            })
             .collect();
 
+        // keyword panel
+        let list_area = if let Some(mut keyword_state) = state.keyword.clone() {
+            let pane = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Percentage(30)])
+                .split(layout[0]);
+
+            let keywords: Vec<ListItem> = state
+                .keywords
+                .iter()
+                .map(|k| ListItem::new(Line::from(format!("{} ({})", k.word, k.count))))
+                .collect();
+
+            let list = List::new(keywords)
+                .block(
+                    Block::default()
+                        .title(tr!("熱門關鍵字"))
+                        .borders(Borders::ALL),
+                )
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+            StatefulWidget::render(list, pane[1], buf, &mut keyword_state);
+            pane[0]
+        } else {
+            layout[0]
+        };
+
         let selected_style = Style::default().add_modifier(Modifier::REVERSED);
         let list = List::new(items)
             .block(block)
             .highlight_style(selected_style);
-        StatefulWidget::render(list, layout[0], buf, &mut state.state);
+        StatefulWidget::render(list, list_area, buf, &mut state.state);
+
+        let rows = list_area.height.saturating_sub(2) as usize;
+        let shown = if self.preview { rows / 2 } else { rows };
+        let offset = state.state.offset();
+        state.visible = offset..(offset + shown).min(state.items.len());
+
+        let height = if self.preview { 2 } else { 1 };
+        let width = list_area.width.saturating_sub(2);
+        state.links = state
+            .items
+            .get(state.visible.clone())
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| !item.deleted)
+            .map(|(row, item)| {
+                let y = list_area.y + 1 + (row * height) as u16;
+                let title = self.title(item).width().min(width as usize) as u16;
+                Hyperlink::new(list_area.x + 1, y, title, item.url.to_owned())
+            })
+            .collect();
 
         Block::default()
             .title(format!("<- {} / {} ->", state.page, state.last_page))
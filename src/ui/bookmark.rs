@@ -0,0 +1,50 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
+};
+
+use super::state::BookmarkPageState;
+
+pub struct BookmarkPageUI;
+
+impl StatefulWidget for BookmarkPageUI {
+    type State = BookmarkPageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title(tr!("樓層書籤"))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+
+        if state.items.is_empty() {
+            Paragraph::new(tr!("還沒有書籤，在貼文頁面按 b 加入目前樓層"))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let items: Vec<ListItem> = state
+            .items
+            .iter()
+            .map(|b| {
+                ListItem::new(vec![
+                    Line::from(format!("[{}] {}", b.board, b.title)),
+                    Line::from(tr!(
+                        "  #{floor}樓  {author}  第{page}頁",
+                        floor = b.floor,
+                        author = b.author,
+                        page = b.page
+                    )),
+                ])
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        StatefulWidget::render(list, area, buf, &mut state.state);
+    }
+}
@@ -0,0 +1,95 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
+};
+
+use super::state::CachePageState;
+
+pub struct CachePageUI {
+    pub prune_days: u64,
+}
+
+pub fn human_size(size: u64) -> String {
+    let size = size as f64;
+    if size >= 1024.0 * 1024.0 {
+        format!("{:.1} MB", size / 1024.0 / 1024.0)
+    } else if size >= 1024.0 {
+        format!("{:.1} KB", size / 1024.0)
+    } else {
+        format!("{} B", size)
+    }
+}
+
+impl StatefulWidget for CachePageUI {
+    type State = CachePageState;
+
+    fn render(
+        self,
+        area: ratatui::layout::Rect,
+        buf: &mut ratatui::buffer::Buffer,
+        state: &mut Self::State,
+    ) {
+        let layout = Layout::default()
+            .constraints([
+                Constraint::Length(5),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .horizontal_margin(1)
+            .split(area);
+
+        let stats = &state.stats;
+        Paragraph::new(vec![
+            Line::from(tr!(
+                "快取大小: {size} / {max} (壓縮前 {raw})",
+                size = human_size(stats.size),
+                max = human_size(stats.max_size),
+                raw = human_size(stats.raw_size)
+            )),
+            Line::from(tr!("快取項目: {count} 筆", count = stats.entries)),
+            Line::from(tr!(
+                "命中率: {rate}% ({hits} / {total})",
+                rate = format!("{:.1}", stats.hit_rate() * 100.0),
+                hits = stats.hits,
+                total = stats.hits + stats.misses
+            )),
+        ])
+        .block(
+            Block::default()
+                .title(tr!("快取管理"))
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL),
+        )
+        .render(layout[0], buf);
+
+        let items: Vec<ListItem> = stats
+            .boards
+            .iter()
+            .map(|b| {
+                ListItem::new(Line::from(tr!(
+                    "看板 {board}  {count} 筆  {size}  命中 {hits} 次",
+                    board = b.board,
+                    count = b.entries,
+                    size = human_size(b.size),
+                    hits = b.hits
+                )))
+            })
+            .collect();
+
+        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+        let list = List::new(items)
+            .block(Block::default().title(tr!("看板")).borders(Borders::ALL))
+            .highlight_style(selected_style);
+        StatefulWidget::render(list, layout[1], buf, &mut state.state);
+
+        Block::default()
+            .title(tr!(
+                "d 清除看板快取  D 清除全部快取  p 清除超過 {days} 天的快取",
+                days = self.prune_days
+            ))
+            .title_alignment(Alignment::Center)
+            .render(layout[2], buf);
+    }
+}
@@ -1,41 +1,91 @@
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Span, Line},
+    text::{Line, Span},
     widgets::{Block, Paragraph, StatefulWidget, Widget, Wrap},
 };
 
+use bahamut::api::post::PostComment;
+
+use crate::config::ThemeConfig;
+
 use super::state::PostCommentState;
 
-pub struct CommentPageUI;
+pub struct CommentPageUI {
+    pub theme: ThemeConfig,
+}
+
+pub fn comment_lines<'a>(comments: &'a [PostComment], theme: &ThemeConfig) -> Vec<Line<'a>> {
+    let name_style = Style::default().add_modifier(Modifier::REVERSED);
+    let floor_style = Style::default().fg(Color::White);
+
+    comments
+        .iter()
+        .flat_map(|comment| {
+            vec![
+                Line::from(vec![
+                    Span::styled::<String>(format!("B{} ", comment.floor), floor_style),
+                    Span::styled::<&str>(
+                        comment.nick.as_ref(),
+                        theme
+                            .poster_color(comment.userid.as_ref())
+                            .map_or(name_style, |color| name_style.fg(color)),
+                    ),
+                    Span::from(format!(": {}", comment.content)),
+                ]),
+                Line::from(""),
+            ]
+        })
+        .collect()
+}
 
 impl StatefulWidget for CommentPageUI {
     type State = PostCommentState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let layout = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        let area = layout[1];
         state.scroll_size(area.height as usize);
 
-        let name_style = Style::default().add_modifier(Modifier::REVERSED);
-        let floor_style = Style::default().fg(Color::White);
-        let items: Vec<Line> = state
-            .items
-            .iter()
-            .skip(state.offset)
-            .flat_map(|comment| {
-                vec![
-                    Line::from(vec![
-                        Span::styled::<String>(format!("B{} ", comment.floor), floor_style),
-                        Span::styled::<&str>(comment.nick.as_ref(), name_style),
-                        Span::from(format!(": {}", comment.content)),
-                    ]),
-                    Line::from(""),
-                ]
-            })
-            .collect();
+        let title = tr!(
+            "{filter} {count}/{total} 則",
+            filter = state.filter.label(),
+            count = state.items.len(),
+            total = state.all_items.len()
+        );
+        Paragraph::new(Line::styled(
+            title,
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .render(layout[0], buf);
+
+        if state.summary {
+            let lines: Vec<Line> = state
+                .commenters()
+                .into_iter()
+                .skip(state.offset)
+                .map(|(id, nick, count)| {
+                    Line::from(tr!(
+                        "{nick} ({id}): {count} 則",
+                        nick = nick,
+                        id = id,
+                        count = count
+                    ))
+                })
+                .collect();
+            Paragraph::new(lines).render(area, buf);
+            return;
+        }
+
+        let items = comment_lines(state.items.get(state.offset..).unwrap_or(&[]), &self.theme);
 
         if items.is_empty() {
-            Block::default().title("此篇沒有任何留言").render(area, buf);
+            Block::default()
+                .title(tr!("此篇沒有任何留言"))
+                .render(area, buf);
         } else {
             Paragraph::new(items)
                 .wrap(Wrap { trim: true })
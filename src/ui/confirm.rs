@@ -0,0 +1,58 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::clipboard::CopiedLink;
+
+#[derive(Clone, PartialEq)]
+pub enum ConfirmAction {
+    Quit,
+    // clear cache of a board, or all cache when None
+    ClearCache(Option<String>),
+    // send the gated request again with the age cookie
+    AgeGate,
+    // thread or board url found in the clipboard on launch
+    OpenCopied(CopiedLink),
+}
+
+pub struct Confirm {
+    pub message: String,
+    pub action: ConfirmAction,
+}
+
+impl Confirm {
+    pub fn new(action: ConfirmAction, message: &str) -> Confirm {
+        Confirm {
+            message: message.to_string(),
+            action,
+        }
+    }
+}
+
+pub struct ConfirmDialog<'a>(pub &'a Confirm);
+
+impl Widget for ConfirmDialog<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = Block::default()
+            .title(tr!("確認"))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bg(Color::Gray).fg(Color::Black))
+            .borders(Borders::ALL);
+
+        Paragraph::new(vec![
+            Line::from(self.0.message.as_ref()),
+            Line::from(""),
+            Line::from(tr!("(y) 確定  (n) 取消")),
+        ])
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(block)
+        .render(area, buf);
+    }
+}
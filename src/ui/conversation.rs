@@ -0,0 +1,70 @@
+use bahamut::api::post::{Post, ReplyNode};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Widget,
+    },
+};
+
+static SNIPPET_LENGTH: usize = 40;
+
+pub struct ConversationPopup<'a> {
+    pub nodes: &'a [ReplyNode],
+    pub post: &'a Post,
+}
+
+impl StatefulWidget for ConversationPopup<'_> {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Clear.render(area, buf);
+        let block = Block::default()
+            .title(tr!("對話串 {count} 則", count = self.nodes.len()))
+            .borders(Borders::ALL);
+
+        if self.nodes.is_empty() {
+            Paragraph::new(tr!("已載入的樓層沒有互相引用的回覆"))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let floor_style = Style::default().fg(Color::DarkGray);
+        let items: Vec<ListItem> = self
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let post = self.post.posts.iter().find(|p| p.floor == node.floor)?;
+                let text = post
+                    .desc
+                    .iter()
+                    .map(|s| s.trim())
+                    .find(|s| !s.is_empty())
+                    .unwrap_or_default();
+                let snippet: String = text.chars().take(SNIPPET_LENGTH).collect();
+                let indent = if node.depth == 0 {
+                    String::new()
+                } else {
+                    format!("{}└ ", "  ".repeat(node.depth - 1))
+                };
+                Some(ListItem::new(Line::from(vec![
+                    Span::raw(indent),
+                    Span::styled(format!("#{} ", node.floor), floor_style),
+                    Span::styled(
+                        post.user.id.as_str(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(format!(": {}", snippet)),
+                ])))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        StatefulWidget::render(list, area, buf, state);
+    }
+}
@@ -0,0 +1,52 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+// vim style count prefix, e.g. 5j 3] 2gt
+#[derive(Default)]
+pub struct CountState {
+    count: Option<u16>,
+    g: bool,
+}
+
+impl CountState {
+    // pending count or prefix shown in the status line
+    pub fn pending(&self) -> Option<String> {
+        let count = self.count.map_or(String::new(), |c| c.to_string());
+        let g = if self.g { "g" } else { "" };
+        let pending = format!("{}{}", count, g);
+        (!pending.is_empty()).then_some(pending)
+    }
+
+    // return the key to dispatch with the repeat count, None if it's consumed
+    // digits are left to the page when it uses them, and g to pages without tabs
+    pub fn handle(&mut self, event: KeyEvent, digits: bool, tabs: bool) -> Option<(u16, KeyEvent)> {
+        if self.g {
+            self.g = false;
+            let count = self.count.take().unwrap_or(1);
+            // gt / gT move between tabs
+            let code = match event.code {
+                KeyCode::Char('t') => KeyCode::Tab,
+                KeyCode::Char('T') => KeyCode::BackTab,
+                _ => return Some((count, event)),
+            };
+            return Some((count, KeyEvent::new(code, KeyModifiers::NONE)));
+        }
+
+        match event.code {
+            KeyCode::Char(c @ '0'..='9') if digits && (c != '0' || self.count.is_some()) => {
+                let digit = c as u16 - '0' as u16;
+                let count = self.count.unwrap_or(0).saturating_mul(10) + digit;
+                self.count = Some(count.min(999));
+                None
+            }
+            KeyCode::Char('g') if tabs && event.modifiers == KeyModifiers::NONE => {
+                self.g = true;
+                None
+            }
+            KeyCode::Esc if self.count.is_some() => {
+                self.count = None;
+                None
+            }
+            _ => Some((self.count.take().unwrap_or(1), event)),
+        }
+    }
+}
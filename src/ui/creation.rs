@@ -0,0 +1,65 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
+};
+
+use super::{post::render_desc, state::CreationPageState};
+
+pub struct CreationPageUI;
+
+impl StatefulWidget for CreationPageUI {
+    type State = CreationPageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let layout = Layout::default()
+            .constraints([Constraint::Min(0)])
+            .horizontal_margin(1)
+            .split(area);
+
+        let block = Block::default()
+            .title(tr!("{owner} 的創作", owner = state.owner))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+
+        let items: Vec<ListItem> = state
+            .items
+            .iter()
+            .map(|item| ListItem::new(Line::from(format!("{}  {}", item.title, item.date))))
+            .collect();
+
+        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(selected_style);
+        StatefulWidget::render(list, layout[0], buf, &mut state.state);
+    }
+}
+
+pub struct CreationArticlePageUI;
+
+impl StatefulWidget for CreationArticlePageUI {
+    type State = CreationPageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Min(0)])
+            .split(area);
+
+        state.scroll_size(layout[1].height as usize);
+
+        let article = &state.article;
+        Paragraph::new(vec![
+            Line::from(article.title.as_ref()),
+            Line::from(article.author.as_ref()),
+            Line::from(article.date.as_ref()),
+        ])
+        .block(Block::default().borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        render_desc(layout[1], buf, &article.desc, state.scroll_offset);
+    }
+}
@@ -0,0 +1,50 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::subscription::{DigestEntry, Unread};
+
+pub struct DigestPopup<'a> {
+    pub entries: &'a [DigestEntry],
+}
+
+impl StatefulWidget for DigestPopup<'_> {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Clear.render(area, buf);
+
+        let count_style = Style::default().fg(Color::Yellow);
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let (kind, count) = match entry.unread {
+                    Unread::Thread(_) => {
+                        (tr!("貼文"), tr!("{count} 樓新回覆", count = entry.count))
+                    }
+                    Unread::Board(..) => {
+                        (tr!("看板"), tr!("{count} 篇新貼文", count = entry.count))
+                    }
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("[{}] {} ", kind, entry.title)),
+                    Span::styled(count, count_style),
+                ]))
+            })
+            .collect();
+
+        let title = tr!(
+            "上次之後的更新 {count} 項 - <Enter> 開啟",
+            count = self.entries.len()
+        );
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        StatefulWidget::render(list, area, buf, state);
+    }
+}
@@ -0,0 +1,53 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Widget},
+};
+
+// key hints of the current screen, like htop
+pub struct Footer {
+    pub hints: Vec<(String, &'static str)>,
+    pub read_only: bool,
+    pub profile: Option<String>,
+    pub data_saver: bool,
+    pub offline: bool,
+}
+
+impl Widget for Footer {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mode = self.read_only.then_some(Span::styled(
+            tr!("[唯讀] "),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        let profile = self.profile.map(|name| {
+            Span::styled(
+                format!("[{}] ", name),
+                Style::default().add_modifier(Modifier::BOLD),
+            )
+        });
+        let saver = self.data_saver.then_some(Span::styled(
+            tr!("[省流] "),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        let offline = self.offline.then_some(Span::styled(
+            tr!("[離線] "),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        let spans: Vec<Span> = profile
+            .into_iter()
+            .chain(mode)
+            .chain(offline)
+            .chain(saver)
+            .chain(self.hints.into_iter().flat_map(|(key, hint)| {
+                vec![
+                    Span::styled(key, Style::default().add_modifier(Modifier::REVERSED)),
+                    Span::raw(format!(" {} ", hint)),
+                ]
+            }))
+            .collect();
+
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+}
@@ -0,0 +1,50 @@
+use ratatui::{buffer::Buffer, layout::Rect, text::Span, widgets::Widget};
+
+use crate::config::TerminalConfig;
+
+// most emoji are outside the basic plane, joiners, variation selectors and keycaps
+// turn other chars into emoji the terminal may draw another width
+fn is_unsupported(symbol: &str) -> bool {
+    symbol.chars().any(|c| {
+        c as u32 > 0xFFFF || matches!(c, '\u{200D}' | '\u{20E3}' | '\u{FE0E}' | '\u{FE0F}')
+    })
+}
+
+// glyphs drawn another width than the layout counted would shift the rest of the line,
+// so they're swapped for the placeholder after everything is rendered
+pub struct GlyphFilter<'a> {
+    pub config: &'a TerminalConfig,
+}
+
+impl GlyphFilter<'_> {
+    fn replaced(&self, symbol: &str, width: usize) -> bool {
+        match self.config.glyph_widths.get(symbol) {
+            Some(columns) => *columns as usize != width,
+            None => self.config.replace_glyphs && is_unsupported(symbol),
+        }
+    }
+}
+
+impl Widget for GlyphFilter<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // the cell after a wide glyph is left blank, so a narrow placeholder keeps the columns,
+        // ambiguous width ones like □ are counted narrow but drawn wide by cjk terminals
+        let placeholder = match Span::raw(self.config.placeholder.as_str()).width() {
+            1 => self.config.placeholder.as_str(),
+            _ => "?",
+        };
+
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                let width = Span::raw(cell.symbol.as_str()).width();
+                if cell.symbol.is_ascii() || width == 0 {
+                    continue;
+                }
+                if self.replaced(cell.symbol.as_str(), width) {
+                    cell.set_symbol(placeholder);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crossterm::{
+    cursor::{MoveTo, RestorePosition, SavePosition},
+    queue, terminal,
+};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageFormat};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, StatefulWidget, Widget},
+};
+
+use super::state::{join_floors, AppState, ImageView, ImageViewer, Page};
+
+// one image at a time, replaced by id
+static IMAGE_ID: u32 = 4801;
+static AVATAR_ID: u32 = 4802;
+static CHUNK_SIZE: usize = 4096;
+
+pub struct ImagePopup<'a> {
+    pub image: Option<&'a Result<Arc<DynamicImage>, String>>,
+    // drawn with half blocks in the buffer instead of the graphics protocol
+    pub mosaic: bool,
+}
+
+impl StatefulWidget for ImagePopup<'_> {
+    type State = ImageViewer;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Clear.render(area, buf);
+        let mut title = tr!(
+            "圖片 {index}/{count} · {zoom}x",
+            index = state.index + 1,
+            count = state.images.len(),
+            zoom = state.zoom
+        );
+        if state.floors().len() > 1 {
+            let floors = join_floors(state.floors());
+            title.push_str(&tr!(" · 出現在 {floors} 樓", floors = floors));
+        }
+        let block = Block::default().title(title).borders(Borders::ALL);
+        state.area = block.inner(area);
+
+        // the image itself is drawn over the empty area after the frame
+        let text = match self.image {
+            Some(Ok(image)) if self.mosaic => {
+                block.render(area, buf);
+                mosaic(image, &state.view(), buf);
+                return;
+            }
+            Some(Ok(_)) => Line::from(""),
+            Some(Err(e)) => Line::styled(
+                tr!("無法顯示圖片: {error}", error = e),
+                Style::default().fg(Color::Red),
+            ),
+            None => Line::from("Loading..."),
+        };
+        Paragraph::new(vec![text, Line::from(state.url())])
+            .block(block)
+            .render(area, buf);
+    }
+}
+
+// draw the image of the viewer, or remove it once the viewer is closed
+pub fn sync<W: Write>(w: &mut W, app: &AppState, shown: &mut Option<ImageView>) -> io::Result<()> {
+    let viewer = app.post.image.as_ref().filter(|_| app.page == Page::Post);
+    let image = viewer.and_then(|v| match app.post.images.get(v.url()) {
+        Some(Some(Ok(image))) => Some((v.view(), image)),
+        _ => None,
+    });
+    let view = image.as_ref().map(|(view, _)| view.clone());
+    if view == *shown {
+        return Ok(());
+    }
+
+    if shown.take().is_some() {
+        write!(w, "\x1b_Ga=d,d=I,i={},q=2\x1b\\", IMAGE_ID)?;
+    }
+    if let Some((view, image)) = image {
+        if view.area.width > 0 && view.area.height > 0 {
+            draw(w, IMAGE_ID, image, &view)?;
+            *shown = Some(view);
+        }
+    }
+    w.flush()
+}
+
+// avatar of the current floor author, hidden under popups
+pub fn sync_avatar<W: Write>(
+    w: &mut W,
+    app: &AppState,
+    shown: &mut Option<(String, Rect)>,
+) -> io::Result<()> {
+    let target = app
+        .post
+        .avatar_area
+        .clone()
+        .filter(|_| app.page == Page::Post && !app.has_overlay());
+    let avatar = target
+        .as_ref()
+        .and_then(|(id, _)| match app.post.avatars.get(id) {
+            Some(Some(Ok(avatar))) => Some(avatar),
+            _ => None,
+        });
+    let target = target.filter(|_| avatar.is_some());
+    if target == *shown {
+        return Ok(());
+    }
+
+    if shown.take().is_some() {
+        write!(w, "\x1b_Ga=d,d=I,i={},q=2\x1b\\", AVATAR_ID)?;
+    }
+    if let (Some((id, area)), Some(avatar)) = (target, avatar) {
+        let view = ImageView {
+            url: String::new(),
+            zoom: 1,
+            center: (500, 500),
+            area,
+        };
+        draw(w, AVATAR_ID, avatar, &view)?;
+        *shown = Some((id, area));
+    }
+    w.flush()
+}
+
+// pixels of a cell, guessed when the terminal doesn't report it
+fn cell_size() -> (u32, u32) {
+    match terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns).max(1) as u32,
+            (size.height / size.rows).max(1) as u32,
+        ),
+        _ => (8, 16),
+    }
+}
+
+// two pixels a cell, the upper one as the foreground of ▀ and the lower one as the background,
+// so a cell is about square and the picture keeps its ratio
+fn mosaic(image: &DynamicImage, view: &ImageView, buf: &mut Buffer) {
+    let area = view.area;
+    let part = visible_part(image, view);
+    let part = part
+        .resize(
+            area.width as u32,
+            area.height as u32 * 2,
+            FilterType::Triangle,
+        )
+        .to_rgb8();
+    let (width, height) = part.dimensions();
+    let x = area.x + (area.width - width as u16) / 2;
+    let y = area.y + (area.height - height.div_ceil(2) as u16) / 2;
+    let rgb = |px: &image::Rgb<u8>| Color::Rgb(px[0], px[1], px[2]);
+    for row in 0..height.div_ceil(2) {
+        for col in 0..width {
+            let top = part.get_pixel(col, row * 2);
+            let bottom = part.get_pixel_checked(col, row * 2 + 1).unwrap_or(top);
+            buf.get_mut(x + col as u16, y + row as u16)
+                .set_symbol("▀")
+                .set_fg(rgb(top))
+                .set_bg(rgb(bottom));
+        }
+    }
+}
+
+// the zoomed in part around the center
+fn visible_part(image: &DynamicImage, view: &ImageView) -> DynamicImage {
+    let (width, height) = image.dimensions();
+    let crop_w = (width / view.zoom).max(1);
+    let crop_h = (height / view.zoom).max(1);
+    let left = (width as u64 * view.center.0 as u64 / 1000) as u32;
+    let top = (height as u64 * view.center.1 as u64 / 1000) as u32;
+    let left = left.saturating_sub(crop_w / 2).min(width - crop_w);
+    let top = top.saturating_sub(crop_h / 2).min(height - crop_h);
+    image.crop_imm(left, top, crop_w, crop_h)
+}
+
+fn draw<W: Write>(w: &mut W, id: u32, image: &DynamicImage, view: &ImageView) -> io::Result<()> {
+    let part = visible_part(image, view);
+    let (crop_w, crop_h) = part.dimensions();
+
+    // fit in the area keeping the ratio, only scaled down here so less is sent
+    let area = view.area;
+    let (cell_w, cell_h) = cell_size();
+    let scale = (area.width as f64 * cell_w as f64 / crop_w as f64)
+        .min(area.height as f64 * cell_h as f64 / crop_h as f64);
+    let fit_w = ((crop_w as f64 * scale) as u32).max(1);
+    let fit_h = ((crop_h as f64 * scale) as u32).max(1);
+    let part = if scale < 1.0 {
+        part.resize_exact(fit_w, fit_h, FilterType::Triangle)
+    } else {
+        part
+    };
+
+    let cols = fit_w.div_ceil(cell_w).clamp(1, area.width as u32) as u16;
+    let rows = fit_h.div_ceil(cell_h).clamp(1, area.height as u32) as u16;
+    let x = area.x + (area.width - cols) / 2;
+    let y = area.y + (area.height - rows) / 2;
+
+    let mut png = io::Cursor::new(vec![]);
+    part.write_to(&mut png, ImageFormat::Png)
+        .map_err(io::Error::other)?;
+    let data = STANDARD.encode(png.into_inner());
+
+    queue!(w, SavePosition, MoveTo(x, y))?;
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(CHUNK_SIZE).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            // q=2 keeps the replies out of stdin, C=1 leaves the cursor alone
+            write!(
+                w,
+                "\x1b_Ga=T,f=100,i={},c={},r={},q=2,C=1,m={};{}\x1b\\",
+                id, cols, rows, more, chunk
+            )?;
+        } else {
+            write!(w, "\x1b_Gm={};{}\x1b\\", more, chunk)?;
+        }
+    }
+    queue!(w, RestorePosition)
+}
@@ -0,0 +1,128 @@
+use bahamut::api::post::{Post, PostContent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+use crate::config::HeaderConfig;
+
+// thread info above the floors of post page
+pub struct ThreadHeader<'a> {
+    pub post: &'a Post,
+    pub current: &'a PostContent,
+    pub page: u16,
+    pub last_page: u16,
+    pub subscribed: bool,
+    pub muted: bool,
+    pub bookmarked: bool,
+    // low content floors skipped
+    pub hidden: usize,
+    // current floor is edited since loaded
+    pub edited: bool,
+    pub config: &'a HeaderConfig,
+}
+
+impl ThreadHeader<'_> {
+    fn title(&self) -> Line<'_> {
+        let mut spans = vec![];
+        if self.config.subscription && self.subscribed {
+            spans.push(Span::raw("★ "));
+        }
+        if self.muted {
+            spans.push(Span::raw(tr!("[靜音] ")));
+        }
+        spans.push(Span::styled(
+            self.post.title.as_str(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+
+        Line::from(spans)
+    }
+
+    fn info(&self) -> Line<'_> {
+        let config = self.config;
+        let mut segments: Vec<String> = vec![];
+
+        if config.board && !self.post.board.is_empty() {
+            segments.push(tr!("看板 {board}", board = self.post.board));
+        }
+
+        if config.author {
+            if let Some(author) = self.post.author.as_ref() {
+                segments.push(tr!("樓主 {name}({id})", name = author.name, id = author.id));
+            }
+        }
+
+        if config.date && !self.post.created.is_empty() {
+            segments.push(tr!("發文 {date}", date = self.post.created));
+        }
+
+        if config.reply {
+            // last floor from url, or the last loaded floor
+            let last = self
+                .post
+                .posts
+                .iter()
+                .map(|p| p.floor)
+                .max()
+                .unwrap_or(0)
+                .max(self.post.floor);
+            segments.push(tr!("回覆 {count}", count = last.saturating_sub(1)));
+        }
+
+        if config.page {
+            segments.push(tr!(
+                "第 {page} / {last} 頁",
+                page = self.page,
+                last = self.last_page.max(1)
+            ));
+        }
+
+        if self.hidden > 0 {
+            segments.push(tr!("已隱藏 {count} 樓 (H 顯示)", count = self.hidden));
+        }
+
+        Line::from(segments.join(" │ "))
+    }
+
+    fn tags(&self) -> Line<'_> {
+        if !self.config.tags {
+            return Line::default();
+        }
+
+        let tags: Vec<String> = self.post.tags.iter().map(|t| format!("#{}", t)).collect();
+        Line::from(tags.join(" "))
+    }
+}
+
+impl Widget for ThreadHeader<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let edited = if self.edited {
+            tr!(" (已編輯，按 d 查看差異)")
+        } else {
+            ""
+        };
+        let bookmark = if self.bookmarked {
+            tr!(" [書籤]")
+        } else {
+            ""
+        };
+        // notices without a floor number of their own
+        let number = match self.current.floor {
+            0 => tr!("公告").to_string(),
+            floor => tr!("{floor}樓", floor = floor),
+        };
+        let floor = Line::from(format!(
+            "{} {}{}{}",
+            number, self.current.date, bookmark, edited
+        ));
+
+        Paragraph::new(vec![self.title(), self.info(), self.tags(), floor])
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL))
+            .render(area, buf);
+    }
+}
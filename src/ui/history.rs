@@ -0,0 +1,49 @@
+use crate::store::ReadPosition;
+
+static MAX_HISTORY: usize = 100;
+
+#[derive(Clone)]
+pub enum HistoryEntry {
+    Search,
+    Board {
+        id: String,
+        name: String,
+        page: u16,
+        query: Option<String>,
+        selected: Option<usize>,
+    },
+    Post {
+        url: String,
+        position: Option<ReadPosition>,
+    },
+    Comment,
+    Cache,
+    Anime,
+    News,
+    Article,
+    Creation,
+    CreationArticle,
+    Bookmark,
+    Activity,
+    Moderation,
+    Timeline,
+    Settings,
+}
+
+#[derive(Default)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn push(&mut self, entry: HistoryEntry) {
+        if self.entries.len() >= MAX_HISTORY {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+
+    pub fn pop(&mut self) -> Option<HistoryEntry> {
+        self.entries.pop()
+    }
+}
@@ -0,0 +1,156 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor::{MoveTo, RestorePosition, SavePosition},
+    queue,
+    style::{
+        Attribute, Color as TermColor, Print, SetAttribute, SetBackgroundColor, SetForegroundColor,
+    },
+};
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::Rect,
+    style::{Color, Modifier},
+    text::Span,
+};
+
+// a clickable part of one row
+#[derive(Clone)]
+pub struct Hyperlink {
+    pub area: Rect,
+    pub url: String,
+}
+
+impl Hyperlink {
+    pub fn new(x: u16, y: u16, width: u16, url: String) -> Hyperlink {
+        Hyperlink {
+            area: Rect::new(x, y, width, 1),
+            url,
+        }
+    }
+}
+
+fn is_url_char(symbol: &str) -> bool {
+    symbol
+        .chars()
+        .all(|c| c.is_ascii_graphic() && !matches!(c, '<' | '>' | '"' | '\''))
+}
+
+fn is_border(symbol: &str) -> bool {
+    symbol
+        .chars()
+        .all(|c| ('\u{2500}'..='\u{257f}').contains(&c))
+}
+
+// urls printed on screen, those cut by a border or the right edge are left out
+// since the rest of them is on the next row
+pub fn find_urls(buf: &Buffer) -> Vec<Hyperlink> {
+    let area = buf.area;
+    let mut links = vec![];
+    for y in area.top()..area.bottom() {
+        let row: Vec<&str> = (area.left()..area.right())
+            .map(|x| buf.get(x, y).symbol.as_str())
+            .collect();
+        let text: String = row
+            .iter()
+            .map(|s| if s.len() == 1 { *s } else { " " })
+            .collect();
+
+        let mut from = 0;
+        while let Some(i) = text[from..].find("http").map(|i| i + from) {
+            let len = row[i..].iter().take_while(|s| is_url_char(s)).count();
+            let end = i + len;
+            let url = text[i..end].trim_end_matches(['.', ',', ')']);
+            let cut = row.get(end).is_none_or(|s| is_border(s));
+            if !cut && (url.starts_with("https://") || url.starts_with("http://")) {
+                links.push(Hyperlink::new(
+                    area.left() + i as u16,
+                    y,
+                    url.len() as u16,
+                    url.to_string(),
+                ));
+            }
+            from = end.max(i + 1);
+        }
+    }
+    links
+}
+
+fn term_color(color: Color) -> TermColor {
+    match color {
+        Color::Reset => TermColor::Reset,
+        Color::Black => TermColor::Black,
+        Color::Red => TermColor::DarkRed,
+        Color::Green => TermColor::DarkGreen,
+        Color::Yellow => TermColor::DarkYellow,
+        Color::Blue => TermColor::DarkBlue,
+        Color::Magenta => TermColor::DarkMagenta,
+        Color::Cyan => TermColor::DarkCyan,
+        Color::Gray => TermColor::Grey,
+        Color::DarkGray => TermColor::DarkGrey,
+        Color::LightRed => TermColor::Red,
+        Color::LightGreen => TermColor::Green,
+        Color::LightYellow => TermColor::Yellow,
+        Color::LightBlue => TermColor::Blue,
+        Color::LightMagenta => TermColor::Magenta,
+        Color::LightCyan => TermColor::Cyan,
+        Color::White => TermColor::White,
+        Color::Rgb(r, g, b) => TermColor::Rgb { r, g, b },
+        Color::Indexed(i) => TermColor::AnsiValue(i),
+    }
+}
+
+fn queue_cell<W: Write>(w: &mut W, cell: &Cell) -> io::Result<()> {
+    queue!(
+        w,
+        SetAttribute(Attribute::Reset),
+        SetForegroundColor(term_color(cell.fg)),
+        SetBackgroundColor(term_color(cell.bg))
+    )?;
+    let modifiers = [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::DIM, Attribute::Dim),
+        (Modifier::ITALIC, Attribute::Italic),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::REVERSED, Attribute::Reverse),
+        (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+    ];
+    for (modifier, attribute) in modifiers {
+        if cell.modifier.contains(modifier) {
+            queue!(w, SetAttribute(attribute))?;
+        }
+    }
+    queue!(w, Print(&cell.symbol))
+}
+
+// ratatui has no hyperlinks, the cells of the links are printed again inside OSC 8
+// with their own style after each draw
+pub fn write<W: Write>(w: &mut W, buf: &Buffer, links: &[Hyperlink]) -> io::Result<()> {
+    if links.is_empty() {
+        return Ok(());
+    }
+
+    queue!(w, SavePosition)?;
+    for link in links {
+        let area = link.area.intersection(buf.area);
+        if area.area() == 0 {
+            continue;
+        }
+
+        queue!(
+            w,
+            MoveTo(area.x, area.y),
+            Print(format!("\x1b]8;;{}\x1b\\", link.url))
+        )?;
+        let mut x = area.left();
+        while x < area.right() {
+            let cell = buf.get(x, area.y);
+            queue_cell(w, cell)?;
+            // the cells after a wide char are covered by it
+            x += Span::raw(cell.symbol.as_str()).width().max(1) as u16;
+        }
+        queue!(w, Print("\x1b]8;;\x1b\\"))?;
+    }
+    queue!(w, SetAttribute(Attribute::Reset), RestorePosition)?;
+    w.flush()
+}
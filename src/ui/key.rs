@@ -1,31 +1,111 @@
-use std::{cell::RefCell, rc::Rc, sync::mpsc::Sender};
+use std::{cell::RefCell, rc::Rc, sync::mpsc::Sender, time::Duration};
 
+use bahamut::api::{creation::CreationItem, post::PostPageUrlParameter};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use tui_input::backend::crossterm::EventHandler;
+use tui_input::{backend::crossterm::EventHandler, Input};
 
-use crate::channel::DataRequestMsg;
+use crate::{
+    browser::open_url,
+    channel::DataRequestMsg,
+    clipboard::{self, CopiedLink},
+    store::ReadPosition,
+    subscription::Unread,
+};
 
-use super::state::{AppState, CursorMoveable, InputMode, Page};
+use super::{
+    action::Action,
+    confirm::{Confirm, ConfirmAction},
+    history::HistoryEntry,
+    state::{AppState, CursorMoveable, InputMode, Page},
+};
 
 #[derive(PartialEq)]
 pub enum KeyBindEvent {
     None,
     Quit,
+    // back to the shell, the terminal is restored until resumed
+    Suspend,
+    Shell,
 }
 
-impl KeyBindEvent {
-    pub fn is_quit(&self) -> bool {
-        *self == KeyBindEvent::Quit
+pub fn handle_key(app: &mut AppState, event: KeyEvent, tx: Sender<DataRequestMsg>) -> KeyBindEvent {
+    if event.kind != KeyEventKind::Press {
+        return KeyBindEvent::None;
+    }
+
+    app.status = None;
+
+    if app.confirm.is_some() {
+        return handle_confirm_key(app, event, tx);
+    }
+
+    if app.digest_state.is_some() {
+        return handle_digest_key(app, event, tx);
+    }
+
+    if app.command.is_some() {
+        return handle_command_key(app, event, tx);
+    }
+
+    if app.post.find_input.is_some() {
+        return handle_find_key(app, event);
+    }
+
+    if app.settings.input.is_some() {
+        return handle_setting_input_key(app, event);
+    }
+
+    if app.board.filter_input.is_some() {
+        return handle_board_filter_key(app, event);
+    }
+
+    if app.search.mode != InputMode::Edit && app.macros.handle(event) {
+        return KeyBindEvent::None;
+    }
+
+    // typed into the input, not mapped
+    if app.search.mode == InputMode::Edit {
+        return dispatch(app, Action::at(&event, app.page), event, tx);
+    }
+
+    let (action, resolved) = app.keymap.resolve(event, app.page);
+    match app
+        .count
+        .handle(resolved, app.page != Page::Article, app.page == Page::Anime)
+    {
+        // gt and gT turn into the tab keys
+        Some((count, next)) if next != resolved => {
+            app.macros.repeat(next, count - 1);
+            dispatch(app, Action::at(&next, app.page), next, tx)
+        }
+        Some((count, _)) => {
+            // the rest are replayed after loading, like macros, and mapped again
+            app.macros.repeat(event, count - 1);
+            dispatch(app, action, resolved, tx)
+        }
+        None => {
+            app.status = app.count.pending();
+            KeyBindEvent::None
+        }
     }
 }
 
-pub fn handle_key(app: &mut AppState, event: KeyEvent, tx: Sender<DataRequestMsg>) -> KeyBindEvent {
-    if event.kind != KeyEventKind::Press {
+// the key after the mapping and the count prefix, with its action on the page
+fn dispatch(
+    app: &mut AppState,
+    action: Option<Action>,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    // the default key of an action from another page does something else here
+    if let Some(action) = action.filter(|a| !a.is_on(app.page)) {
+        app.status(tr!("此頁面沒有動作 {action}", action = action.name()));
         return KeyBindEvent::None;
     }
 
-    if handle_general_key(app, event, tx.clone()).is_quit() {
-        return KeyBindEvent::Quit;
+    match handle_general_key(app, action, event, tx.clone()) {
+        KeyBindEvent::None => {}
+        other => return other,
     }
 
     if app.loading {
@@ -34,16 +114,217 @@ pub fn handle_key(app: &mut AppState, event: KeyEvent, tx: Sender<DataRequestMsg
 
     match app.page {
         Page::Search => handle_search_key(app, event, tx),
-        Page::Board => handle_board_key(app, event, tx),
-        Page::Post => handle_post_key(app, event, tx),
+        Page::Board => handle_board_key(app, action, event, tx),
+        Page::Post => handle_post_key(app, action, event, tx),
         Page::Comment => handle_comment_key(app, event, tx),
+        Page::Cache => handle_cache_key(app, event, tx),
+        Page::Anime => handle_anime_key(app, event, tx),
+        Page::News => handle_news_key(app, event, tx),
+        Page::Article => handle_article_key(app, event, tx),
+        Page::Creation => handle_creation_key(app, event, tx),
+        Page::CreationArticle => handle_creation_article_key(app, event, tx),
+        Page::Bookmark => handle_bookmark_key(app, event, tx),
+        Page::Activity => handle_activity_key(app, event, tx),
+        Page::Moderation => handle_moderation_key(app, event, tx),
+        Page::Timeline => handle_timeline_key(app, action, event, tx),
+        Page::Settings => handle_settings_key(app, event),
+    }
+}
+
+fn handle_confirm_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    let confirm = match app.confirm.take() {
+        Some(confirm) => confirm,
+        None => return KeyBindEvent::None,
+    };
+    let retry = app.age_gate.take();
+
+    match event.code {
+        KeyCode::Char('y' | 'Y') | KeyCode::Enter => match confirm.action {
+            ConfirmAction::Quit => KeyBindEvent::Quit,
+            ConfirmAction::ClearCache(board) => {
+                clear_cache(app, board);
+                KeyBindEvent::None
+            }
+            ConfirmAction::AgeGate => {
+                app.client.confirm_age(&app.config.session.age_gate_cookie);
+                if let Some(retry) = retry {
+                    app.loading = true;
+                    tx.send(retry).unwrap_or(());
+                }
+                KeyBindEvent::None
+            }
+            ConfirmAction::OpenCopied(link) => {
+                open_copied(app, link, tx);
+                KeyBindEvent::None
+            }
+        },
+        _ => KeyBindEvent::None,
+    }
+}
+
+fn open_copied(app: &mut AppState, link: CopiedLink, tx: Sender<DataRequestMsg>) {
+    app.push_history();
+    app.loading = true;
+    match link {
+        CopiedLink::Thread(url) => {
+            app.post.url = url.to_owned();
+            app.post.restore = app.read_position.get(url.as_ref());
+            let page = app.post.restore.map_or(1, |p| p.page);
+            tx.send(DataRequestMsg::PostPage(url, page, true))
+                .unwrap_or(())
+        }
+        CopiedLink::Board(id) => {
+            app.board.id(id.to_owned());
+            app.board.name(id.to_owned());
+            app.board.query = None;
+            tx.send(DataRequestMsg::BoardPage(id, 1, true))
+                .unwrap_or(())
+        }
+    }
+}
+
+fn handle_command_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Esc => app.command = None,
+        KeyCode::Enter => {
+            let command = app.command.take().unwrap_or_default();
+            let value = command.value().trim();
+            let (name, args) = value.split_once(' ').unwrap_or((value, ""));
+            let args: Vec<&str> = args.split_whitespace().collect();
+            match (name, args.as_slice()) {
+                ("", _) => {}
+                ("sh" | "shell", []) => return KeyBindEvent::Shell,
+                ("q" | "quit", []) => return KeyBindEvent::Quit,
+                ("set" | "settings", []) if app.page != Page::Settings => {
+                    app.push_history();
+                    app.open_settings();
+                }
+                ("set" | "settings", []) => {}
+                ("saver", []) => app.toggle_data_saver(),
+                ("map", []) => {
+                    let list = app.keymap.list();
+                    app.status(if list.is_empty() {
+                        tr!("沒有對應的按鍵").to_string()
+                    } else {
+                        list.join(" ")
+                    });
+                }
+                ("map", [key, action]) => match app.keymap.map(key, action) {
+                    Ok(()) => {
+                        app.status(tr!("已對應 {key} 到 {action}", key = key, action = action))
+                    }
+                    Err(e) => app.status(e),
+                },
+                ("unmap", [key]) => {
+                    if !app.keymap.unmap(key) {
+                        app.status(tr!("{key} 沒有對應", key = key));
+                    }
+                }
+                ("do", [name]) => match Action::parse(name) {
+                    Some(action) => return dispatch(app, Some(action), action.key(), tx),
+                    None => app.status(tr!("未知的動作: {action}", action = name)),
+                },
+                ("map" | "do", _) => app.status(tr!(
+                    "可用的動作: {actions}",
+                    actions = Action::names().join(", ")
+                )),
+                _ => app.status(tr!("未知的指令: {command}", command = value)),
+            }
+        }
+        // leave like vim when erased to empty
+        KeyCode::Backspace if app.command.as_ref().is_some_and(|c| c.value().is_empty()) => {
+            app.command = None
+        }
+        _ => {
+            if let Some(command) = app.command.as_mut() {
+                command.handle_event(&Event::Key(event));
+            }
+        }
+    }
+
+    KeyBindEvent::None
+}
+
+fn handle_setting_input_key(app: &mut AppState, event: KeyEvent) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Esc => app.settings.input = None,
+        KeyCode::Enter => {
+            let value = app
+                .settings
+                .input
+                .as_ref()
+                .map(|i| i.value().to_string())
+                .unwrap_or_default();
+            app.save_setting(value.as_ref());
+        }
+        _ => {
+            if let Some(input) = app.settings.input.as_mut() {
+                input.handle_event(&Event::Key(event));
+            }
+        }
+    }
+
+    KeyBindEvent::None
+}
+
+fn handle_find_key(app: &mut AppState, event: KeyEvent) -> KeyBindEvent {
+    let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+    match event.code {
+        KeyCode::Esc => app.post.find_input = None,
+        KeyCode::Enter => match app.post.apply_find() {
+            Ok(Some(count)) => app.status(tr!("已載入的樓層中有 {count} 樓符合", count = count)),
+            Ok(None) => app.status(tr!("已清除貼文內搜尋").to_string()),
+            Err(e) => app.status(e),
+        },
+        KeyCode::Char('r') if ctrl => app.post.find_regex = !app.post.find_regex,
+        KeyCode::Char('s') if ctrl => app.post.find_case = !app.post.find_case,
+        _ => {
+            if let Some(input) = app.post.find_input.as_mut() {
+                input.handle_event(&Event::Key(event));
+            }
+        }
+    }
+
+    KeyBindEvent::None
+}
+
+fn handle_board_filter_key(app: &mut AppState, event: KeyEvent) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Esc => app.board.close_filter(false),
+        KeyCode::Enter => app.board.close_filter(true),
+        KeyCode::Down => app.board.next(),
+        KeyCode::Up => app.board.previous(),
+        _ => {
+            if let Some(input) = app.board.filter_input.as_mut() {
+                input.handle_event(&Event::Key(event));
+            }
+            app.board.update_filter();
+        }
     }
+
+    KeyBindEvent::None
+}
+
+fn clear_cache(app: &mut AppState, board: Option<String>) {
+    let cache = app.client.cache();
+    let count = cache.map_or(0, |cache| cache.clear(board.as_deref()));
+    app.cache.refresh(cache);
+    app.status(tr!("已清除 {count} 筆快取", count = count));
 }
 
 fn handle_general_key(
     app: &mut AppState,
+    action: Option<Action>,
     event: KeyEvent,
-    _: Sender<DataRequestMsg>,
+    tx: Sender<DataRequestMsg>,
 ) -> KeyBindEvent {
     if let KeyEvent {
         code: KeyCode::Char('c'),
@@ -55,38 +336,312 @@ fn handle_general_key(
         return KeyBindEvent::Quit;
     }
 
-    if let KeyCode::Char('q') = event.code {
-        if app.search.mode != InputMode::Edit {
-            match app.page {
-                Page::Search => return KeyBindEvent::Quit,
-                Page::Board => app.page = Page::Search,
-                Page::Post => app.page = Page::Board,
-                Page::Comment => app.page = Page::Post,
+    match action {
+        Some(Action::Suspend) => return KeyBindEvent::Suspend,
+        // also while loading, to see what is slow
+        Some(Action::Metrics) => {
+            app.show_metrics = !app.show_metrics;
+            return KeyBindEvent::None;
+        }
+        _ => (),
+    }
+
+    // a page that never finishes loading can still be left
+    let leaving = matches!(action, Some(Action::Quit | Action::Back));
+    if app.search.mode == InputMode::Edit || (app.loading && !leaving) {
+        return KeyBindEvent::None;
+    }
+    if leaving {
+        app.loading = false;
+    }
+
+    match action {
+        Some(Action::Command) => app.command = Some(Input::default()),
+        Some(Action::Quit) => match app.page {
+            Page::Search => {
+                if !app.config.confirm.quit {
+                    return KeyBindEvent::Quit;
+                }
+
+                app.confirm = Some(Confirm::new(ConfirmAction::Quit, tr!("確定要離開程式嗎?")));
+            }
+            Page::Board => {
+                app.push_history();
+                app.page = Page::Search
+            }
+            Page::Post => {
+                app.push_history();
+                app.save_read_position();
+                app.page = Page::Board
+            }
+            Page::Comment => {
+                app.push_history();
+                app.page = Page::Post
+            }
+            Page::Cache => {
+                app.push_history();
+                app.page = app.cache.back
+            }
+            Page::Anime => {
+                app.push_history();
+                app.page = app.anime.back
+            }
+            Page::News => {
+                app.push_history();
+                app.page = app.news.back
+            }
+            Page::Article => {
+                app.push_history();
+                app.page = Page::News
+            }
+            Page::Creation => {
+                app.push_history();
+                app.page = Page::Post
+            }
+            Page::CreationArticle => {
+                app.push_history();
+                app.page = Page::Creation
+            }
+            Page::Bookmark => {
+                app.push_history();
+                app.page = app.bookmarks.back
+            }
+            Page::Activity => {
+                app.push_history();
+                app.page = app.activity.back
+            }
+            Page::Moderation => {
+                app.push_history();
+                app.page = app.moderation.back
+            }
+            Page::Timeline => {
+                app.push_history();
+                app.page = app.timeline.back
+            }
+            Page::Settings => {
+                app.push_history();
+                app.page = app.settings.back
+            }
+        },
+        Some(Action::Cache) if app.page != Page::Cache => {
+            app.push_history();
+            app.cache.open(app.page, app.client.cache());
+            app.page = Page::Cache
+        }
+        Some(Action::Bookmarks) if app.page != Page::Bookmark => {
+            if let Page::Post = app.page {
+                app.save_read_position();
+            }
+            app.push_history();
+            app.open_bookmarks();
+        }
+        Some(Action::Activity) if app.page != Page::Activity => {
+            match app.client.session_user_id() {
+                Some(owner) => {
+                    app.push_history();
+                    app.activity.back = app.page;
+                    app.activity.owner = owner.to_owned();
+                    app.loading = true;
+                    tx.send(DataRequestMsg::Activity(owner, app.config.activity.pages))
+                        .unwrap_or(());
+                }
+                None => app.status(tr!("請先執行 login 儲存登入 Cookie").to_string()),
+            }
+        }
+        Some(Action::Anime) if app.page != Page::Anime => {
+            app.push_history();
+            app.anime.back = app.page;
+            app.loading = true;
+            tx.send(DataRequestMsg::AnimeHome).unwrap_or(());
+        }
+        Some(Action::Timeline) if app.page != Page::Timeline => {
+            let back = app.page;
+            if app.request_timeline(&tx, false) {
+                app.push_history();
+                app.timeline.back = back;
+                app.loading = true;
+            } else {
+                app.status(tr!("沒有訂閱的看板，在看板列表按 w 訂閱").to_string());
             }
         }
+        Some(Action::News) if !matches!(app.page, Page::News | Page::Article) => {
+            app.push_history();
+            app.news.back = app.page;
+            app.loading = true;
+            tx.send(DataRequestMsg::NewsList).unwrap_or(());
+        }
+        Some(Action::Back) => go_back(app, tx),
+        // anime page uses Tab to switch the list
+        Some(Action::NextUnread)
+            if app.config.navigation.follow_unread && app.page != Page::Anime =>
+        {
+            jump_unread(app, tx)
+        }
+        _ => (),
     };
 
     KeyBindEvent::None
 }
 
+fn handle_digest_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.digest_next(),
+        KeyCode::Char('k') | KeyCode::Up => app.digest_previous(),
+        KeyCode::Enter => {
+            if let Some(unread) = app.take_digest() {
+                open_unread(app, unread, tx);
+            }
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            app.digest_state = None;
+            app.digest.clear();
+        }
+        _ => (),
+    }
+
+    KeyBindEvent::None
+}
+
+fn jump_unread(app: &mut AppState, tx: Sender<DataRequestMsg>) {
+    if let Page::Post = app.page {
+        app.save_read_position();
+    }
+
+    match app.next_unread() {
+        Some(unread) => open_unread(app, unread, tx),
+        None => app.status(tr!("訂閱的貼文與看板都已讀完").to_string()),
+    }
+}
+
+fn open_unread(app: &mut AppState, unread: Unread, tx: Sender<DataRequestMsg>) {
+    match unread {
+        Unread::Thread(url) => {
+            let url = app.thread_link.head(url.as_ref());
+            app.push_history();
+            app.loading = true;
+            app.post.url = url.to_owned();
+            app.post.restore = app.read_position.get(url.as_ref());
+            let page = app.post.restore.map_or(1, |p| p.page);
+            tx.send(DataRequestMsg::PostPage(url, page, true))
+                .unwrap_or(())
+        }
+        Unread::Board(id, name) => {
+            app.push_history();
+            app.loading = true;
+            app.board.id(id.to_owned());
+            app.board.name(name);
+            app.board.query = None;
+            tx.send(DataRequestMsg::BoardPage(id, 1, true))
+                .unwrap_or(())
+        }
+    }
+}
+
+fn go_back(app: &mut AppState, tx: Sender<DataRequestMsg>) {
+    let entry = match app.history.pop() {
+        Some(entry) => entry,
+        None => return,
+    };
+
+    if let Page::Post = app.page {
+        app.save_read_position();
+    }
+
+    match entry {
+        HistoryEntry::Search => app.page = Page::Search,
+        HistoryEntry::Comment => app.page = Page::Comment,
+        HistoryEntry::Cache => {
+            app.cache.refresh(app.client.cache());
+            app.page = Page::Cache
+        }
+        HistoryEntry::Anime => app.page = Page::Anime,
+        HistoryEntry::News => app.page = Page::News,
+        HistoryEntry::Article => app.page = Page::Article,
+        HistoryEntry::Creation => app.page = Page::Creation,
+        HistoryEntry::CreationArticle => app.page = Page::CreationArticle,
+        HistoryEntry::Bookmark => app.open_bookmarks(),
+        HistoryEntry::Activity => app.page = Page::Activity,
+        HistoryEntry::Moderation => app.page = Page::Moderation,
+        HistoryEntry::Timeline => app.page = Page::Timeline,
+        HistoryEntry::Settings => app.page = Page::Settings,
+        HistoryEntry::Board {
+            id,
+            name,
+            page,
+            query,
+            selected,
+        } => {
+            if app.board.id == id && app.board.page == page && app.board.query == query {
+                app.board.select_in_page(selected);
+                app.board.reorder(&app.read_position);
+                app.page = Page::Board;
+            } else {
+                app.loading = true;
+                app.board.id(id);
+                app.board.name(name);
+                app.board.query = query;
+                app.board.restore = selected;
+                tx.send(board_request(app, page, true)).unwrap_or(());
+            }
+        }
+        HistoryEntry::Post { url, position } => {
+            let is_loaded = app.post.url == url;
+            app.post.url = url.to_owned();
+            app.post.restore = position;
+
+            if is_loaded && (position.is_none() || app.post.is_restorable()) {
+                app.post.apply_restore();
+                app.page = Page::Post;
+            } else {
+                app.loading = true;
+                let page = position.map_or(1, |p| p.page);
+                tx.send(DataRequestMsg::PostPage(url, page, true))
+                    .unwrap_or(());
+            }
+        }
+    }
+}
+
 fn handle_search_key(
     app: &mut AppState,
     event: KeyEvent,
     tx: Sender<DataRequestMsg>,
 ) -> KeyBindEvent {
+    if app.search.saved_state.is_some() {
+        return handle_saved_search_key(app, event, tx);
+    }
+
     match app.search.mode {
         InputMode::Normal => match event.code {
             KeyCode::Char('j') | KeyCode::Down => app.search.next(),
             KeyCode::Char('k') | KeyCode::Up => app.search.previous(),
-            KeyCode::Char('a' | 'e' | 'i' | 'o') => app.search.mode(InputMode::Edit),
+            KeyCode::Char('a' | 'e' | 'i' | 'o') => {
+                app.search.recall = None;
+                app.search.mode(InputMode::Edit)
+            }
+            KeyCode::Char('s') => app.search.toggle_saved(app.searches.saved.clone()),
+            KeyCode::Char('x') => app.toggle_board_ignore(),
+            KeyCode::Char('X') => {
+                let status = if app.search.toggle_show_ignored() {
+                    tr!("顯示已忽略的看板")
+                } else {
+                    tr!("隱藏已忽略的看板")
+                };
+                app.status(status.to_string());
+            }
             KeyCode::Enter => {
                 if let Some(i) = app.search.state.selected() {
                     if let Some(board) = app.search.items.get(i) {
+                        app.history.push(HistoryEntry::Search);
                         app.loading = true;
                         app.board.name(board.name.to_owned());
                         app.board.id(board.id.to_owned());
                         tx.send(DataRequestMsg::BoardPage(board.id.to_string(), 1, true))
-                            .map_or((), |_| ());
+                            .unwrap_or(());
                     }
                 }
             }
@@ -96,13 +651,17 @@ fn handle_search_key(
             KeyCode::Esc => app.search.mode(InputMode::Normal),
             KeyCode::Enter => {
                 app.search.mode(InputMode::Normal);
-                let value = app.search.input.value();
+                let value = app.search.input.value().to_string();
                 if !value.is_empty() {
+                    app.record_search(&value, None);
                     app.loading = true;
-                    tx.send(DataRequestMsg::SearchResult(value.into()))
-                        .map_or((), |_| ());
+                    tx.send(DataRequestMsg::SearchResult(value)).unwrap_or(());
                 }
             }
+            KeyCode::Up | KeyCode::Down => {
+                let history = app.searches.queries(None);
+                app.search.recall(&history, event.code == KeyCode::Up);
+            }
             _ => {
                 app.search.input.handle_event(&Event::Key(event));
             }
@@ -112,7 +671,80 @@ fn handle_search_key(
     KeyBindEvent::None
 }
 
+fn handle_saved_search_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.search.saved_next(),
+        KeyCode::Char('k') | KeyCode::Up => app.search.saved_previous(),
+        KeyCode::Char('s') | KeyCode::Esc => app.search.toggle_saved(vec![]),
+        KeyCode::Enter => {
+            if let Some(saved) = app.search.current_saved().cloned() {
+                app.history.push(HistoryEntry::Search);
+                app.loading = true;
+                app.board.name(saved.name);
+                app.board.id(saved.board);
+                app.board.query = Some(saved.query);
+                tx.send(board_request(app, 1, false)).unwrap_or(());
+            }
+        }
+        _ => (),
+    }
+
+    KeyBindEvent::None
+}
+
 fn handle_board_key(
+    app: &mut AppState,
+    action: Option<Action>,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    if app.board.keyword.is_some() {
+        return handle_board_keyword_key(app, event, tx);
+    }
+
+    match action {
+        Some(Action::Subscribe) => app.toggle_board_subscription(),
+        Some(Action::Category) => {
+            app.board.cycle_prefix();
+            if app.board.prefix.is_none() && app.board.prefixes().is_empty() {
+                app.status(tr!("此頁沒有標題分類").to_string());
+            }
+        }
+        Some(Action::Filter) => app.board.open_filter(),
+        Some(Action::UnreadFirst) => app.toggle_unread_first(),
+        Some(Action::Translate) => app.toggle_translation(),
+        Some(Action::ModerationLog) => {
+            app.push_history();
+            app.moderation.back = app.page;
+            app.moderation.name = app.board.name.to_owned();
+            app.loading = true;
+            tx.send(DataRequestMsg::ModerationLog(app.board.id.to_owned()))
+                .unwrap_or(());
+        }
+        Some(Action::Keywords) => {
+            app.board.toggle_keyword();
+            if app.board.keyword.is_some() && app.board.keywords_of != app.board.id {
+                app.loading = true;
+                tx.send(DataRequestMsg::BoardKeywords(
+                    app.board.id.to_owned(),
+                    app.config.keyword.pages,
+                    app.config.keyword.limit,
+                ))
+                .unwrap_or(())
+            }
+        }
+        _ => return handle_board_list_key(app, event, tx),
+    }
+
+    KeyBindEvent::None
+}
+
+// keys moving in the list and between pages
+fn handle_board_list_key(
     app: &mut AppState,
     event: KeyEvent,
     tx: Sender<DataRequestMsg>,
@@ -124,46 +756,43 @@ fn handle_board_key(
             if app.board.page <= 1 {
                 app.board.page(1)
             } else {
+                app.push_history();
                 app.loading = true;
-                tx.send(DataRequestMsg::BoardPage(
-                    app.board.id.to_owned(),
-                    app.board.page - 1,
-                    true,
-                ))
-                .map_or((), |_| ())
+                tx.send(board_request(app, app.board.page - 1, true))
+                    .unwrap_or(())
             }
         }
         KeyCode::Char('l') | KeyCode::Right => {
             if app.board.page >= app.board.last_page {
                 app.board.page(app.board.last_page)
             } else {
+                app.push_history();
                 app.loading = true;
-                tx.send(DataRequestMsg::BoardPage(
-                    app.board.id.to_owned(),
-                    app.board.page + 1,
-                    true,
-                ))
-                .map_or((), |_| ())
+                tx.send(board_request(app, app.board.page + 1, true))
+                    .unwrap_or(())
             }
         }
         KeyCode::Enter => {
             if let Some(v) = app.board.state.selected() {
-                if let Some(post) = app.board.items.get(v) {
+                if app.board.items.get(v).is_some_and(|p| p.deleted) {
+                    app.status(tr!("此文章已被刪除").to_string());
+                } else if let Some(url) = app.board.items.get(v).map(|p| p.url.to_string()) {
+                    let url = app.thread_link.head(url.as_ref());
+                    app.push_history();
                     app.loading = true;
-                    app.post.url = post.url.to_string();
-                    tx.send(DataRequestMsg::PostPage(post.url.to_string(), 1, true))
-                        .map_or((), |_| ())
+                    app.post.url = url.to_owned();
+                    app.post.restore = app.read_position.get(url.as_ref());
+                    let page = app.post.restore.map_or(1, |p| p.page);
+                    tx.send(DataRequestMsg::PostPage(url, page, true))
+                        .unwrap_or(())
                 }
             }
         }
+        KeyCode::Char('S') => app.toggle_saved_search(),
         KeyCode::Char('r') => {
             app.loading = true;
-            tx.send(DataRequestMsg::BoardPage(
-                app.board.id.to_owned(),
-                app.board.page,
-                false,
-            ))
-            .map_or((), |_| ())
+            tx.send(board_request(app, app.board.page, false))
+                .unwrap_or(())
         }
         _ => (),
     }
@@ -171,55 +800,358 @@ fn handle_board_key(
     KeyBindEvent::None
 }
 
-fn handle_post_key(
+fn handle_board_keyword_key(
     app: &mut AppState,
     event: KeyEvent,
     tx: Sender<DataRequestMsg>,
 ) -> KeyBindEvent {
-    let app = Rc::new(RefCell::new(app));
-    let next = |app: &Rc<RefCell<&mut AppState>>| {
-        let mut app = app.borrow_mut();
-        if app.post.next().is_none() && app.post.has_next() {
-            app.loading = true;
-            tx.send(DataRequestMsg::PostPage(
-                app.post.url.to_owned(),
-                app.post.page + 1,
-                false,
-            ))
-            .map_or((), |_| ());
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.board.keyword_next(),
+        KeyCode::Char('k') | KeyCode::Up => app.board.keyword_previous(),
+        KeyCode::Char('K') | KeyCode::Esc => app.board.toggle_keyword(),
+        KeyCode::Enter => {
+            if let Some(keyword) = app.board.current_keyword() {
+                app.push_history();
+                app.loading = true;
+                app.board.keyword = None;
+                app.record_search(&keyword, Some(&app.board.id.to_owned()));
+                app.board.query = Some(keyword);
+                tx.send(board_request(app, 1, true)).unwrap_or(())
+            }
         }
-    };
+        _ => (),
+    }
+
+    KeyBindEvent::None
+}
+
+// board page or search result in the board
+// board pages looked through for the thread opened by g
+pub static PARENT_BOARD_PAGES: u16 = 10;
+
+pub fn board_request(app: &AppState, page: u16, use_cache: bool) -> DataRequestMsg {
+    let id = app.board.id.to_owned();
+    match &app.board.query {
+        Some(query) => DataRequestMsg::BoardSearch(id, query.to_owned(), page, use_cache),
+        None => DataRequestMsg::BoardPage(id, page, use_cache),
+    }
+}
+
+// the site has no api to post, so the filled template is pasted into its reply form
+fn copy_reply(app: &mut AppState) {
+    let reply = match app.post.take_reply() {
+        Some(reply) => reply,
+        None => return,
+    };
+    if let Err(e) = clipboard::copy(&reply) {
+        return app.status(tr!("複製失敗: {error}", error = e));
+    }
+
+    let url = app
+        .post
+        .reply_url()
+        .filter(|_| app.config.reply.open_browser);
+    match url.map(|url| open_url(url.as_ref())) {
+        Some(Err(e)) => app.status(tr!("無法開啟連結: {error}", error = e)),
+        Some(Ok(_)) => app.status(tr!("已複製回覆範本，請貼到開啟的回覆頁面").to_string()),
+        None => app.status(tr!("已複製回覆範本到剪貼簿").to_string()),
+    }
+}
+
+fn handle_post_key(
+    app: &mut AppState,
+    action: Option<Action>,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    if app.post.participants_state.is_some() {
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => app.post.participants_next(),
+            KeyCode::Char('k') | KeyCode::Up => app.post.participants_previous(),
+            KeyCode::Enter | KeyCode::Char('f') => app.post.participant_jump(false),
+            KeyCode::Char('l') => app.post.participant_jump(true),
+            KeyCode::Char('w') => app.toggle_user_list(false),
+            KeyCode::Char('x') => app.toggle_user_list(true),
+            KeyCode::Char('p') | KeyCode::Esc => app.post.toggle_participants(),
+            _ => (),
+        };
+
+        return KeyBindEvent::None;
+    }
+
+    if app.post.reply_state.is_some() {
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => app.post.reply_next(),
+            KeyCode::Char('k') | KeyCode::Up => app.post.reply_previous(),
+            KeyCode::Enter => copy_reply(app),
+            KeyCode::Char('R') | KeyCode::Esc => app.post.reply_state = None,
+            _ => (),
+        };
+
+        return KeyBindEvent::None;
+    }
+
+    if let Some(summary) = app.summary.as_mut() {
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => summary.scroll += 1,
+            KeyCode::Char('k') | KeyCode::Up => summary.scroll = summary.scroll.saturating_sub(1),
+            KeyCode::Char('y') => {
+                if let Some(Ok(text)) = summary.result.as_ref() {
+                    match clipboard::copy(text) {
+                        Ok(_) => app.status(tr!("已複製摘要到剪貼簿").to_string()),
+                        Err(e) => app.status(tr!("複製失敗: {error}", error = e)),
+                    }
+                }
+            }
+            KeyCode::Char('S') | KeyCode::Char('q') | KeyCode::Esc => app.summary = None,
+            _ => (),
+        };
+
+        return KeyBindEvent::None;
+    }
+
+    if let Some(viewer) = app.post.image.as_mut() {
+        match event.code {
+            KeyCode::Char('+') | KeyCode::Char('=') => viewer.zoom(true),
+            KeyCode::Char('-') => viewer.zoom(false),
+            KeyCode::Char('0') => viewer.reset(),
+            KeyCode::Char('h') | KeyCode::Left => viewer.pan(-1, 0),
+            KeyCode::Char('l') | KeyCode::Right => viewer.pan(1, 0),
+            KeyCode::Char('k') | KeyCode::Up => viewer.pan(0, -1),
+            KeyCode::Char('j') | KeyCode::Down => viewer.pan(0, 1),
+            KeyCode::Char(']') | KeyCode::Char('n') => viewer.next(),
+            KeyCode::Char('[') | KeyCode::Char('N') => viewer.previous(),
+            KeyCode::Char('o') => {
+                if let Err(e) = open_url(viewer.url()) {
+                    app.status(tr!("無法開啟連結: {error}", error = e));
+                }
+            }
+            KeyCode::Char('i') | KeyCode::Char('q') | KeyCode::Esc => app.post.close_image_viewer(),
+            _ => (),
+        };
+
+        return KeyBindEvent::None;
+    }
+
+    if app.post.conversation_state.is_some() {
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => app.post.conversation_next(),
+            KeyCode::Char('k') | KeyCode::Up => app.post.conversation_previous(),
+            KeyCode::Char('h') | KeyCode::Left => app.post.conversation_move(true),
+            KeyCode::Char('l') | KeyCode::Right => app.post.conversation_move(false),
+            KeyCode::Enter => app.post.conversation_jump(),
+            KeyCode::Char('c') | KeyCode::Esc => app.post.toggle_conversation(),
+            _ => (),
+        };
+
+        return KeyBindEvent::None;
+    }
+
+    if app.post.outline.is_some() {
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => app.post.outline_next(),
+            KeyCode::Char('k') | KeyCode::Up => app.post.outline_previous(),
+            KeyCode::Enter => app.post.outline_jump(),
+            KeyCode::Char('t') | KeyCode::Esc => app.post.toggle_outline(),
+            _ => (),
+        };
+
+        return KeyBindEvent::None;
+    }
+
+    if app.post.visual.is_some() {
+        match event.code {
+            KeyCode::Char('j') | KeyCode::Down => app.post.move_visual(true),
+            KeyCode::Char('k') | KeyCode::Up => app.post.move_visual(false),
+            KeyCode::Char('y') => yank(app),
+            KeyCode::Char('V') | KeyCode::Esc => app.post.toggle_visual(),
+            _ => (),
+        };
+
+        return KeyBindEvent::None;
+    }
+
+    let app = Rc::new(RefCell::new(app));
+    let next = |app: &Rc<RefCell<&mut AppState>>| {
+        let mut app = app.borrow_mut();
+        if app.post.next().is_none() && app.post.has_next() {
+            app.loading = true;
+            tx.send(DataRequestMsg::PostPage(
+                app.post.url.to_owned(),
+                app.post.page + 1,
+                false,
+            ))
+            .unwrap_or(());
+        }
+    };
+
+    let previous = |app: &Rc<RefCell<&mut AppState>>| {
+        let mut app = app.borrow_mut();
+        if app.post.has_previous_page() {
+            app.loading = true;
+            tx.send(DataRequestMsg::PostPage(
+                app.post.url.to_owned(),
+                app.post.start_page - 1,
+                true,
+            ))
+            .unwrap_or(());
+        } else {
+            app.post.previous();
+        }
+    };
 
     let app = Rc::clone(&app);
-    match event.code {
-        KeyCode::PageDown => next(&app),
-        KeyCode::PageUp => app.borrow_mut().post.previous(),
-        KeyCode::Home => app.borrow_mut().post.first(),
-        KeyCode::Char('j') | KeyCode::Down => app.borrow_mut().post.scroll_down(),
-        KeyCode::Char('k') | KeyCode::Up => app.borrow_mut().post.scroll_up(),
-        KeyCode::Char('o') => {
+    match action {
+        Some(Action::NextFloor) => next(&app),
+        Some(Action::PreviousFloor) => previous(&app),
+        Some(Action::Top) => app.borrow_mut().post.first(),
+        Some(Action::Comments) => {
             let mut app = app.borrow_mut();
+            app.push_history();
             app.loading = true;
             app.comment.init();
-            if let Some(content) = app.post.current() {
-                tx.send(DataRequestMsg::CommentPage(
-                    app.board.id.to_owned(),
-                    content.id.to_owned(),
+            app.comment.starter = app
+                .post
+                .data
+                .author
+                .as_ref()
+                .map_or(String::new(), |a| a.id.to_owned());
+            app.comment.floor_author = app
+                .post
+                .current()
+                .map_or(String::new(), |c| c.user.id.to_owned());
+            if let (Some(bsn), Some(content)) = (app.post.board_id(), app.post.current()) {
+                tx.send(DataRequestMsg::CommentPage(bsn, content.id.to_owned()))
+                    .unwrap_or(());
+            }
+        }
+        Some(Action::Outline) => app.borrow_mut().post.toggle_outline(),
+        Some(Action::Select) => app.borrow_mut().post.toggle_visual(),
+        Some(Action::Find) => app.borrow_mut().post.open_find(),
+        Some(Action::Participants) => app.borrow_mut().post.toggle_participants(),
+        Some(Action::Reply) => {
+            let mut app = app.borrow_mut();
+            let templates = app.config.reply.templates.clone();
+            if !app.post.toggle_reply(&templates) {
+                app.status(tr!("沒有回覆範本，請先在設定檔的 [reply] 設定 templates").to_string());
+            }
+        }
+        Some(Action::Conversation) => app.borrow_mut().post.toggle_conversation(),
+        Some(Action::Images) => app.borrow_mut().open_image_viewer(),
+        Some(Action::Attachments) => app.borrow_mut().download_attachments(&tx),
+        Some(Action::Summary) => app.borrow_mut().summarize(&tx),
+        Some(Action::ClearFind) => {
+            let mut app = app.borrow_mut();
+            if app.post.clear_find() {
+                app.status(tr!("已清除貼文內搜尋").to_string());
+            }
+        }
+        Some(Action::LineNumbers) => {
+            let mut app = app.borrow_mut();
+            app.post.line_numbers = !app.post.line_numbers;
+        }
+        Some(Action::ShowHidden) => {
+            let mut app = app.borrow_mut();
+            let count = app.post.hidden.len();
+            let status = if app.post.toggle_hidden() {
+                tr!("顯示 {count} 樓低內容回覆", count = count)
+            } else {
+                tr!("隱藏 {count} 樓低內容回覆", count = count)
+            };
+            app.status(status);
+        }
+        Some(Action::Diff) => {
+            let mut app = app.borrow_mut();
+            if !app.post.toggle_diff() && !app.post.is_edited() {
+                app.status(tr!("此樓層沒有被編輯過").to_string());
+            }
+        }
+        Some(Action::Subscribe) => app.borrow_mut().toggle_subscription(),
+        Some(Action::Mute) => app.borrow_mut().toggle_mute(),
+        Some(Action::Bookmark) => app.borrow_mut().toggle_bookmark(),
+        Some(Action::ParentBoard) => {
+            let mut app = app.borrow_mut();
+            if let Ok(param) = PostPageUrlParameter::try_from(app.post.url.to_owned()) {
+                app.save_read_position();
+                app.push_history();
+                let name = app.post.data.board.to_owned();
+                app.board.id(param.board_id.to_owned());
+                app.board.name(name);
+                app.board.restore_thread = Some(param.id.to_owned());
+                app.loading = true;
+                tx.send(DataRequestMsg::ParentBoard(
+                    param.board_id,
+                    param.id,
+                    PARENT_BOARD_PAGES,
                 ))
-                .map_or((), |_| ());
+                .unwrap_or(());
             }
         }
-        KeyCode::Char('r') => {
+        Some(Action::Speak) => {
             let mut app = app.borrow_mut();
-            app.loading = true;
-            tx.send(DataRequestMsg::PostPage(
-                app.post.url.to_owned(),
-                app.post.page,
-                false,
-            ))
-            .map_or((), |_| ())
+            let app = &mut **app;
+            if let Some(content) = app.post.current() {
+                let text = content.text();
+                match app.speaker.toggle(&app.config.tts, text.as_ref()) {
+                    Ok(_) if app.speaker.is_playing() => app.status(tr!("朗讀中...").to_string()),
+                    Ok(_) => app.status(tr!("停止朗讀").to_string()),
+                    Err(e) => app.status(tr!("朗讀失敗: {error}", error = e)),
+                }
+            }
         }
-        _ => (),
+        _ => match event.code {
+            KeyCode::PageDown => next(&app),
+            KeyCode::PageUp => previous(&app),
+            KeyCode::Char('j') | KeyCode::Down => app.borrow_mut().post.scroll_down(),
+            KeyCode::Char('k') | KeyCode::Up => app.borrow_mut().post.scroll_up(),
+            KeyCode::Char('u') => {
+                let mut app = app.borrow_mut();
+                if let Some(owner) = app.post.current().map(|c| c.user.id.to_owned()) {
+                    app.push_history();
+                    app.loading = true;
+                    app.creation.owner = owner.to_owned();
+                    tx.send(DataRequestMsg::CreationList(owner)).unwrap_or(());
+                }
+            }
+            KeyCode::Char('O') => {
+                let mut app = app.borrow_mut();
+                match app.post.visible_animation().map(str::to_string) {
+                    Some(url) => {
+                        if let Err(e) = open_url(url.as_ref()) {
+                            app.status(tr!("無法開啟連結: {error}", error = e));
+                        }
+                    }
+                    None => app.status(tr!("畫面上沒有動圖").to_string()),
+                }
+            }
+            KeyCode::Char('m') => app.borrow_mut().mark_thread_link(),
+            KeyCode::Char('L') => {
+                let mut app = app.borrow_mut();
+                if app.link_thread() {
+                    tx.send(DataRequestMsg::ThreadSeries(app.thread_link.series.clone()))
+                        .unwrap_or(());
+                }
+            }
+            KeyCode::Char('U') => {
+                let mut app = app.borrow_mut();
+                if app.unlink_thread() {
+                    tx.send(DataRequestMsg::ThreadSeries(app.thread_link.series.clone()))
+                        .unwrap_or(());
+                }
+            }
+            KeyCode::Char('r') => {
+                let mut app = app.borrow_mut();
+                app.loading = true;
+                tx.send(DataRequestMsg::PostPage(
+                    app.post.url.to_owned(),
+                    app.post.page,
+                    false,
+                ))
+                .unwrap_or(())
+            }
+
+            _ => (),
+        },
     };
 
     // with control
@@ -232,7 +1164,7 @@ fn handle_post_key(
     {
         match code {
             KeyCode::Char('f') => next(&app),
-            KeyCode::Char('b') => app.borrow_mut().post.previous(),
+            KeyCode::Char('b') => previous(&app),
             _ => (),
         }
     };
@@ -240,6 +1172,21 @@ fn handle_post_key(
     KeyBindEvent::None
 }
 
+fn yank(app: &mut AppState) {
+    let text = match app.post.yank_visual() {
+        Some(text) => text,
+        None => return,
+    };
+
+    match clipboard::copy(&text) {
+        Ok(_) => app.status(tr!(
+            "已複製 {count} 行到剪貼簿",
+            count = text.lines().count()
+        )),
+        Err(e) => app.status(tr!("複製失敗: {error}", error = e)),
+    }
+}
+
 fn handle_comment_key(
     app: &mut AppState,
     event: KeyEvent,
@@ -248,8 +1195,318 @@ fn handle_comment_key(
     match event.code {
         KeyCode::Char('j') | KeyCode::Down => app.comment.next(),
         KeyCode::Char('k') | KeyCode::Up => app.comment.previous(),
+        KeyCode::Char('a') => {
+            let filter = app.comment.cycle_filter();
+            app.status(filter.label().to_string());
+        }
+        KeyCode::Char('s') => app.comment.toggle_summary(),
+        _ => (),
+    };
+
+    KeyBindEvent::None
+}
+
+fn handle_cache_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    _: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    let clear = |app: &mut AppState, board: Option<String>| {
+        if !app.ensure_writable() {
+            return;
+        }
+
+        if !app.config.confirm.clear_cache {
+            return clear_cache(app, board);
+        }
+
+        let message = match &board {
+            Some(board) => tr!("確定要清除看板 {board} 的快取嗎?", board = board),
+            None => tr!("確定要清除全部快取嗎?").to_string(),
+        };
+        app.confirm = Some(Confirm::new(
+            ConfirmAction::ClearCache(board),
+            message.as_ref(),
+        ));
+    };
+
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.cache.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.cache.previous(),
+        KeyCode::Char('d') => {
+            if let Some(board) = app.cache.selected_board() {
+                clear(app, Some(board))
+            }
+        }
+        KeyCode::Char('D') => clear(app, None),
+        KeyCode::Char('p') if app.ensure_writable() => {
+            let days = app.config.cache.prune_days;
+            let max_age = Duration::from_secs(days * 24 * 60 * 60);
+            let cache = app.client.cache();
+            let count = cache.map_or(0, |cache| cache.prune(max_age));
+            app.cache.refresh(cache);
+            app.status(tr!(
+                "已清除 {count} 筆超過 {days} 天的快取",
+                count = count,
+                days = days
+            ));
+        }
+        KeyCode::Char('r') => app.cache.refresh(app.client.cache()),
         _ => (),
     };
 
     KeyBindEvent::None
 }
+
+fn handle_anime_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.anime.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.anime.previous(),
+        KeyCode::Tab | KeyCode::BackTab => app.anime.toggle_tab(),
+        KeyCode::Enter => {
+            if let Some(url) = app.anime.current().map(|e| e.url.to_owned()) {
+                if let Err(e) = open_url(url.as_ref()) {
+                    app.status(tr!("無法開啟連結: {error}", error = e));
+                }
+            }
+        }
+        KeyCode::Char('r') => {
+            app.loading = true;
+            tx.send(DataRequestMsg::AnimeHome).unwrap_or(());
+        }
+        _ => (),
+    };
+
+    KeyBindEvent::None
+}
+
+fn handle_news_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.news.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.news.previous(),
+        KeyCode::Enter => {
+            if let Some(url) = app.news.current().map(|n| n.url.to_owned()) {
+                app.push_history();
+                app.loading = true;
+                tx.send(DataRequestMsg::NewsArticle(url)).unwrap_or(());
+            }
+        }
+        KeyCode::Char('r') => {
+            app.loading = true;
+            tx.send(DataRequestMsg::NewsList).unwrap_or(());
+        }
+        _ => (),
+    };
+
+    KeyBindEvent::None
+}
+
+fn handle_article_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.news.scroll_down(),
+        KeyCode::Char('k') | KeyCode::Up => app.news.scroll_up(),
+        // jump to related board
+        KeyCode::Char(c @ '1'..='9') => {
+            let i = c as usize - '1' as usize;
+            if let Some(board) = app.news.article.boards.get(i).cloned() {
+                app.push_history();
+                app.loading = true;
+                app.board.name(board.name);
+                app.board.id(board.id.to_owned());
+                tx.send(DataRequestMsg::BoardPage(board.id, 1, true))
+                    .unwrap_or(());
+            }
+        }
+        _ => (),
+    };
+
+    KeyBindEvent::None
+}
+
+fn handle_creation_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.creation.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.creation.previous(),
+        KeyCode::Enter => {
+            if let Some(url) = app.creation.current().map(|c| c.url.to_owned()) {
+                app.push_history();
+                app.loading = true;
+                tx.send(DataRequestMsg::CreationArticle(url)).unwrap_or(());
+            }
+        }
+        KeyCode::Char('r') => {
+            app.loading = true;
+            tx.send(DataRequestMsg::CreationList(app.creation.owner.to_owned()))
+                .unwrap_or(());
+        }
+        _ => (),
+    };
+
+    KeyBindEvent::None
+}
+
+fn handle_creation_article_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    _: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.creation.scroll_down(),
+        KeyCode::Char('k') | KeyCode::Up => app.creation.scroll_up(),
+        _ => (),
+    };
+
+    KeyBindEvent::None
+}
+
+fn handle_bookmark_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.bookmarks.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.bookmarks.previous(),
+        KeyCode::Char('d') => app.remove_bookmark(),
+        KeyCode::Enter => {
+            if let Some(bookmark) = app.bookmarks.selected().cloned() {
+                app.push_history();
+                let is_loaded = app.post.url == bookmark.url;
+                app.post.url = bookmark.url.to_owned();
+                app.post.restore = Some(ReadPosition {
+                    page: bookmark.page,
+                    floor: bookmark.floor,
+                    offset: 0,
+                });
+
+                // fetch the page of the floor again unless it is loaded
+                if is_loaded && app.post.is_restorable() {
+                    app.post.apply_restore();
+                    app.page = Page::Post;
+                } else {
+                    app.loading = true;
+                    tx.send(DataRequestMsg::PostPage(bookmark.url, bookmark.page, true))
+                        .unwrap_or(());
+                }
+            }
+        }
+        _ => (),
+    }
+
+    KeyBindEvent::None
+}
+
+fn handle_moderation_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.moderation.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.moderation.previous(),
+        KeyCode::Char('r') => {
+            app.loading = true;
+            tx.send(DataRequestMsg::ModerationLog(app.board.id.to_owned()))
+                .unwrap_or(());
+        }
+        _ => (),
+    };
+
+    KeyBindEvent::None
+}
+
+fn handle_timeline_key(
+    app: &mut AppState,
+    action: Option<Action>,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    if let Some(Action::Translate) = action {
+        app.toggle_translation();
+        return KeyBindEvent::None;
+    }
+
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.timeline.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.timeline.previous(),
+        KeyCode::Char('r') => {
+            app.loading = app.request_timeline(&tx, false);
+        }
+        KeyCode::Enter => {
+            if let Some(url) = app.timeline.selected().map(|item| item.post.url.to_owned()) {
+                let url = app.thread_link.head(url.as_ref());
+                app.push_history();
+                app.loading = true;
+                app.post.url = url.to_owned();
+                app.post.restore = app.read_position.get(url.as_ref());
+                let page = app.post.restore.map_or(1, |p| p.page);
+                tx.send(DataRequestMsg::PostPage(url, page, true))
+                    .unwrap_or(())
+            }
+        }
+        _ => (),
+    };
+
+    KeyBindEvent::None
+}
+
+fn handle_activity_key(
+    app: &mut AppState,
+    event: KeyEvent,
+    tx: Sender<DataRequestMsg>,
+) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('h') | KeyCode::Left => app.activity.move_day(-7),
+        KeyCode::Char('l') | KeyCode::Right => app.activity.move_day(7),
+        KeyCode::Up => app.activity.move_day(-1),
+        KeyCode::Down => app.activity.move_day(1),
+        KeyCode::Char('j') => app.activity.next(),
+        KeyCode::Char('k') => app.activity.previous(),
+        KeyCode::Enter => {
+            let items: Vec<CreationItem> = app.activity.day_items().into_iter().cloned().collect();
+            let selected = app.activity.state.selected();
+            if let Some(url) = selected
+                .and_then(|i| items.get(i))
+                .map(|i| i.url.to_owned())
+            {
+                // back to the creations of the day from the article
+                app.push_history();
+                app.creation.owner = app.activity.owner.to_owned();
+                app.creation.items(items);
+                app.creation.state.select(selected);
+                app.loading = true;
+                tx.send(DataRequestMsg::CreationArticle(url)).unwrap_or(());
+            }
+        }
+        _ => (),
+    }
+
+    KeyBindEvent::None
+}
+
+fn handle_settings_key(app: &mut AppState, event: KeyEvent) -> KeyBindEvent {
+    match event.code {
+        KeyCode::Char('j') | KeyCode::Down => app.settings.next(),
+        KeyCode::Char('k') | KeyCode::Up => app.settings.previous(),
+        KeyCode::Enter | KeyCode::Char('e') => app.edit_setting(),
+        _ => (),
+    }
+
+    KeyBindEvent::None
+}
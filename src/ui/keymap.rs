@@ -0,0 +1,292 @@
+use super::{
+    action::{parse_key, Action, KeyMap},
+    state::Page,
+};
+
+pub struct KeyHelp {
+    pub keys: &'static str,
+    pub desc: &'static str,
+    // short label shown in the footer
+    pub hint: Option<&'static str>,
+}
+
+pub struct PageHelp {
+    pub page: &'static str,
+    pub keys: &'static [KeyHelp],
+}
+
+macro_rules! key_hint {
+    () => {
+        None
+    };
+    ($hint:expr) => {
+        Some($hint)
+    };
+}
+
+macro_rules! key_help {
+    ($($keys:expr => $desc:expr $(=> $hint:expr)?),* $(,)?) => {
+        &[$(KeyHelp { keys: $keys, desc: $desc, hint: key_hint!($($hint)?) }),*]
+    };
+}
+
+pub static KEYBINDINGS: &[PageHelp] = &[
+    PageHelp {
+        page: "共通操作",
+        keys: key_help![
+            "<ctrl-c>" => "離開程式",
+            "<ctrl-z>" => "暫停並回到 shell，以 fg 回來",
+            ":" => "輸入指令，:sh 開啟子 shell，:set 開啟設定，:saver 切換省流模式，:map <按鍵> <動作> 對應按鍵，:do <動作> 執行動作，:q 離開",
+            "<Backspace>" => "回到上一個瀏覽的畫面",
+            "Q x" => "開始/停止錄製巨集到暫存器 x",
+            "@ x" => "執行暫存器 x 的巨集",
+            "@@" => "重複執行上次的巨集",
+            "C" => "開啟快取管理",
+            "B" => "開啟樓層書籤",
+            "P" => "開啟自己的發文活動(需先 login)",
+            "A" => "開啟動畫瘋新番列表",
+            "N" => "開啟 GNN 新聞",
+            "T" => "開啟看板動態，依最後回覆時間列出所有訂閱看板的最新貼文",
+            "<F12>" => "顯示/關閉請求統計與貼文樓層的解析結果",
+            "<Tab>" => "開啟 follow_unread 時，跳到下一個有未讀內容的訂閱貼文或看板",
+            "數字 + 按鍵" => "輸入次數後重複執行按鍵，例如 5j 往下滾動 5 行、3] 往下 3 樓",
+        ],
+    },
+    PageHelp {
+        page: "搜尋看板頁面",
+        keys: key_help![
+            "a, e, i, o" => "進入搜尋模式" => "搜尋",
+            "↑, ↓" => "搜尋模式中叫出之前搜尋過的關鍵字",
+            "s" => "開啟/關閉已儲存的搜尋與新結果數，在搜尋上按 <Enter> 開啟" => "已儲存",
+            "x" => "忽略/取消忽略選擇的看板，不再出現在搜尋結果",
+            "X" => "顯示/隱藏已忽略的看板",
+            "j, ↓" => "往下選擇看板",
+            "k, ↑" => "往上選擇看板",
+            "<Enter>" => "選擇看板" => "選擇",
+            "q" => "離開程式" => "離開",
+        ],
+    },
+    PageHelp {
+        page: "看板頁面",
+        keys: key_help![
+            "j, ↓" => "往下選擇貼文",
+            "k, ↑" => "往上選擇貼文",
+            "l, →" => "看板翻下一頁" => "下頁",
+            "h, ←" => "看板翻上一頁" => "上頁",
+            "<Enter>" => "選擇貼文" => "開啟",
+            "w" => "訂閱/取消訂閱看板" => "訂閱",
+            "S" => "儲存/取消儲存目前看板中的搜尋",
+            "c" => "依標題分類(例如【情報】)篩選貼文，重複按切換下一個分類" => "分類",
+            "f" => "以標題或作者即時篩選已載入的貼文，<Enter> 保留、<Esc> 清除" => "篩選",
+            "u" => "切換有未讀回覆的貼文排在前面",
+            "t" => "翻譯/顯示原本的標題，需設定 [translate]",
+            "L" => "開啟看板的處理紀錄，列出最近刪除、鎖定的貼文與理由" => "紀錄",
+            "K" => "開啟/關閉熱門關鍵字，在關鍵字上按 <Enter> 搜尋" => "關鍵字",
+            "q" => "回到搜尋看板" => "返回",
+            "r" => "重新整理" => "重新整理",
+        ],
+    },
+    PageHelp {
+        page: "貼文頁面",
+        keys: key_help![
+            "<ctrl-f>, <PageDown>, ]" => "往下一篇文章回覆" => "下一樓",
+            "<ctrl-b>, <PageUp>, [" => "往上一篇文章回覆" => "上一樓",
+            "j, ↓" => "文章往下滾動",
+            "k, ↑" => "文章往上滾動",
+            "o" => "開啟文章留言" => "留言",
+            "u" => "瀏覽目前樓層作者的小屋創作",
+            "O" => "以外部程式開啟畫面上第一張標示 [GIF] 的動圖",
+            "i" => "全螢幕檢視這頁樓層的圖片，從目前樓層開始，重複轉貼的圖片只列一次並標示出現的樓層(需支援 kitty 圖片協定或開啟 mosaic)，+/- 縮放、h/j/k/l 移動、0 還原、]/[ 下一張/上一張、o 以外部程式開啟，q 關閉" => "圖片",
+            "a" => "下載目前樓層的附件到下載目錄，未完成的檔案會接續下載，與這篇貼文已下載的檔案相同時略過" => "附件",
+            "S" => "以外部指令或 OpenAI 相容的 API 摘要已載入的樓層，j/k 捲動、y 複製，q 關閉" => "摘要",
+            "s" => "朗讀/停止朗讀目前樓層",
+            "d" => "顯示/關閉重新整理後被編輯樓層的差異",
+            "t" => "開啟/關閉文章目錄" => "目錄",
+            "#" => "顯示/隱藏行號",
+            "V" => "進入選取模式，j/k 調整範圍，y 複製選取的行到剪貼簿，<Esc> 取消" => "選取",
+            "/" => "搜尋已載入的樓層，只顯示符合的樓層，可用 author:作者 與 floor:>N 條件，<ctrl-r> 切換正規表示式，<ctrl-s> 切換區分大小寫" => "搜尋",
+            "<Esc>" => "清除貼文內搜尋",
+            "p" => "開啟/關閉參與者列表，<Enter>/f 跳到第一樓、l 跳到最後一樓，w 追蹤、x 封鎖(隱藏其樓層)" => "參與者",
+            "R" => "開啟/關閉回覆範本，<Enter> 將套用目前樓層的範本複製到剪貼簿，需設定 [reply] templates" => "回覆",
+            "c" => "開啟/關閉對話串，列出引用其他樓層(#12、B12、12樓、@id)的回覆，h/l 移到引用的樓層或第一個回覆，<Enter> 跳到該樓" => "對話",
+            "H" => "顯示/隱藏低內容的回覆(需開啟 filter)",
+            "w" => "訂閱/取消訂閱貼文" => "訂閱",
+            "M" => "靜音/取消靜音貼文，靜音後不再通知或標示未讀",
+            "b" => "將目前樓層加入/移出書籤" => "書籤",
+            "g" => "開啟貼文所在的看板，並跳到列出這篇貼文的頁面(只找前 10 頁)" => "所在看板",
+            "m" => "標記目前貼文為前一集",
+            "L" => "將目前貼文連結為標記貼文的續集",
+            "U" => "取消目前貼文的續集連結",
+            "<Home>" => "回到頂樓",
+            "q" => "回到看板" => "返回",
+            "r" => "重新整理" => "重新整理",
+        ],
+    },
+    PageHelp {
+        page: "留言頁面",
+        keys: key_help![
+            "j, ↓" => "往下滾動" => "下",
+            "k, ↑" => "往上滾動" => "上",
+            "a" => "切換顯示全部、只看樓層作者、只看樓主的留言" => "篩選",
+            "s" => "顯示/關閉各留言者的留言數" => "統計",
+            "q" => "回到貼文" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "小屋創作頁面",
+        keys: key_help![
+            "j, ↓" => "往下選擇創作",
+            "k, ↑" => "往上選擇創作",
+            "<Enter>" => "閱讀創作" => "閱讀",
+            "r" => "重新整理" => "重新整理",
+            "q" => "回到貼文" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "創作內容頁面",
+        keys: key_help![
+            "j, ↓" => "往下滾動" => "下",
+            "k, ↑" => "往上滾動" => "上",
+            "q" => "回到創作列表" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "GNN 新聞頁面",
+        keys: key_help![
+            "j, ↓" => "往下選擇新聞",
+            "k, ↑" => "往上選擇新聞",
+            "<Enter>" => "閱讀新聞" => "閱讀",
+            "r" => "重新整理" => "重新整理",
+            "q" => "回到上一頁" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "GNN 新聞內容頁面",
+        keys: key_help![
+            "j, ↓" => "往下滾動",
+            "k, ↑" => "往上滾動",
+            "1 ~ 9" => "前往第 n 個相關看板" => "相關看板",
+            "q" => "回到新聞列表" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "動畫瘋頁面",
+        keys: key_help![
+            "j, ↓" => "往下選擇動畫",
+            "k, ↑" => "往上選擇動畫",
+            "<Tab>, gt, gT" => "切換新上架/每週新番" => "切換",
+            "<Enter>" => "在瀏覽器開啟動畫" => "開啟",
+            "r" => "重新整理" => "重新整理",
+            "q" => "回到上一頁" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "快取管理頁面",
+        keys: key_help![
+            "j, ↓" => "往下選擇看板",
+            "k, ↑" => "往上選擇看板",
+            "d" => "清除選擇看板的快取" => "清除看板",
+            "D" => "清除全部快取" => "全部清除",
+            "p" => "清除超過設定天數的快取" => "清除過期",
+            "r" => "重新整理" => "重新整理",
+            "q" => "回到上一頁" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "發文活動頁面",
+        keys: key_help![
+            "h, ←" => "前一週" => "上週",
+            "l, →" => "下一週" => "下週",
+            "↑" => "前一天",
+            "↓" => "後一天",
+            "j" => "往下選擇當天的創作",
+            "k" => "往上選擇當天的創作",
+            "<Enter>" => "閱讀創作" => "閱讀",
+            "q" => "回到上一頁" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "書籤頁面",
+        keys: key_help![
+            "j, ↓" => "往下選擇書籤",
+            "k, ↑" => "往上選擇書籤",
+            "<Enter>" => "跳到書籤的樓層" => "開啟",
+            "d" => "移除選擇的書籤" => "移除",
+            "q" => "回到上一頁" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "處理紀錄頁面",
+        keys: key_help![
+            "j, ↓" => "往下選擇紀錄",
+            "k, ↑" => "往上選擇紀錄",
+            "r" => "重新整理" => "重新整理",
+            "q" => "回到看板" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "看板動態頁面",
+        keys: key_help![
+            "j, ↓" => "往下選擇貼文",
+            "k, ↑" => "往上選擇貼文",
+            "<Enter>" => "開啟貼文" => "開啟",
+            "r" => "重新整理，開啟時也會依 refresh_secs 定時更新" => "重新整理",
+            "t" => "翻譯/顯示原本的標題，需設定 [translate]",
+            "q" => "回到上一頁" => "返回",
+        ],
+    },
+    PageHelp {
+        page: "設定頁面",
+        keys: key_help![
+            "j, ↓" => "往下選擇設定",
+            "k, ↑" => "往上選擇設定",
+            "<Enter>, e" => "切換開關或選項，其他設定輸入新的值後按 <Enter> 儲存，<Esc> 取消" => "修改",
+            "q" => "回到上一頁" => "返回",
+        ],
+    },
+];
+
+// page name of KEYBINDINGS for each screen
+fn page_name(page: Page) -> &'static str {
+    match page {
+        Page::Search => "搜尋看板頁面",
+        Page::Board => "看板頁面",
+        Page::Post => "貼文頁面",
+        Page::Comment => "留言頁面",
+        Page::Cache => "快取管理頁面",
+        Page::Anime => "動畫瘋頁面",
+        Page::News => "GNN 新聞頁面",
+        Page::Article => "GNN 新聞內容頁面",
+        Page::Creation => "小屋創作頁面",
+        Page::CreationArticle => "創作內容頁面",
+        Page::Bookmark => "書籤頁面",
+        Page::Activity => "發文活動頁面",
+        Page::Moderation => "處理紀錄頁面",
+        Page::Timeline => "看板動態頁面",
+        Page::Settings => "設定頁面",
+    }
+}
+
+// keys with a hint of the screen, as (key, hint), a key mapped to the action comes first
+pub fn hints(page: Page, keymap: &KeyMap) -> Vec<(String, &'static str)> {
+    let name = page_name(page);
+    KEYBINDINGS
+        .iter()
+        .filter(|help| help.page == name)
+        .flat_map(|help| help.keys.iter())
+        .filter_map(|key| {
+            let hint = key.hint?;
+            let mut keys = key.keys.split(", ");
+            // first one of the alternative keys
+            let first = keys.clone().next()?;
+            let action = keys.find_map(|k| parse_key(k).and_then(|e| Action::at(&e, page)));
+            let mapped = action.map_or(vec![], |action| keymap.keys_of(action));
+            match mapped.into_iter().next() {
+                Some(mapped) => Some((mapped, tr!(hint))),
+                // the key runs another action now
+                None if parse_key(first).is_some_and(|e| keymap.is_mapped(&e)) => None,
+                None => Some((first.to_string(), tr!(hint))),
+            }
+        })
+        .collect()
+}
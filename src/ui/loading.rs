@@ -1,26 +1,58 @@
 use ratatui::{
     buffer::Buffer,
-    layout::{Alignment, Rect},
-    style::{Color, Style},
-    widgets::{Block, Borders, Clear, Paragraph, Widget},
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Clear, Widget},
 };
 
-pub struct Loading;
+use super::state::Page;
 
-impl Widget for Loading {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        Clear.render(area, buf);
+// widths of the placeholder rows in percent, repeated
+static ROW_WIDTHS: &[u16] = &[72, 90, 55, 84, 38, 66];
+
+// the page stays on screen while the next one loads, only its content rows are
+// covered with placeholders, so borders, titles and the thread header don't flash
+pub struct Skeleton {
+    pub page: Page,
+}
 
-        let block = Block::default()
-            .style(Style::default().bg(Color::Gray))
-            .borders(Borders::ALL);
+impl Skeleton {
+    // content rows of the page, inside its borders
+    fn content(&self, area: Rect) -> Rect {
+        let (top, side, bottom) = match self.page {
+            // thread header above the floor
+            Page::Post => (7, 1, 1),
+            // margin and the page indicator below the list
+            Page::Board => (1, 2, 2),
+            _ => (1, 1, 1),
+        };
+        Rect::new(
+            area.x + side,
+            area.y + top,
+            area.width.saturating_sub(side * 2),
+            area.height.saturating_sub(top + bottom),
+        )
+    }
+}
 
-        let text = "Loading...";
+impl Widget for Skeleton {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = self.content(area);
+        if area.area() == 0 {
+            return;
+        }
+        Clear.render(area, buf);
 
-        Paragraph::new(text)
-            .style(Style::default().fg(Color::Black))
-            .alignment(Alignment::Center)
-            .block(block)
-            .render(area, buf);
+        let bar = Style::default().fg(Color::DarkGray);
+        let label = Style::default()
+            .fg(Color::Gray)
+            .add_modifier(Modifier::ITALIC);
+        // every other row, like list items with a gap
+        for (i, y) in (area.top()..area.bottom()).step_by(2).enumerate() {
+            let percent = ROW_WIDTHS[i % ROW_WIDTHS.len()];
+            let width = (area.width as u32 * percent as u32 / 100).max(1) as u16;
+            buf.set_string(area.x, y, "░".repeat(width as usize), bar);
+        }
+        buf.set_string(area.x, area.y, " Loading... ", label);
     }
 }
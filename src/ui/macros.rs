@@ -0,0 +1,84 @@
+use std::collections::{HashMap, VecDeque};
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+enum MacroPending {
+    Record,
+    Replay,
+}
+
+#[derive(Default)]
+pub struct MacroState {
+    pending: Option<MacroPending>,
+    recording: Option<(char, Vec<KeyEvent>)>,
+    registers: HashMap<char, Vec<KeyEvent>>,
+    last: Option<char>,
+    // repeated keys of a count prefix, already recorded with the count
+    repeats: usize,
+    pub queue: VecDeque<KeyEvent>,
+}
+
+impl MacroState {
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(r, _)| *r)
+    }
+
+    // return true if the key is consumed by macro
+    pub fn handle(&mut self, event: KeyEvent) -> bool {
+        if let Some(pending) = self.pending.take() {
+            let register = match event.code {
+                KeyCode::Char('@') => self.last,
+                KeyCode::Char(c) if c.is_ascii_alphanumeric() => Some(c),
+                _ => None,
+            };
+
+            if let Some(register) = register {
+                match pending {
+                    MacroPending::Record => self.recording = Some((register, vec![])),
+                    MacroPending::Replay => self.replay(register),
+                }
+                return true;
+            }
+
+            return false;
+        }
+
+        match event.code {
+            KeyCode::Char('Q') => {
+                if let Some((register, keys)) = self.recording.take() {
+                    self.registers.insert(register, keys);
+                } else {
+                    self.pending = Some(MacroPending::Record);
+                }
+                true
+            }
+            KeyCode::Char('@') => {
+                self.pending = Some(MacroPending::Replay);
+                true
+            }
+            _ => {
+                if self.repeats > 0 {
+                    self.repeats -= 1;
+                } else if let Some((_, keys)) = self.recording.as_mut() {
+                    keys.push(event);
+                }
+                false
+            }
+        }
+    }
+
+    // run the key again for the count prefix, before any queued macro
+    pub fn repeat(&mut self, event: KeyEvent, times: u16) {
+        for _ in 0..times {
+            self.queue.push_front(event);
+        }
+        self.repeats += times as usize;
+    }
+
+    fn replay(&mut self, register: char) {
+        if let Some(keys) = self.registers.get(&register) {
+            self.queue.extend(keys.iter().copied());
+            self.last = Some(register);
+        }
+    }
+}
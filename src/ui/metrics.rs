@@ -0,0 +1,66 @@
+use bahamut::api::metrics;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use super::cache::human_size;
+
+// request metrics of this session, for diagnosing slowness
+pub struct MetricsOverlay;
+
+impl Widget for MetricsOverlay {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let m = metrics::snapshot();
+        let slowest = m.slowest().map_or("-".to_string(), |r| {
+            format!("{} ms {}", r.duration.as_millis(), r.url)
+        });
+
+        let coverage = m
+            .coverage
+            .as_ref()
+            .map_or(Line::from(tr!("樓層解析: -")), |c| {
+                let line = tr!(
+                    "樓層解析: 第 {page} 頁 {detected}/{expected} 樓 (不足 {short} 頁)",
+                    page = c.page,
+                    detected = c.detected,
+                    expected = c.expected,
+                    short = m.short_pages
+                );
+                if c.is_short() {
+                    Line::styled(line, Style::default().fg(Color::LightRed))
+                } else {
+                    Line::from(line)
+                }
+            });
+
+        Paragraph::new(vec![
+            Line::from(tr!(
+                "請求數: {count} (失敗 {failed})",
+                count = m.requests,
+                failed = m.failed
+            )),
+            Line::from(tr!("下載量: {size}", size = human_size(m.bytes))),
+            Line::from(tr!("快取命中: {count}", count = m.cache_hits)),
+            Line::from(tr!(
+                "解析: {count} 次，平均 {ms} ms",
+                count = m.parses,
+                ms = m.average_parse_time().as_millis()
+            )),
+            coverage,
+            Line::from(tr!("最近最慢的請求: {request}", request = slowest)),
+        ])
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title(tr!("請求統計"))
+                .borders(Borders::ALL),
+        )
+        .render(area, buf);
+    }
+}
@@ -1,10 +1,38 @@
+pub mod action;
+pub mod activity;
+pub mod anime;
 pub mod board;
+pub mod bookmark;
+pub mod cache;
 pub mod comment;
+pub mod confirm;
+pub mod conversation;
+pub mod count;
+pub mod creation;
+pub mod digest;
+pub mod footer;
+pub mod glyph;
+pub mod graphics;
+pub mod header;
+pub mod history;
+pub mod hyperlink;
 pub mod key;
+pub mod keymap;
 pub mod loading;
+pub mod macros;
+pub mod metrics;
+pub mod moderation;
+pub mod monochrome;
+pub mod news;
+pub mod participants;
 pub mod post;
+pub mod reply;
 pub mod search;
+pub mod settings;
 pub mod state;
+pub mod status;
+pub mod summary;
+pub mod timeline;
 
 use ratatui::{
     backend::Backend,
@@ -13,16 +41,53 @@ use ratatui::{
 };
 
 use self::{
+    activity::ActivityPageUI,
+    anime::AnimePageUI,
     board::BoardPageUI,
+    bookmark::BookmarkPageUI,
+    cache::CachePageUI,
     comment::CommentPageUI,
-    loading::Loading,
+    confirm::ConfirmDialog,
+    conversation::ConversationPopup,
+    creation::{CreationArticlePageUI, CreationPageUI},
+    digest::DigestPopup,
+    footer::Footer,
+    glyph::GlyphFilter,
+    graphics::ImagePopup,
+    loading::Skeleton,
+    metrics::MetricsOverlay,
+    moderation::ModerationPageUI,
+    monochrome::Monochrome,
+    news::{ArticlePageUI, NewsPageUI},
+    participants::ParticipantsPopup,
     post::PostPageUI,
+    reply::ReplyPopup,
     search::SearchPageUI,
+    settings::SettingsPageUI,
     state::{AppState, InputMode, Page},
+    status::StatusLine,
+    summary::SummaryPopup,
+    timeline::TimelinePageUI,
 };
 
+use crate::config;
+
 pub fn ui<B: Backend>(f: &mut Frame<B>, app: &mut AppState) {
-    let size = f.size();
+    let full = f.size();
+    let size = if app.config.layout.footer && full.height > 1 {
+        let area = Rect::new(full.x, full.bottom() - 1, full.width, 1);
+        let footer = Footer {
+            hints: keymap::hints(app.page, &app.keymap),
+            read_only: app.config.session.read_only,
+            profile: config::profile(),
+            data_saver: app.config.network.data_saver,
+            offline: app.client.cache().is_some_and(|c| c.is_offline()),
+        };
+        f.render_widget(footer, area);
+        Rect::new(full.x, full.y, full.width, full.height - 1)
+    } else {
+        full
+    };
 
     match app.page {
         Page::Search => {
@@ -32,26 +97,191 @@ pub fn ui<B: Backend>(f: &mut Frame<B>, app: &mut AppState) {
             }
         }
         Page::Board => {
-            f.render_stateful_widget(BoardPageUI, size, &mut app.board);
+            let board = BoardPageUI {
+                subscribed: app.subscription.is_board_subscribed(app.board.id.as_ref()),
+                theme: app.config.theme.clone(),
+                preview: app.config.list_preview.enabled
+                    && !app.config.network.text_only(&app.config.cache),
+                translation: app
+                    .board
+                    .translate
+                    .then_some((&app.translation, app.config.translate.language.as_str())),
+            };
+            f.render_stateful_widget(board, size, &mut app.board);
         }
         Page::Post => {
-            f.render_stateful_widget(PostPageUI, size, &mut app.post);
+            let post = PostPageUI {
+                dual_pane_width: app.config.layout.dual_pane_width,
+                subscribed: app.subscription.is_subscribed(app.post.url.as_ref()),
+                muted: app.mute.is_muted(app.post.url.as_ref()),
+                bookmarked: app.post.current().is_some_and(|current| {
+                    app.bookmark.contains(app.post.url.as_ref(), current.floor)
+                }),
+                header: app.config.header.clone(),
+                theme: app.config.theme.clone(),
+                me: app.client.session_user_id(),
+                followed: app.post.followed.clone(),
+                avatars: app.config.layout.avatars,
+                inline_comments: app.config.layout.inline_comments,
+                graphics: app.graphics,
+            };
+            f.render_stateful_widget(post, size, &mut app.post);
         }
         Page::Comment => {
-            f.render_stateful_widget(CommentPageUI, size, &mut app.comment);
+            let comment = CommentPageUI {
+                theme: app.config.theme.clone(),
+            };
+            f.render_stateful_widget(comment, size, &mut app.comment);
+        }
+        Page::Cache => {
+            let cache = CachePageUI {
+                prune_days: app.config.cache.prune_days,
+            };
+            f.render_stateful_widget(cache, size, &mut app.cache);
+        }
+        Page::Anime => {
+            f.render_stateful_widget(AnimePageUI, size, &mut app.anime);
+        }
+        Page::News => {
+            f.render_stateful_widget(NewsPageUI, size, &mut app.news);
+        }
+        Page::Article => {
+            f.render_stateful_widget(ArticlePageUI, size, &mut app.news);
+        }
+        Page::Creation => {
+            f.render_stateful_widget(CreationPageUI, size, &mut app.creation);
+        }
+        Page::CreationArticle => {
+            f.render_stateful_widget(CreationArticlePageUI, size, &mut app.creation);
+        }
+        Page::Bookmark => {
+            f.render_stateful_widget(BookmarkPageUI, size, &mut app.bookmarks);
+        }
+        Page::Activity => {
+            f.render_stateful_widget(ActivityPageUI, size, &mut app.activity);
+        }
+        Page::Moderation => {
+            f.render_stateful_widget(ModerationPageUI, size, &mut app.moderation);
+        }
+        Page::Timeline => {
+            let timeline = TimelinePageUI {
+                translation: app
+                    .timeline
+                    .translate
+                    .then_some((&app.translation, app.config.translate.language.as_str())),
+            };
+            f.render_stateful_widget(timeline, size, &mut app.timeline);
+        }
+        Page::Settings => {
+            let settings = SettingsPageUI {
+                config: &app.config,
+            };
+            f.render_stateful_widget(settings, size, &mut app.settings);
         }
     };
 
+    let recording = app
+        .macros
+        .recording()
+        .map(|r| tr!("錄製巨集 @{register}", register = r));
+    if let Some(status) = app.status.as_ref().or(recording.as_ref()) {
+        let area = Rect::new(full.x, full.bottom().max(1) - 1, full.width, 1);
+        f.render_widget(StatusLine(status), area);
+    }
+
+    let setting = app.settings.selected().map_or("", |s| s.key);
+    let prompt = match (app.command.as_ref(), app.post.find_input.as_ref()) {
+        (Some(command), _) => Some((format!(":{}", command.value()), command.visual_cursor())),
+        (None, None) if app.board.filter_input.is_some() => app
+            .board
+            .filter_input
+            .as_ref()
+            .map(|input| (format!("/{}", input.value()), input.visual_cursor())),
+        (None, None) if app.settings.input.is_some() => app.settings.input.as_ref().map(|input| {
+            let prefix = format!("{} = ", setting);
+            let cursor = prefix.chars().count() + input.visual_cursor();
+            (format!("{}{}", prefix, input.value()), cursor)
+        }),
+        (None, Some(input)) => {
+            let regex = if app.post.find_regex { " [.*]" } else { "" };
+            let case = if app.post.find_case { " [Aa]" } else { "" };
+            let text = format!("/{}    {}{}", input.value(), regex, case);
+            Some((text, input.visual_cursor()))
+        }
+        _ => None,
+    };
+    if let Some((text, cursor)) = prompt {
+        let area = Rect::new(full.x, full.bottom().max(1) - 1, full.width, 1);
+        f.render_widget(StatusLine(&text), area);
+        let x = area.x + 1 + cursor as u16;
+        f.set_cursor(x.min(area.right().saturating_sub(1)), area.y);
+    }
+
+    if let (Page::Post, Some(state)) = (app.page, app.post.participants_state.as_mut()) {
+        let popup = ParticipantsPopup {
+            participants: &app.post.participants,
+            followed: &app.post.followed,
+            blocked: &app.post.blocked,
+        };
+        f.render_stateful_widget(popup, centered_rect(60, 60, size), state);
+    }
+
+    if let (Page::Post, Some(state)) = (app.page, app.post.reply_state.as_mut()) {
+        let popup = ReplyPopup {
+            replies: &app.post.replies,
+        };
+        f.render_stateful_widget(popup, centered_rect(60, 40, size), state);
+    }
+
+    if let (Page::Post, Some(state)) = (app.page, app.post.conversation_state.as_mut()) {
+        let popup = ConversationPopup {
+            nodes: &app.post.conversation,
+            post: &app.post.data,
+        };
+        f.render_stateful_widget(popup, centered_rect(70, 70, size), state);
+    }
+
+    if let (Page::Post, Some(state)) = (app.page, app.summary.as_ref()) {
+        f.render_widget(SummaryPopup { state }, centered_rect(70, 70, size));
+    }
+
+    if let (Page::Post, Some(viewer)) = (app.page, app.post.image.as_mut()) {
+        let popup = ImagePopup {
+            image: app.post.images.get(viewer.url()).and_then(Option::as_ref),
+            mosaic: !app.graphics,
+        };
+        f.render_stateful_widget(popup, size, viewer);
+    }
+
     if app.loading {
-        let y = if size.height < 18 {
-            25
-        } else if size.height > 25 {
-            13
-        } else {
-            15
+        f.render_widget(Skeleton { page: app.page }, size);
+    }
+
+    if let Some(state) = app.digest_state.as_mut() {
+        let popup = DigestPopup {
+            entries: &app.digest,
         };
-        let area = centered_rect(10, y, size);
-        f.render_widget(Loading, area);
+        f.render_stateful_widget(popup, centered_rect(70, 60, size), state);
+    }
+
+    if app.show_metrics {
+        let width = size.width.min(60);
+        let area = Rect::new(size.right() - width, size.y, width, size.height.min(10));
+        f.render_widget(MetricsOverlay, area);
+    }
+
+    if let Some(confirm) = &app.confirm {
+        let area = centered_rect(40, 25, size);
+        f.render_widget(ConfirmDialog(confirm), area);
+    }
+
+    let terminal = &app.config.terminal;
+    if terminal.replace_glyphs || !terminal.glyph_widths.is_empty() {
+        f.render_widget(GlyphFilter { config: terminal }, full);
+    }
+
+    if !app.color {
+        f.render_widget(Monochrome, full);
     }
 }
 
@@ -0,0 +1,67 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
+};
+
+use super::state::ModerationPageState;
+
+pub struct ModerationPageUI;
+
+// deletions stand out from locks and moves
+fn action_color(action: &str) -> Color {
+    if action.contains('刪') {
+        Color::LightRed
+    } else if action.contains('鎖') {
+        Color::LightYellow
+    } else {
+        Color::LightBlue
+    }
+}
+
+impl StatefulWidget for ModerationPageUI {
+    type State = ModerationPageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title(tr!("{board} - 處理紀錄", board = state.name))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+
+        if state.items.is_empty() {
+            Paragraph::new(tr!("沒有處理紀錄，或看板不公開處理紀錄"))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let reason_style = Style::default().fg(Color::DarkGray);
+        let items: Vec<ListItem> = state
+            .items
+            .iter()
+            .map(|entry| {
+                let action = Style::default().fg(action_color(&entry.action));
+                let reason = if entry.reason.is_empty() {
+                    tr!("未註明理由").to_string()
+                } else {
+                    entry.reason.to_owned()
+                };
+                ListItem::new(vec![
+                    Line::from(vec![
+                        Span::raw(format!("{} ", entry.date)),
+                        Span::styled(format!("[{}] ", entry.action), action),
+                        Span::raw(entry.title.as_str()),
+                    ]),
+                    Line::styled(format!("  {}: {}", entry.moderator, reason), reason_style),
+                ])
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        StatefulWidget::render(list, area, buf, &mut state.state);
+    }
+}
@@ -0,0 +1,16 @@
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+// strip every color from rendered cells but keep modifiers like reversed
+pub struct Monochrome;
+
+impl Widget for Monochrome {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use super::{post::render_desc, state::NewsPageState};
+
+pub struct NewsPageUI;
+
+impl StatefulWidget for NewsPageUI {
+    type State = NewsPageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let layout = Layout::default()
+            .constraints([Constraint::Min(0)])
+            .horizontal_margin(1)
+            .split(area);
+
+        let block = Block::default()
+            .title(tr!("GNN 新聞"))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+
+        let items: Vec<ListItem> = state
+            .items
+            .iter()
+            .map(|item| {
+                ListItem::new(vec![
+                    Line::from(item.title.as_ref()),
+                    Line::from(format!("  {}", item.summary)),
+                ])
+            })
+            .collect();
+
+        let selected_style = Style::default().add_modifier(Modifier::REVERSED);
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(selected_style);
+        StatefulWidget::render(list, layout[0], buf, &mut state.state);
+    }
+}
+
+pub struct ArticlePageUI;
+
+impl StatefulWidget for ArticlePageUI {
+    type State = NewsPageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Min(0)])
+            .split(area);
+
+        state.scroll_size(layout[1].height as usize);
+
+        let article = &state.article;
+        let boards = article
+            .boards
+            .iter()
+            .take(9)
+            .enumerate()
+            .map(|(i, b)| format!("[{}] {}", i + 1, b.name))
+            .collect::<Vec<String>>()
+            .join("  ");
+
+        Paragraph::new(vec![
+            Line::from(article.title.as_ref()),
+            Line::from(article.date.as_ref()),
+            Line::from(tr!("相關看板: {boards}", boards = boards)),
+        ])
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL))
+        .render(layout[0], buf);
+
+        render_desc(layout[1], buf, &article.desc, state.scroll_offset);
+    }
+}
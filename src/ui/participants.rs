@@ -0,0 +1,58 @@
+use std::collections::HashSet;
+
+use bahamut::api::post::Participant;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+pub struct ParticipantsPopup<'a> {
+    pub participants: &'a [Participant],
+    pub followed: &'a HashSet<String>,
+    pub blocked: &'a HashSet<String>,
+}
+
+impl StatefulWidget for ParticipantsPopup<'_> {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Clear.render(area, buf);
+
+        let items: Vec<ListItem> = self
+            .participants
+            .iter()
+            .map(|p| {
+                let mark = if self.blocked.contains(&p.user.id) {
+                    tr!(" [封鎖]")
+                } else if self.followed.contains(&p.user.id) {
+                    tr!(" [追蹤]")
+                } else {
+                    ""
+                };
+                let floors = tr!(
+                    "{count} 樓 ({first}~{last})",
+                    count = p.floors.len(),
+                    first = p.first_floor(),
+                    last = p.last_floor()
+                );
+                ListItem::new(Line::from(format!(
+                    "{} {} {} - {}{}",
+                    p.user.id,
+                    p.user.name,
+                    p.user.badge(),
+                    floors,
+                    mark
+                )))
+            })
+            .collect();
+
+        let title = tr!("參與者 {count} 人", count = self.participants.len());
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        StatefulWidget::render(list, area, buf, state);
+    }
+}
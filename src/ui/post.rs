@@ -1,20 +1,85 @@
-use bahamut::api::post::PostContent;
+use std::collections::HashSet;
+
+use bahamut::api::{
+    post::{FloorKind, Post, PostContent},
+    preview::{find_links, is_animated},
+    user::User,
+};
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Direction, Layout, Rect},
-    text::Line,
-    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget, Wrap},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, StatefulWidget, Widget, Wrap},
 };
 
-use super::state::PostPageState;
+use crate::{
+    config::{HeaderConfig, ThemeConfig},
+    diff::DiffKind,
+    find::FindQuery,
+};
+
+use super::{
+    comment::comment_lines, header::ThreadHeader, hyperlink::Hyperlink, state::PostPageState,
+};
 
 #[derive(Default)]
-pub struct PostPageUI;
+pub struct PostPageUI {
+    pub dual_pane_width: u16,
+    pub subscribed: bool,
+    pub muted: bool,
+    pub bookmarked: bool,
+    pub header: HeaderConfig,
+    pub theme: ThemeConfig,
+    // user id of the logged in session
+    pub me: Option<String>,
+    pub followed: HashSet<String>,
+    // avatar next to the author, drawn over by the graphics protocol when it's on
+    pub avatars: bool,
+    pub inline_comments: bool,
+    pub graphics: bool,
+}
+
+impl PostPageUI {
+    // role label and color of the floor author, myself first
+    fn role(&self, post: &Post, current: &PostContent) -> Option<(&'static str, Color)> {
+        let theme = &self.theme;
+        let user_id = current.user.id.as_str();
+        let (label, color) = if current.kind == FloorKind::Notice {
+            (tr!("公告"), &theme.notice)
+        } else if self.me.as_deref() == Some(user_id) {
+            (tr!("我"), &theme.me)
+        } else if post.author.as_ref().is_some_and(|a| a.id == user_id) {
+            (tr!("樓主"), &theme.starter)
+        } else if post.moderators.iter().any(|m| m == user_id) {
+            (tr!("版主"), &theme.moderator)
+        } else if self.followed.contains(user_id) {
+            (tr!("追蹤"), &theme.followed)
+        } else {
+            return None;
+        };
+
+        Some((label, color.parse().ok()?))
+    }
+}
 
 impl StatefulWidget for PostPageUI {
     type State = PostPageState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.dual_pane = self.dual_pane_width > 0 && area.width >= self.dual_pane_width;
+        let area = if state.dual_pane {
+            let pane = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(area);
+
+            render_comment_pane(pane[1], buf, state, &self.theme);
+            pane[0]
+        } else {
+            area
+        };
+
         let layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(6), Constraint::Min(0)])
@@ -30,34 +95,383 @@ impl StatefulWidget for PostPageUI {
         // user
         let current = PostContent::default();
         let current = state.current().map_or(&current, |x| x);
+        let mut user_block = Block::default().borders(Borders::ALL);
+        let mut user_style = Style::default();
+        if let Some((label, color)) = self.role(&state.data, current) {
+            user_style = user_style.fg(color).add_modifier(Modifier::BOLD);
+            user_block = user_block
+                .title(label)
+                .border_style(Style::default().fg(color));
+        } else if let Some(color) = self.theme.poster_color(current.user.id.as_ref()) {
+            user_style = user_style.fg(color);
+        }
+
+        let user_area = user_block.inner(top[0]);
+        // notices have no author
+        let badge = if current.user.id.is_empty() {
+            String::new()
+        } else {
+            format!("{} {}", current.user.badge(), current.user.race)
+        };
         Paragraph::new(vec![
-            Line::from(current.user.id.as_ref()),
-            Line::from(current.user.name.as_ref()),
-            Line::from(current.user.carrer.to_string()),
-            Line::from(current.user.race.to_string()),
-            Line::from(format!("lv.{}", current.user.lv)),
+            Line::styled(current.user.id.as_str(), user_style),
+            Line::styled(current.user.name.as_str(), user_style),
+            Line::from(badge),
+            Line::styled(
+                current.user.title.as_str(),
+                Style::default().fg(Color::DarkGray),
+            ),
         ])
-        .block(Block::default().borders(Borders::ALL))
+        .block(user_block)
         .render(top[0], buf);
 
-        Paragraph::new(vec![
-            Line::from(state.data.title.as_ref()),
-            Line::from(format!("{}樓", current.floor)),
-            Line::from(current.date.as_ref()),
-        ])
-        .block(Block::default().borders(Borders::ALL))
+        let width = current
+            .user
+            .id
+            .len()
+            .min(top[0].width.saturating_sub(2) as usize) as u16;
+        let author = Hyperlink::new(top[0].x + 1, top[0].y + 1, width, current.user.home_url());
+
+        let mut avatar_area = None;
+        if self.avatars && user_area.width >= AVATAR_WIDTH * 3 && !current.user.id.is_empty() {
+            let area = Rect::new(
+                user_area.right() - AVATAR_WIDTH,
+                user_area.y,
+                AVATAR_WIDTH,
+                user_area.height,
+            );
+            let loaded = matches!(state.avatars.get(&current.user.id), Some(Some(Ok(_))));
+            if self.graphics && loaded {
+                Clear.render(area, buf);
+                avatar_area = Some((current.user.id.to_owned(), area));
+            } else {
+                let color = self
+                    .role(&state.data, current)
+                    .map(|(_, color)| color)
+                    .or(self.theme.poster_color(&current.user.id))
+                    .unwrap_or(Color::DarkGray);
+                render_badge(area, buf, &current.user, color);
+            }
+        }
+
+        ThreadHeader {
+            post: &state.data,
+            current,
+            page: *state.pages.get(state.index as usize).unwrap_or(&state.page),
+            last_page: state.last_page,
+            subscribed: self.subscribed,
+            muted: self.muted,
+            bookmarked: self.bookmarked,
+            hidden: state.hidden_count(),
+            edited: state.is_edited(),
+            config: &self.header,
+        }
         .render(top[1], buf);
 
-        // desc
-        let desc: Vec<Line> = current
-            .desc
-            .iter()
-            .skip(state.scroll_offset)
-            .map(|s| Line::from(s.to_owned()))
-            .collect();
-        Paragraph::new(desc)
-            .wrap(Wrap { trim: false })
-            .block(Block::default().borders(Borders::ALL))
-            .render(layout[1], buf);
+        // outline
+        let desc_area = if let Some(mut outline_state) = state.outline.clone() {
+            let pane = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(30), Constraint::Min(0)])
+                .split(layout[1]);
+
+            let items: Vec<ListItem> = current
+                .outline()
+                .into_iter()
+                .map(|o| ListItem::new(Line::from(o.text)))
+                .collect();
+
+            let list = List::new(items)
+                .block(Block::default().title(tr!("目錄")).borders(Borders::ALL))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+            StatefulWidget::render(list, pane[0], buf, &mut outline_state);
+            pane[1]
+        } else {
+            layout[1]
+        };
+
+        // attachments under the content
+        let desc_area = if current.attachments.is_empty() {
+            desc_area
+        } else {
+            let height = current.attachments.len().min(5) as u16 + 2;
+            let pane = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(height)])
+                .split(desc_area);
+
+            let size_style = Style::default().fg(Color::DarkGray);
+            let items: Vec<Line> = current
+                .attachments
+                .iter()
+                .map(|a| {
+                    let size = a
+                        .size
+                        .as_ref()
+                        .map_or(String::new(), |s| format!(" ({})", s));
+                    Line::from(vec![
+                        Span::raw(a.name.as_str()),
+                        Span::styled(size, size_style),
+                    ])
+                })
+                .collect();
+            Paragraph::new(items)
+                .block(
+                    Block::default()
+                        .title(tr!("附件 - a 下載"))
+                        .borders(Borders::ALL),
+                )
+                .render(pane[1], buf);
+            pane[0]
+        };
+
+        let desc_area = if self.inline_comments && !state.dual_pane {
+            render_inline_comments(desc_area, buf, state, &self.theme)
+        } else {
+            desc_area
+        };
+
+        match state.diff.as_ref() {
+            Some(diff) => render_diff(desc_area, buf, diff, state.scroll_offset),
+            None => render_floor_desc(desc_area, buf, &current.desc, state),
+        }
+        state.links = vec![author];
+        state.avatar_area = avatar_area;
+    }
+}
+
+// cells of the avatar, about square with two rows of text a cell
+static AVATAR_WIDTH: u16 = 8;
+
+// initial of the name on the color of the author
+fn render_badge(area: Rect, buf: &mut Buffer, user: &User, color: Color) {
+    let initial: String = user
+        .name
+        .chars()
+        .chain(user.id.chars())
+        .take(1)
+        .flat_map(char::to_uppercase)
+        .collect();
+    let style = Style::default()
+        .fg(Color::Black)
+        .bg(color)
+        .add_modifier(Modifier::BOLD);
+    let height = area.height.min(AVATAR_WIDTH / 2);
+    let area = Rect::new(area.x, area.y, area.width, height);
+    let mut lines = vec![Line::from(""); (height.saturating_sub(1) / 2) as usize];
+    lines.push(Line::from(initial));
+    Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .style(style)
+        .render(area, buf);
+}
+
+// every block takes at least one row and every char one cell,
+// so only the blocks and chars that fit in the viewport are laid out
+fn viewport(area: Rect) -> (usize, usize) {
+    let height = area.height.saturating_sub(2) as usize;
+    let width = area.width.saturating_sub(2) as usize;
+    (height, height * width)
+}
+
+fn visible_part(s: &str, cells: usize) -> &str {
+    match s.char_indices().nth(cells) {
+        Some((i, _)) => &s[..i],
+        None => s,
+    }
+}
+
+fn render_diff(area: Rect, buf: &mut Buffer, diff: &[Vec<(DiffKind, String)>], offset: usize) {
+    let (height, _) = viewport(area);
+    let lines: Vec<Line> = diff
+        .iter()
+        .skip(offset)
+        .take(height)
+        .map(|line| {
+            let spans: Vec<Span> = line
+                .iter()
+                .map(|(kind, text)| match kind {
+                    DiffKind::Same => Span::raw(text.as_str()),
+                    DiffKind::Added => Span::styled(
+                        text.as_str(),
+                        Style::default()
+                            .fg(Color::Green)
+                            .add_modifier(Modifier::UNDERLINED),
+                    ),
+                    DiffKind::Removed => Span::styled(
+                        text.as_str(),
+                        Style::default()
+                            .fg(Color::Red)
+                            .add_modifier(Modifier::CROSSED_OUT),
+                    ),
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(tr!("編輯差異"))
+                .borders(Borders::ALL),
+        )
+        .render(area, buf);
+}
+
+// content blocks of a floor, also used by other article readers
+pub fn render_desc(area: Rect, buf: &mut Buffer, desc: &[String], offset: usize) {
+    let (height, cells) = viewport(area);
+    let desc: Vec<Line> = desc
+        .iter()
+        .skip(offset)
+        .take(height)
+        .map(|s| Line::from(visible_part(s, cells)))
+        .collect();
+    Paragraph::new(desc)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL))
+        .render(area, buf);
+}
+
+// with line numbers, the visual selection, search matches, gif badges and preview cards under the block of their link
+fn render_floor_desc(area: Rect, buf: &mut Buffer, desc: &[String], state: &PostPageState) {
+    let (height, cells) = viewport(area);
+    let card_style = Style::default().fg(Color::DarkGray);
+    let gif_style = Style::default()
+        .fg(Color::Magenta)
+        .add_modifier(Modifier::BOLD);
+    let selection = state.visual_range();
+    let numbered = state.line_numbers || selection.is_some();
+    let desc: Vec<Line> = desc
+        .iter()
+        .enumerate()
+        .skip(state.scroll_offset)
+        .take(height)
+        .flat_map(|(i, s)| {
+            let style = match &selection {
+                Some(range) if range.contains(&i) => {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                }
+                _ => Style::default(),
+            };
+            let mut spans = highlight(visible_part(s, cells), style, state.find.as_ref());
+            if is_animated(s) {
+                spans.insert(0, Span::styled("[GIF] ", gif_style));
+            }
+            if numbered {
+                spans.insert(0, Span::styled(format!("{:>3} ", i + 1), card_style));
+            }
+
+            let mut lines = vec![Line::from(spans)];
+            let cards = find_links(s)
+                .filter_map(|link| state.previews.get(link)?.as_ref())
+                .filter(|card| !card.is_empty());
+            for card in cards {
+                lines.push(Line::styled(
+                    format!("  ┃ {}", card.title),
+                    card_style.add_modifier(Modifier::BOLD),
+                ));
+                if !card.description.is_empty() {
+                    lines.push(Line::styled(
+                        format!("  ┃ {}", visible_part(&card.description, cells)),
+                        card_style,
+                    ));
+                }
+            }
+            lines
+        })
+        .collect();
+    Paragraph::new(desc)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL))
+        .render(area, buf);
+}
+
+fn highlight<'a>(text: &'a str, style: Style, find: Option<&FindQuery>) -> Vec<Span<'a>> {
+    let found = style.fg(Color::Black).bg(Color::Yellow);
+    let mut spans = vec![];
+    let mut last = 0;
+    for range in find.map_or(vec![], |f| f.ranges(text)) {
+        if range.start > last {
+            spans.push(Span::styled(&text[last..range.start], style));
+        }
+        spans.push(Span::styled(&text[range.clone()], found));
+        last = range.end;
     }
+    if last < text.len() || spans.is_empty() {
+        spans.push(Span::styled(&text[last..], style));
+    }
+    spans
+}
+
+// comments shown under the content, the rest are in the comment page
+static INLINE_COMMENTS: usize = 3;
+
+// the newest comments of the floor, one a line, returns the area left for the content
+fn render_inline_comments(
+    area: Rect,
+    buf: &mut Buffer,
+    state: &PostPageState,
+    theme: &ThemeConfig,
+) -> Rect {
+    let comments = state
+        .current()
+        .and_then(|content| state.comments.get(&content.id));
+    let lines = match comments {
+        Some(Some(comments)) if comments.is_empty() => return area,
+        Some(Some(comments)) => {
+            let name_style = Style::default().add_modifier(Modifier::BOLD);
+            comments
+                .iter()
+                .skip(comments.len().saturating_sub(INLINE_COMMENTS))
+                .map(|comment| {
+                    let style = theme
+                        .poster_color(comment.userid.as_ref())
+                        .map_or(name_style, |color| name_style.fg(color));
+                    Line::from(vec![
+                        Span::styled(comment.nick.as_str(), style),
+                        Span::raw(format!(": {}", comment.content.replace('\n', " "))),
+                    ])
+                })
+                .collect()
+        }
+        _ => vec![Line::from(tr!("載入中..."))],
+    };
+
+    let count = comments.and_then(Option::as_ref).map_or(0, Vec::len);
+    let pane = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(lines.len() as u16 + 2),
+        ])
+        .split(area);
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(tr!("留言 {count} 則 - o 查看全部", count = count))
+                .borders(Borders::ALL),
+        )
+        .render(pane[1], buf);
+    pane[0]
+}
+
+fn render_comment_pane(area: Rect, buf: &mut Buffer, state: &PostPageState, theme: &ThemeConfig) {
+    let block = Block::default().title(tr!("留言")).borders(Borders::ALL);
+    let comments = state
+        .current()
+        .and_then(|content| state.comments.get(&content.id));
+
+    let items = match comments {
+        Some(Some(comments)) if comments.is_empty() => vec![Line::from(tr!("此篇沒有任何留言"))],
+        Some(Some(comments)) => comment_lines(comments, theme),
+        _ => vec![Line::from(tr!("載入中..."))],
+    };
+
+    Paragraph::new(items)
+        .wrap(Wrap { trim: true })
+        .block(block)
+        .render(area, buf);
 }
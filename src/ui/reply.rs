@@ -0,0 +1,35 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+pub struct ReplyPopup<'a> {
+    pub replies: &'a [String],
+}
+
+impl StatefulWidget for ReplyPopup<'_> {
+    type State = ListState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Clear.render(area, buf);
+
+        // one line each, the whole text is what gets copied
+        let items: Vec<ListItem> = self
+            .replies
+            .iter()
+            .map(|reply| ListItem::new(Line::from(reply.replace('\n', " "))))
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(tr!("回覆範本 - <Enter> 複製"))
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        StatefulWidget::render(list, area, buf, state);
+    }
+}
@@ -1,6 +1,6 @@
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::Line,
     widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
@@ -21,7 +21,9 @@ impl StatefulWidget for SearchPageUI {
             .split(area);
 
         // input
-        let block = Block::default().borders(Borders::ALL).title("看板搜尋");
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(tr!("看板搜尋"));
         let width = layout[0].width.max(3) - 3;
         let scroll = state.input.visual_scroll(width as usize);
         let value = state.input.value();
@@ -29,7 +31,7 @@ impl StatefulWidget for SearchPageUI {
             InputMode::Edit => value,
             InputMode::Normal => {
                 if value.is_empty() {
-                    "輸入 'a'/'e'/'i'/'o' 開始搜尋"
+                    tr!("輸入 'a'/'e'/'i'/'o' 開始搜尋")
                 } else {
                     value
                 }
@@ -55,7 +57,14 @@ impl StatefulWidget for SearchPageUI {
         let items: Vec<ListItem> = state
             .items
             .iter()
-            .map(|item| ListItem::new(vec![Line::from(item.name.as_ref())]))
+            .map(|item| {
+                if state.ignored.contains(&item.id) {
+                    let name = format!("{} {}", item.name, tr!("(已忽略)"));
+                    ListItem::new(Line::styled(name, Style::default().fg(Color::DarkGray)))
+                } else {
+                    ListItem::new(vec![Line::from(item.name.as_ref())])
+                }
+            })
             .collect();
 
         let block = Block::default().borders(Borders::ALL);
@@ -65,6 +74,39 @@ impl StatefulWidget for SearchPageUI {
             .block(block)
             .highlight_style(selected_style);
 
-        StatefulWidget::render(list, layout[1], buf, &mut state.state);
+        // saved searches panel
+        let list_area = if let Some(mut saved_state) = state.saved_state.clone() {
+            let pane = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Percentage(40)])
+                .split(layout[1]);
+
+            let saved: Vec<ListItem> = state
+                .saved
+                .iter()
+                .map(|s| {
+                    let count = match state.counts.get(&s.key()) {
+                        Some(Some(0)) => String::new(),
+                        Some(Some(count)) => tr!(" ({count} 則新結果)", count = count),
+                        _ => " (...)".to_string(),
+                    };
+                    ListItem::new(Line::from(format!("{}: {}{}", s.name, s.query, count)))
+                })
+                .collect();
+
+            let list = List::new(saved)
+                .block(
+                    Block::default()
+                        .title(tr!("已儲存的搜尋"))
+                        .borders(Borders::ALL),
+                )
+                .highlight_style(selected_style);
+            StatefulWidget::render(list, pane[1], buf, &mut saved_state);
+            pane[0]
+        } else {
+            layout[1]
+        };
+
+        StatefulWidget::render(list, list_area, buf, &mut state.state);
     }
 }
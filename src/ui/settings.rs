@@ -0,0 +1,54 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::{config::Config, settings::SETTINGS};
+
+use super::state::SettingsPageState;
+
+pub struct SettingsPageUI<'a> {
+    pub config: &'a Config,
+}
+
+impl StatefulWidget for SettingsPageUI<'_> {
+    type State = SettingsPageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let layout = Layout::default()
+            .constraints([Constraint::Min(0), Constraint::Length(3)])
+            .split(area);
+
+        let width = SETTINGS.iter().map(|s| s.key.len()).max().unwrap_or(0);
+        let value_style = Style::default().fg(Color::Cyan);
+        let items: Vec<ListItem> = SETTINGS
+            .iter()
+            .map(|s| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:width$}  ", s.key, width = width)),
+                    Span::styled(s.value(self.config), value_style),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(tr!("設定"))
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        StatefulWidget::render(list, layout[0], buf, &mut state.state);
+
+        // what the selected one does
+        let desc = state.selected().map_or("", |s| tr!(s.desc));
+        Paragraph::new(desc)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL))
+            .render(layout[1], buf);
+    }
+}
@@ -1,11 +1,58 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+    sync::{mpsc::Sender, Arc},
+    time::{Duration, Instant},
+};
+
 use bahamut::api::{
-    board::BoardPost,
-    post::{Post, PostComment, PostContent},
+    anime::{AnimeEpisode, AnimeHome},
+    board::{BoardKeyword, BoardPost, ModerationEntry},
+    cache::{BoardCacheStats, Cache, CacheStats},
+    client::BahamutClient,
+    creation::{CreationArticle, CreationItem},
+    news::{NewsArticle, NewsHeadline},
+    post::{
+        FloorKind, Participant, Post, PostComment, PostContent, PostDescription,
+        PostPageUrlParameter, ReplyNode,
+    },
+    preview::{is_animated, is_image, LinkPreview},
     search::SearchResult,
+    DN,
 };
-use ratatui::widgets::ListState;
+use image::DynamicImage;
+use ratatui::{layout::Rect, widgets::ListState};
 use tui_input::Input;
 
+use super::{
+    action::KeyMap,
+    activity::{parse_day, today},
+    confirm::Confirm,
+    count::CountState,
+    history::{History, HistoryEntry},
+    hyperlink::Hyperlink,
+    macros::MacroState,
+    timeline::reply_time,
+};
+use crate::{
+    channel::DataRequestMsg,
+    config::{Config, FloorFilterConfig},
+    diff::{word_diff, DiffKind},
+    find::FindQuery,
+    settings::{Setting, SETTINGS},
+    store::{
+        search_key, thread_key, with_lock, BoardIgnoreStore, Bookmark, BookmarkStore, MuteStore,
+        ReadPosition, ReadPositionStore, SavedSearch, SearchQuery, SearchStore, Store,
+        ThreadLinkStore, TranslationStore, UserListStore,
+    },
+    subscription::{DigestEntry, SubscriptionStore, Unread},
+    summary::thread_text,
+    template::fill_template,
+    terminal::{supports_color, supports_graphics, supports_hyperlinks},
+    tts::Speaker,
+};
+
 #[derive(Clone, PartialEq, Default)]
 pub enum InputMode {
     #[default]
@@ -13,11 +60,24 @@ pub enum InputMode {
     Edit,
 }
 
+#[derive(Clone, Copy, PartialEq, Default)]
 pub enum Page {
+    #[default]
     Search,
     Board,
     Post,
     Comment,
+    Cache,
+    Anime,
+    News,
+    Article,
+    Creation,
+    CreationArticle,
+    Bookmark,
+    Activity,
+    Moderation,
+    Timeline,
+    Settings,
 }
 
 pub trait CursorMoveable {
@@ -71,12 +131,837 @@ pub struct AppState {
     pub board: BoardPageState,
     pub post: PostPageState,
     pub comment: PostCommentState,
+    pub cache: CachePageState,
+    pub anime: AnimePageState,
+    pub news: NewsPageState,
+    pub creation: CreationPageState,
+    pub bookmarks: BookmarkPageState,
+    pub activity: ActivityPageState,
+    pub moderation: ModerationPageState,
+    pub timeline: TimelinePageState,
+    pub settings: SettingsPageState,
     pub loading: bool,
+    pub status: Option<String>,
+    pub speaker: Speaker,
+    pub summary: Option<SummaryState>,
+    pub config: Config,
+    pub read_position: ReadPositionStore,
+    pub macros: MacroState,
+    // keys mapped by [keymap] and :map
+    pub keymap: KeyMap,
+    pub count: CountState,
+    pub history: History,
+    pub confirm: Option<Confirm>,
+    // updates of the subscriptions listed on launch
+    pub digest: Vec<DigestEntry>,
+    pub digest_state: Option<ListState>,
+    pub color: bool,
+    pub hyperlinks: bool,
+    // kitty graphics protocol for the image viewer
+    pub graphics: bool,
+    pub subscription: SubscriptionStore,
+    pub thread_link: ThreadLinkStore,
+    pub bookmark: BookmarkStore,
+    pub mute: MuteStore,
+    pub searches: SearchStore,
+    pub translation: TranslationStore,
+    // titles sent to the translation backend
+    pub translating: HashSet<String>,
+    pub link_mark: Option<String>,
+    pub show_metrics: bool,
+    pub checking_update: bool,
+    // request to send again after the 18+ confirmation
+    pub age_gate: Option<DataRequestMsg>,
+    // `:` command being typed
+    pub command: Option<Input>,
+    // shared with the fetcher
+    pub client: Arc<BahamutClient>,
 }
 
 impl AppState {
-    pub fn new() -> AppState {
-        AppState::default()
+    pub fn new(config: Config, client: Arc<BahamutClient>) -> AppState {
+        let users = UserListStore::load();
+        AppState {
+            color: supports_color(config.terminal.color),
+            hyperlinks: supports_hyperlinks(config.terminal.hyperlinks),
+            graphics: supports_graphics(config.terminal.graphics),
+            search: SearchPageState {
+                ignored: BoardIgnoreStore::load().boards,
+                ..SearchPageState::default()
+            },
+            board: BoardPageState {
+                unread_first: config.navigation.unread_first,
+                hide_deleted: config.filter.hide_deleted,
+                ..BoardPageState::default()
+            },
+            post: PostPageState {
+                filter: config.filter.clone(),
+                line_numbers: config.layout.line_numbers,
+                followed: users.follow,
+                blocked: users.block,
+                ..PostPageState::default()
+            },
+            config,
+            read_position: ReadPositionStore::load(),
+            subscription: SubscriptionStore::load(),
+            thread_link: ThreadLinkStore::load(),
+            bookmark: BookmarkStore::load(),
+            mute: MuteStore::load(),
+            searches: SearchStore::load(),
+            translation: TranslationStore::load(),
+            client,
+            ..AppState::default()
+        }
+    }
+
+    pub fn save_read_position(&mut self) {
+        if self.config.session.read_only {
+            return;
+        }
+
+        if let Some(position) = self.post.position() {
+            let url = self.post.url.as_ref();
+            if let Ok((store, _)) = ReadPositionStore::update(|store| store.set(url, position)) {
+                self.read_position = store;
+            }
+        }
+    }
+
+    pub fn status(&mut self, status: String) {
+        self.status = Some(status);
+    }
+
+    // every action changing data should check this first
+    pub fn ensure_writable(&mut self) -> bool {
+        if self.config.session.read_only {
+            self.status(tr!("唯讀模式下無法執行此操作").to_string());
+            false
+        } else {
+            true
+        }
+    }
+
+    pub fn toggle_subscription(&mut self) {
+        if !self.ensure_writable() {
+            return;
+        }
+
+        let last_floor = self
+            .post
+            .data
+            .posts
+            .iter()
+            .map(|p| p.floor)
+            .max()
+            .unwrap_or(0)
+            .max(self.post.data.floor);
+
+        let (url, title) = (self.post.url.as_ref(), self.post.data.title.as_ref());
+        let status = match SubscriptionStore::update(|store| store.toggle(url, title, last_floor)) {
+            Err(e) => tr!("儲存訂閱失敗: {error}", error = e),
+            Ok((store, subscribed)) => {
+                self.subscription = store;
+                if subscribed {
+                    tr!("已訂閱此貼文").to_string()
+                } else {
+                    tr!("已取消訂閱此貼文").to_string()
+                }
+            }
+        };
+        self.status(status);
+    }
+
+    pub fn toggle_board_subscription(&mut self) {
+        if !self.ensure_writable() {
+            return;
+        }
+
+        let (id, name) = (self.board.id.as_ref(), self.board.name.as_ref());
+        let status = match SubscriptionStore::update(|store| store.toggle_board(id, name)) {
+            Err(e) => tr!("儲存訂閱失敗: {error}", error = e),
+            Ok((store, subscribed)) => {
+                self.subscription = store;
+                if subscribed {
+                    tr!("已訂閱此看板").to_string()
+                } else {
+                    tr!("已取消訂閱此看板").to_string()
+                }
+            }
+        };
+        self.status(status);
+    }
+
+    // reload first so the floors and threads found by the watch command are picked up
+    pub fn next_unread(&mut self) -> Option<Unread> {
+        self.subscription = SubscriptionStore::load();
+        let unread = self.subscription.unread(&self.read_position, &self.mute);
+        let current = unread.iter().position(|u| match (u, self.page) {
+            (Unread::Thread(url), Page::Post) => {
+                thread_key(url.as_ref()) == thread_key(self.post.url.as_ref())
+            }
+            (Unread::Board(id, _), Page::Board) => *id == self.board.id,
+            _ => false,
+        });
+
+        let next = current.map_or(0, |i| i + 1) % unread.len().max(1);
+        unread.get(next).cloned()
+    }
+
+    pub fn open_digest(&mut self) {
+        self.digest = self.subscription.digest(&self.read_position, &self.mute);
+        if !self.digest.is_empty() {
+            let mut state = ListState::default();
+            state.select(Some(0));
+            self.digest_state = Some(state);
+        }
+    }
+
+    pub fn digest_next(&mut self) {
+        let max = self.digest.len().max(1);
+        if let Some(state) = self.digest_state.as_mut() {
+            state.select(state.selected().map(|i| (i + 1) % max));
+        }
+    }
+
+    pub fn digest_previous(&mut self) {
+        let max = self.digest.len().max(1);
+        if let Some(state) = self.digest_state.as_mut() {
+            state.select(state.selected().map(|i| (i + max - 1) % max));
+        }
+    }
+
+    // the digest is closed with the selected entry taken out
+    pub fn take_digest(&mut self) -> Option<Unread> {
+        let i = self.digest_state.take()?.selected()?;
+        let entry = std::mem::take(&mut self.digest).into_iter().nth(i)?;
+        Some(entry.unread)
+    }
+
+    pub fn toggle_unread_first(&mut self) {
+        self.board.unread_first = !self.board.unread_first;
+        self.board.reorder(&self.read_position);
+        self.status(if self.board.unread_first {
+            tr!("未讀的貼文排在前面").to_string()
+        } else {
+            tr!("依原本的順序排列").to_string()
+        });
+    }
+
+    // titles of the board list or the timeline, each screen is toggled on its own
+    pub fn toggle_translation(&mut self) {
+        if self
+            .config
+            .translate
+            .backend(&self.config.summary)
+            .is_none()
+        {
+            self.status(
+                tr!("請先在設定檔的 [translate] 設定 command，或在 [summary] 設定 endpoint")
+                    .to_string(),
+            );
+            return;
+        }
+
+        let translate = match self.page {
+            Page::Board => &mut self.board.translate,
+            Page::Timeline => &mut self.timeline.translate,
+            _ => return,
+        };
+        *translate = !*translate;
+        let on = *translate;
+        self.status(if on {
+            tr!(
+                "翻譯標題為 {language}",
+                language = self.config.translate.language
+            )
+            .to_string()
+        } else {
+            tr!("顯示原本的標題").to_string()
+        });
+    }
+
+    // kept on disk, so a title is only translated once
+    pub fn translated(&mut self, titles: Vec<String>, translated: Result<Vec<String>, String>) {
+        for title in titles.iter() {
+            self.translating.remove(title);
+        }
+
+        let translated = match translated {
+            Ok(translated) => translated,
+            // not asked again and again for a broken backend
+            Err(e) => {
+                self.board.translate = false;
+                self.timeline.translate = false;
+                self.status(tr!("翻譯失敗: {error}", error = e).to_string());
+                return;
+            }
+        };
+
+        let language = self.config.translate.language.to_owned();
+        let pairs: Vec<(String, String)> = titles.into_iter().zip(translated).collect();
+        for (title, text) in pairs.iter() {
+            self.translation.insert(&language, title, text.to_owned());
+        }
+        // kept for this session only
+        if self.config.session.read_only {
+            return;
+        }
+        if let Err(e) = TranslationStore::update(|store| {
+            for (title, text) in pairs {
+                store.insert(&language, &title, text);
+            }
+        }) {
+            self.status(tr!("儲存翻譯失敗: {error}", error = e).to_string());
+        }
+    }
+
+    // popups and loading cover the page, so images and links of the page are left out
+    pub fn has_overlay(&self) -> bool {
+        self.confirm.is_some()
+            || self.loading
+            || self.digest_state.is_some()
+            || self.post.participants_state.is_some()
+            || self.post.reply_state.is_some()
+            || self.post.conversation_state.is_some()
+            || self.post.image.is_some()
+            || self.summary.is_some()
+    }
+
+    pub fn mark_board_read(&mut self) {
+        if self.config.session.read_only {
+            return;
+        }
+
+        self.subscription = with_lock(|| {
+            let mut store = SubscriptionStore::load();
+            if store.mark_board_read(self.board.id.as_ref()) {
+                store.save().unwrap_or(());
+            }
+            store
+        });
+    }
+
+    // history is only kept by the writable instance, without a status
+    pub fn record_search(&mut self, query: &str, board: Option<&str>) {
+        if self.config.session.read_only {
+            return;
+        }
+
+        let query = SearchQuery {
+            query: query.to_string(),
+            board: board.map(str::to_string),
+        };
+        if let Ok((store, _)) = SearchStore::update(|store| store.record(query)) {
+            self.searches = store;
+        }
+    }
+
+    pub fn toggle_saved_search(&mut self) {
+        if !self.ensure_writable() {
+            return;
+        }
+
+        let query = match self.board.query.as_ref() {
+            Some(query) => query.to_owned(),
+            None => {
+                self.status(tr!("請先在看板中搜尋").to_string());
+                return;
+            }
+        };
+        let search = SavedSearch {
+            query,
+            board: self.board.id.to_owned(),
+            name: self.board.name.to_owned(),
+            seen: self.board.items.iter().map(|i| i.url.to_owned()).collect(),
+        };
+        let status = match SearchStore::update(|store| store.toggle_saved(search)) {
+            Err(e) => tr!("儲存搜尋失敗: {error}", error = e),
+            Ok((store, saved)) => {
+                self.searches = store;
+                if saved {
+                    tr!("已儲存此搜尋").to_string()
+                } else {
+                    tr!("已取消儲存此搜尋").to_string()
+                }
+            }
+        };
+        self.status(status);
+    }
+
+    // the first result page of a saved search is seen when opened
+    pub fn mark_search_read(&mut self) {
+        let query = match self.board.query.as_ref() {
+            Some(query) if !self.config.session.read_only => query.to_owned(),
+            _ => return,
+        };
+
+        let board = self.board.id.to_owned();
+        if !self.searches.is_saved(&board, &query) {
+            return;
+        }
+
+        let urls: HashSet<String> = self.board.items.iter().map(|i| i.url.to_owned()).collect();
+        self.searches = with_lock(|| {
+            let mut store = SearchStore::load();
+            if store.mark_seen(&board, &query, urls) {
+                store.save().unwrap_or(());
+            }
+            store
+        });
+        self.search
+            .counts
+            .insert(search_key(&board, &query), Some(0));
+    }
+
+    pub fn toggle_board_ignore(&mut self) {
+        if !self.ensure_writable() {
+            return;
+        }
+
+        let board = match self
+            .search
+            .state
+            .selected()
+            .and_then(|i| self.search.items.get(i))
+        {
+            Some(board) => board.clone(),
+            None => return,
+        };
+        let status = match BoardIgnoreStore::update(|store| store.toggle(&board.id)) {
+            Err(e) => tr!("儲存忽略看板失敗: {error}", error = e),
+            Ok((store, ignored)) => {
+                self.search.ignored(store.boards);
+                if ignored {
+                    tr!("已忽略看板「{name}」", name = board.name)
+                } else {
+                    tr!("已取消忽略看板「{name}」", name = board.name)
+                }
+            }
+        };
+        self.status(status);
+    }
+
+    // follow or block the selected participant
+    pub fn toggle_user_list(&mut self, block: bool) {
+        if !self.ensure_writable() {
+            return;
+        }
+
+        let user = match self.post.current_participant() {
+            Some(p) => p.user.clone(),
+            None => return,
+        };
+        let result = UserListStore::update(|store| {
+            if block {
+                store.toggle_block(&user.id)
+            } else {
+                store.toggle_follow(&user.id)
+            }
+        });
+        let status = match result {
+            Err(e) => tr!("儲存使用者清單失敗: {error}", error = e),
+            Ok((store, added)) => {
+                self.post.followed = store.follow;
+                self.post.blocked = store.block;
+                match (block, added) {
+                    (true, true) => tr!("已封鎖 {user}，隱藏其樓層", user = user.id),
+                    (true, false) => tr!("已取消封鎖 {user}", user = user.id),
+                    (false, true) => tr!("已追蹤 {user}", user = user.id),
+                    (false, false) => tr!("已取消追蹤 {user}", user = user.id),
+                }
+            }
+        };
+        self.status(status);
+    }
+
+    pub fn toggle_mute(&mut self) {
+        if !self.ensure_writable() {
+            return;
+        }
+
+        let url = self.post.url.as_ref();
+        let status = match MuteStore::update(|store| store.toggle(url)) {
+            Err(e) => tr!("儲存靜音失敗: {error}", error = e),
+            Ok((store, muted)) => {
+                self.mute = store;
+                if muted {
+                    tr!("已將此貼文靜音，不再通知或標示未讀").to_string()
+                } else {
+                    tr!("已取消此貼文的靜音").to_string()
+                }
+            }
+        };
+        self.status(status);
+    }
+
+    pub fn toggle_bookmark(&mut self) {
+        if !self.ensure_writable() {
+            return;
+        }
+
+        let (position, current) = match (self.post.position(), self.post.current()) {
+            (Some(position), Some(current)) => (position, current),
+            _ => return,
+        };
+
+        let bookmark = Bookmark {
+            url: self.post.url.to_owned(),
+            board: self.post.data.board.to_owned(),
+            title: self.post.data.title.to_owned(),
+            author: current.user.id.to_owned(),
+            page: position.page,
+            floor: position.floor,
+        };
+
+        let status = match BookmarkStore::update(|store| store.toggle(bookmark)) {
+            Err(e) => tr!("儲存書籤失敗: {error}", error = e),
+            Ok((store, bookmarked)) => {
+                self.bookmark = store;
+                if bookmarked {
+                    tr!("已將 {floor} 樓加入書籤", floor = position.floor)
+                } else {
+                    tr!("已移除 {floor} 樓的書籤", floor = position.floor)
+                }
+            }
+        };
+        self.status(status);
+    }
+
+    pub fn open_settings(&mut self) {
+        self.settings.open(self.page);
+        self.page = Page::Settings;
+    }
+
+    // bool and choice options change in place, the others open the input
+    pub fn edit_setting(&mut self) {
+        if !self.ensure_writable() {
+            return;
+        }
+
+        let setting = match self.settings.selected() {
+            Some(setting) => setting,
+            None => return,
+        };
+
+        if setting.is_toggle() {
+            let value = setting.toggled(&self.config);
+            self.save_setting(value.as_ref());
+        } else {
+            self.settings.input = Some(Input::new(setting.value(&self.config)));
+        }
+    }
+
+    pub fn save_setting(&mut self, value: &str) {
+        if !self.ensure_writable() {
+            self.settings.input = None;
+            return;
+        }
+
+        let setting = match self.settings.selected() {
+            Some(setting) => setting,
+            None => return,
+        };
+
+        match setting.save(value) {
+            Ok(config) => {
+                self.settings.input = None;
+                self.apply_config(config);
+                self.status(tr!("已儲存 {key}", key = setting.key));
+            }
+            // kept open to fix the value
+            Err(e) => self.status(e),
+        }
+    }
+
+    // settings read when drawing take effect at once, the rest after restarting
+    pub fn apply_config(&mut self, mut config: Config) {
+        config.session.read_only = self.config.session.read_only;
+        // the fetcher and the disk cache were set up with it
+        config.cache.low_memory = self.config.cache.low_memory;
+        self.color = supports_color(config.terminal.color);
+        self.hyperlinks = supports_hyperlinks(config.terminal.hyperlinks);
+        self.graphics = supports_graphics(config.terminal.graphics);
+        self.post.filter = config.filter.clone();
+        if self.board.hide_deleted != config.filter.hide_deleted {
+            self.board.hide_deleted = config.filter.hide_deleted;
+            self.board.reorder(&self.read_position);
+        }
+        self.apply_keymap(&config);
+        self.client.set_timeouts(config.network.timeouts());
+        self.client.set_interval(config.network.request_interval());
+        self.client
+            .set_text_only(config.network.text_only(&config.cache));
+        self.config = config;
+    }
+
+    // mappings of :map are replaced too, invalid entries are reported
+    pub fn apply_keymap(&mut self, config: &Config) {
+        let (keymap, errors) = KeyMap::from_config(&config.keymap);
+        self.keymap = keymap;
+        if !errors.is_empty() {
+            self.status(format!("[keymap] {}", errors.join(", ")));
+        }
+    }
+
+    // for this session only, saved with :set
+    pub fn toggle_data_saver(&mut self) {
+        let on = !self.config.network.data_saver;
+        self.config.network.data_saver = on;
+        self.client
+            .set_text_only(self.config.network.text_only(&self.config.cache));
+        let status = if on {
+            tr!("已開啟省流模式，之後讀取的頁面不含圖片與影片")
+        } else {
+            tr!("已關閉省流模式")
+        };
+        self.status(status.to_string());
+    }
+
+    pub fn open_image_viewer(&mut self) {
+        if self.config.cache.low_memory {
+            self.status(tr!("低記憶體模式下不顯示圖片").to_string());
+        } else if !self.graphics && !self.config.terminal.mosaic {
+            self.status(tr!("終端機不支援 kitty 圖片協定，可用 :set 開啟 terminal.graphics 或 terminal.mosaic").to_string());
+        } else if !self.post.open_image_viewer() {
+            self.status(tr!("這頁沒有圖片").to_string());
+        }
+    }
+
+    // every attachment of the current floor, an unfinished one continues where it stopped
+    pub fn download_attachments(&mut self, tx: &Sender<DataRequestMsg>) {
+        let (floor, attachments) = self
+            .post
+            .current()
+            .map_or((0, vec![]), |c| (c.floor, c.attachments.clone()));
+        if attachments.is_empty() {
+            self.status(tr!("此樓層沒有附件").to_string());
+            return;
+        }
+
+        let dir = match self.config.download.dir() {
+            Some(dir) => dir,
+            None => return self.status(tr!("找不到下載目錄").to_string()),
+        };
+        self.status(tr!(
+            "下載 {count} 個附件到 {dir}",
+            count = attachments.len(),
+            dir = dir.display()
+        ));
+        for attachment in attachments {
+            tx.send(DataRequestMsg::Download(attachment, dir.to_owned(), floor))
+                .unwrap_or(());
+        }
+    }
+
+    // a file with the same bytes as one saved from the thread before is removed again
+    pub fn downloaded(&mut self, name: String, floor: u16, saved: Result<(PathBuf, u64), String>) {
+        let (path, hash) = match saved {
+            Ok(saved) => saved,
+            Err(e) => return self.status(tr!("下載 {name} 失敗: {error}", name = name, error = e)),
+        };
+
+        let key = (self.post.url.to_owned(), hash);
+        let (first, floors) = self
+            .post
+            .downloads
+            .entry(key)
+            .or_insert_with(|| (path.to_owned(), vec![]));
+        if !floors.contains(&floor) {
+            floors.push(floor);
+            floors.sort();
+        }
+        if *first == path || !first.exists() {
+            *first = path.to_owned();
+            return self.status(tr!("已下載 {path}", path = path.display()));
+        }
+
+        fs::remove_file(&path).unwrap_or(());
+        let status = tr!(
+            "{name} 與 {path} 相同，已略過 (出現在 {floors} 樓)",
+            name = name,
+            path = first.display(),
+            floors = join_floors(floors)
+        );
+        self.status(status);
+    }
+
+    // the loaded floors, later pages are summarized after they are read
+    pub fn summarize(&mut self, tx: &Sender<DataRequestMsg>) {
+        let config = &self.config.summary;
+        if !config.is_enabled() {
+            self.status(tr!("請先在設定檔的 [summary] 設定 command 或 endpoint").to_string());
+            return;
+        }
+
+        let text = thread_text(&self.post.data, config.max_chars);
+        tx.send(DataRequestMsg::Summary(config.clone(), text))
+            .unwrap_or(());
+        self.summary = Some(SummaryState {
+            title: self.post.data.title.to_owned(),
+            result: None,
+            scroll: 0,
+        });
+    }
+
+    // time until the timeline refreshes by itself, only while it's open
+    pub fn timeline_refresh_in(&self) -> Option<Duration> {
+        if self.page != Page::Timeline {
+            return None;
+        }
+        self.timeline.refresh_in(self.config.timeline.refresh_secs)
+    }
+
+    // threads of the subscribed boards, false when none is subscribed
+    pub fn request_timeline(&mut self, tx: &Sender<DataRequestMsg>, refresh: bool) -> bool {
+        let boards: Vec<(String, String)> = self
+            .subscription
+            .boards
+            .values()
+            .map(|board| (board.id.to_owned(), board.name.to_owned()))
+            .collect();
+        if boards.is_empty() {
+            return false;
+        }
+
+        self.timeline.boards = boards.len();
+        self.timeline.requested = Some(Instant::now());
+        tx.send(DataRequestMsg::Timeline(boards, refresh))
+            .unwrap_or(());
+        true
+    }
+
+    pub fn open_bookmarks(&mut self) {
+        self.bookmarks
+            .open(self.page, self.bookmark.bookmarks.clone());
+        self.page = Page::Bookmark;
+    }
+
+    pub fn remove_bookmark(&mut self) {
+        if !self.ensure_writable() {
+            return;
+        }
+
+        let selected = match self.bookmarks.selected() {
+            Some(bookmark) => bookmark.clone(),
+            None => return,
+        };
+
+        let (url, floor) = (selected.url.as_ref(), selected.floor);
+        let status = match BookmarkStore::update(|store| store.remove(url, floor)) {
+            Err(e) => tr!("儲存書籤失敗: {error}", error = e),
+            Ok((store, _)) => {
+                self.bookmark = store;
+                tr!("已移除書籤").to_string()
+            }
+        };
+        self.status(status);
+        self.bookmarks.items(self.bookmark.bookmarks.clone());
+    }
+
+    pub fn mark_thread_link(&mut self) {
+        self.link_mark = Some(self.post.url.to_owned());
+        self.status(tr!("已標記此貼文，到續集貼文按 L 連結").to_string());
+    }
+
+    // link current thread as the continuation of the marked one, return true if the links changed
+    pub fn link_thread(&mut self) -> bool {
+        if !self.ensure_writable() {
+            return false;
+        }
+
+        let prev = match self.link_mark.take() {
+            Some(prev) => prev,
+            None => {
+                self.status(tr!("請先在前一集貼文按 m 標記").to_string());
+                return false;
+            }
+        };
+
+        let url = self.post.url.as_ref();
+        let status = match ThreadLinkStore::update(|store| store.link(prev.as_ref(), url)) {
+            Ok((store, false)) => {
+                self.thread_link = store;
+                self.status(tr!("無法連結同一篇貼文").to_string());
+                return false;
+            }
+            Err(e) => tr!("儲存連結失敗: {error}", error = e),
+            Ok((store, true)) => {
+                self.thread_link = store;
+                tr!("已連結續集，重新開啟貼文即可合併閱讀").to_string()
+            }
+        };
+        self.status(status);
+        true
+    }
+
+    pub fn unlink_thread(&mut self) -> bool {
+        if !self.ensure_writable() {
+            return false;
+        }
+
+        // checked on the fresh store, it may be linked by another instance
+        let url = self.post.url.as_ref();
+        let status = match ThreadLinkStore::update(|store| store.unlink(url)) {
+            Ok((store, false)) => {
+                self.thread_link = store;
+                self.status(tr!("此貼文沒有連結續集").to_string());
+                return false;
+            }
+            Err(e) => tr!("儲存連結失敗: {error}", error = e),
+            Ok((store, true)) => {
+                self.thread_link = store;
+                tr!("已取消連結").to_string()
+            }
+        };
+        self.status(status);
+        true
+    }
+
+    pub fn snapshot(&self) -> HistoryEntry {
+        match self.page {
+            Page::Search => HistoryEntry::Search,
+            Page::Board => HistoryEntry::Board {
+                id: self.board.id.to_owned(),
+                name: self.board.name.to_owned(),
+                page: self.board.page,
+                query: self.board.query.to_owned(),
+                selected: self.board.page_index(),
+            },
+            Page::Post => HistoryEntry::Post {
+                url: self.post.url.to_owned(),
+                position: self.post.position(),
+            },
+            Page::Comment => HistoryEntry::Comment,
+            Page::Cache => HistoryEntry::Cache,
+            Page::Anime => HistoryEntry::Anime,
+            Page::News => HistoryEntry::News,
+            Page::Article => HistoryEntry::Article,
+            Page::Creation => HistoryEntry::Creation,
+            Page::CreationArticle => HistoryEntry::CreationArticle,
+            Page::Bookmark => HistoryEntry::Bookmark,
+            Page::Activity => HistoryEntry::Activity,
+            Page::Moderation => HistoryEntry::Moderation,
+            Page::Timeline => HistoryEntry::Timeline,
+            Page::Settings => HistoryEntry::Settings,
+        }
+    }
+
+    pub fn push_history(&mut self) {
+        let entry = self.snapshot();
+        self.history.push(entry);
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        self.loading
+            || self.checking_update
+            || !self.macros.queue.is_empty()
+            || self.post.comments.values().any(Option::is_none)
+            || self.post.previews.values().any(Option::is_none)
+            || self.post.images.values().any(Option::is_none)
+            || self.summary.as_ref().is_some_and(|s| s.result.is_none())
+            || self.board.previews.values().any(Option::is_none)
+            || self.search.counts.values().any(Option::is_none)
+            || !self.translating.is_empty()
     }
 }
 
@@ -88,7 +973,44 @@ impl Default for AppState {
             board: BoardPageState::default(),
             post: PostPageState::default(),
             comment: PostCommentState::default(),
+            cache: CachePageState::default(),
+            anime: AnimePageState::default(),
+            news: NewsPageState::default(),
+            creation: CreationPageState::default(),
+            bookmarks: BookmarkPageState::default(),
+            settings: SettingsPageState::default(),
+            activity: ActivityPageState::default(),
+            moderation: ModerationPageState::default(),
+            timeline: TimelinePageState::default(),
             loading: false,
+            status: None,
+            speaker: Speaker::default(),
+            summary: None,
+            config: Config::default(),
+            read_position: ReadPositionStore::default(),
+            macros: MacroState::default(),
+            keymap: KeyMap::default(),
+            count: CountState::default(),
+            history: History::default(),
+            confirm: None,
+            digest: vec![],
+            digest_state: None,
+            color: true,
+            hyperlinks: false,
+            graphics: false,
+            subscription: SubscriptionStore::default(),
+            thread_link: ThreadLinkStore::default(),
+            bookmark: BookmarkStore::default(),
+            mute: MuteStore::default(),
+            searches: SearchStore::default(),
+            translation: TranslationStore::default(),
+            translating: HashSet::new(),
+            link_mark: None,
+            show_metrics: false,
+            checking_update: false,
+            age_gate: None,
+            command: None,
+            client: Arc::default(),
         }
     }
 }
@@ -97,14 +1019,104 @@ impl Default for AppState {
 pub struct SearchPageState {
     pub state: ListState,
     pub items: Vec<SearchResult>,
+    pub all_items: Vec<SearchResult>,
+    // ids of the ignored boards, listed only with show_ignored
+    pub ignored: HashSet<String>,
+    pub show_ignored: bool,
     pub mode: InputMode,
     pub input: Input,
     pub cursor: (u16, u16),
+    // index in the history while recalling with up and down
+    pub recall: Option<usize>,
+    // saved searches panel
+    pub saved_state: Option<ListState>,
+    pub saved: Vec<SavedSearch>,
+    // new posts in the first result page by saved search key, None while fetching
+    pub counts: HashMap<String, Option<usize>>,
 }
 
 impl SearchPageState {
+    // older with up, back to the empty input below the newest
+    pub fn recall(&mut self, history: &[String], older: bool) {
+        self.recall = match (self.recall, older) {
+            (None, true) => history.len().checked_sub(1),
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) => Some(i + 1).filter(|i| *i < history.len()),
+            (None, false) => None,
+        };
+
+        let value = self.recall.and_then(|i| history.get(i)).cloned();
+        self.input = Input::new(value.unwrap_or_default());
+    }
+
+    pub fn toggle_saved(&mut self, saved: Vec<SavedSearch>) {
+        if self.saved_state.take().is_some() {
+            return;
+        }
+
+        let mut state = ListState::default();
+        if !saved.is_empty() {
+            state.select(Some(0));
+        }
+        // counted again every time it's opened
+        self.counts.clear();
+        self.saved = saved;
+        self.saved_state = Some(state);
+    }
+
+    pub fn saved_next(&mut self) {
+        let max = self.saved.len();
+        if let Some(state) = self.saved_state.as_mut() {
+            let i = state.selected().map_or(0, |i| (i + 1) % max.max(1));
+            state.select(Some(i));
+        }
+    }
+
+    pub fn saved_previous(&mut self) {
+        let max = self.saved.len();
+        if let Some(state) = self.saved_state.as_mut() {
+            let i = state
+                .selected()
+                .map_or(0, |i| if i == 0 { max.max(1) - 1 } else { i - 1 });
+            state.select(Some(i));
+        }
+    }
+
+    pub fn current_saved(&self) -> Option<&SavedSearch> {
+        let i = self.saved_state.as_ref()?.selected()?;
+        self.saved.get(i)
+    }
+
     pub fn items(&mut self, items: Vec<SearchResult>) {
-        self.items = items;
+        self.all_items = items;
+        self.filter_items();
+    }
+
+    // keep the cursor around the same place after hiding one
+    fn filter_items(&mut self) {
+        self.items = self
+            .all_items
+            .iter()
+            .filter(|item| self.show_ignored || !self.ignored.contains(&item.id))
+            .cloned()
+            .collect();
+
+        match self.state.selected() {
+            Some(i) if i < self.items.len() => (),
+            Some(_) if !self.items.is_empty() => self.state.select(Some(self.items.len() - 1)),
+            _ => self.init_select(),
+        }
+    }
+
+    pub fn ignored(&mut self, ignored: HashSet<String>) {
+        self.ignored = ignored;
+        self.filter_items();
+    }
+
+    pub fn toggle_show_ignored(&mut self) -> bool {
+        self.show_ignored = !self.show_ignored;
+        self.filter_items();
+        self.show_ignored
     }
 
     pub fn mode(&mut self, mode: InputMode) {
@@ -149,39 +1161,50 @@ impl CursorMoveable for SearchPageState {
 }
 
 #[derive(Default)]
-pub struct BoardPageState {
+pub struct CreationPageState {
+    pub owner: String,
     pub state: ListState,
-    pub items: Vec<BoardPost>,
-    pub id: String,
-    pub name: String,
-    pub last_page: u16,
-    pub page: u16,
+    pub items: Vec<CreationItem>,
+    pub article: CreationArticle,
+    pub scroll_offset: usize,
+    pub scroll_size: usize,
 }
 
-impl BoardPageState {
-    pub fn id(&mut self, id: String) {
-        self.id = id;
+impl CreationPageState {
+    pub fn items(&mut self, items: Vec<CreationItem>) {
+        self.items = items;
+        self.init_select();
     }
 
-    pub fn name(&mut self, name: String) {
-        self.name = name;
+    pub fn article(&mut self, article: CreationArticle) {
+        self.article = article;
+        self.scroll_offset = 0;
     }
 
-    pub fn items(&mut self, items: Vec<BoardPost>) {
-        self.items = items;
+    pub fn current(&self) -> Option<&CreationItem> {
+        self.items.get(self.state.selected()?)
     }
 
-    pub fn last_page(&mut self, page: u16) {
-        self.last_page = page;
+    pub fn scroll_up(&mut self) {
+        if self.scroll_offset > 0 {
+            self.scroll_offset -= 1;
+        }
     }
 
-    pub fn page(&mut self, page: u16) {
-        self.page = page;
+    pub fn scroll_down(&mut self) {
+        let len = self.article.desc.len();
+        if len >= self.scroll_offset && len - self.scroll_offset > self.scroll_size {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn scroll_size(&mut self, size: usize) {
+        self.scroll_size = size;
     }
 }
 
-impl ListStateInit<BoardPost> for BoardPageState {
-    fn lists(&self) -> &Vec<BoardPost> {
+impl ListStateInit<CreationItem> for CreationPageState {
+    fn lists(&self) -> &Vec<CreationItem> {
         &self.items
     }
 
@@ -190,7 +1213,7 @@ impl ListStateInit<BoardPost> for BoardPageState {
     }
 }
 
-impl CursorMoveable for BoardPageState {
+impl CursorMoveable for CreationPageState {
     fn index(&self) -> usize {
         self.state.selected().map_or(1, |x| x)
     }
@@ -200,42 +1223,1493 @@ impl CursorMoveable for BoardPageState {
     }
 
     fn next(&mut self) {
-        self.state.select(Some(self.next_index()))
+        if !self.items.is_empty() {
+            self.state.select(Some(self.next_index()))
+        }
     }
 
     fn previous(&mut self) {
-        self.state.select(Some(self.previous_index()))
+        if !self.items.is_empty() {
+            self.state.select(Some(self.previous_index()))
+        }
     }
 }
 
+// 處理紀錄 of a board, opened from its list
 #[derive(Default)]
-pub struct PostPageState {
-    pub data: Post,
-    pub index: u16,
-    pub page: u16,
-    pub last_page: u16,
-    pub url: String,
-    pub scroll_offset: usize,
-    pub scroll_size: usize,
+pub struct ModerationPageState {
+    pub state: ListState,
+    pub items: Vec<ModerationEntry>,
+    pub name: String,
+    pub back: Page,
 }
 
-impl PostPageState {
-    pub fn data(&mut self, data: Post) {
-        self.data = data;
+impl ModerationPageState {
+    pub fn items(&mut self, items: Vec<ModerationEntry>) {
+        self.items = items;
+        self.init_select();
     }
+}
 
-    pub fn chain_posts(&mut self, posts: Vec<PostContent>) {
-        self.data.posts.extend(posts);
+impl ListStateInit<ModerationEntry> for ModerationPageState {
+    fn lists(&self) -> &Vec<ModerationEntry> {
+        &self.items
     }
 
-    pub fn index(&mut self, index: u16) {
-        self.index = index;
+    fn state(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+}
+
+impl CursorMoveable for ModerationPageState {
+    fn index(&self) -> usize {
+        self.state.selected().map_or(1, |x| x)
+    }
+
+    fn max(&self) -> usize {
+        self.items.len()
+    }
+
+    fn next(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.next_index()))
+        }
+    }
+
+    fn previous(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.previous_index()))
+        }
+    }
+}
+
+pub struct TimelineItem {
+    pub board: String,
+    pub post: BoardPost,
+    // minutes of the last reply, None when the date didn't parse
+    pub time: Option<i64>,
+}
+
+// newest threads of every subscribed board, by the last reply
+#[derive(Default)]
+pub struct TimelinePageState {
+    pub state: ListState,
+    pub items: Vec<TimelineItem>,
+    // subscribed boards when it was asked for
+    pub boards: usize,
+    pub back: Page,
+    // last request, the next refresh is counted from it
+    pub requested: Option<Instant>,
+    // items shown in the last render
+    pub visible: std::ops::Range<usize>,
+    pub translate: bool,
+}
+
+impl TimelinePageState {
+    // the selected thread is kept across refreshes
+    pub fn items(&mut self, items: Vec<(String, BoardPost)>) {
+        let selected = self.selected().map(|item| item.post.url.to_owned());
+        let mut items: Vec<TimelineItem> = items
+            .into_iter()
+            .filter(|(_, post)| !post.deleted)
+            .map(|(board, post)| TimelineItem {
+                time: reply_time(&post.date),
+                board,
+                post,
+            })
+            .collect();
+        // undated ones go last, the order of each board is kept for the same minute
+        items.sort_by_key(|item| std::cmp::Reverse(item.time));
+        self.items = items;
+
+        match selected.and_then(|url| self.items.iter().position(|i| i.post.url == url)) {
+            Some(i) => self.state.select(Some(i)),
+            None => self.init_select(),
+        }
+    }
+
+    pub fn selected(&self) -> Option<&TimelineItem> {
+        self.items.get(self.state.selected()?)
+    }
+
+    // time until the next refresh, None when it's not refreshed by itself
+    pub fn refresh_in(&self, refresh_secs: u64) -> Option<Duration> {
+        if refresh_secs == 0 {
+            return None;
+        }
+        let requested = self.requested?;
+        Some(Duration::from_secs(refresh_secs).saturating_sub(requested.elapsed()))
+    }
+}
+
+impl ListStateInit<TimelineItem> for TimelinePageState {
+    fn lists(&self) -> &Vec<TimelineItem> {
+        &self.items
+    }
+
+    fn state(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+}
+
+impl CursorMoveable for TimelinePageState {
+    fn index(&self) -> usize {
+        self.state.selected().map_or(1, |x| x)
+    }
+
+    fn max(&self) -> usize {
+        self.items.len()
+    }
+
+    fn next(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.next_index()))
+        }
+    }
+
+    fn previous(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.previous_index()))
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct NewsPageState {
+    pub state: ListState,
+    pub items: Vec<NewsHeadline>,
+    pub article: NewsArticle,
+    pub scroll_offset: usize,
+    pub scroll_size: usize,
+    pub back: Page,
+}
+
+impl NewsPageState {
+    pub fn items(&mut self, items: Vec<NewsHeadline>) {
+        self.items = items;
+        self.init_select();
+    }
+
+    pub fn article(&mut self, article: NewsArticle) {
+        self.article = article;
+        self.scroll_offset = 0;
+    }
+
+    pub fn current(&self) -> Option<&NewsHeadline> {
+        self.items.get(self.state.selected()?)
+    }
+
+    pub fn scroll_up(&mut self) {
+        if self.scroll_offset > 0 {
+            self.scroll_offset -= 1;
+        }
+    }
+
+    pub fn scroll_down(&mut self) {
+        let len = self.article.desc.len();
+        if len >= self.scroll_offset && len - self.scroll_offset > self.scroll_size {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn scroll_size(&mut self, size: usize) {
+        self.scroll_size = size;
+    }
+}
+
+impl ListStateInit<NewsHeadline> for NewsPageState {
+    fn lists(&self) -> &Vec<NewsHeadline> {
+        &self.items
+    }
+
+    fn state(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+}
+
+impl CursorMoveable for NewsPageState {
+    fn index(&self) -> usize {
+        self.state.selected().map_or(1, |x| x)
+    }
+
+    fn max(&self) -> usize {
+        self.items.len()
+    }
+
+    fn next(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.next_index()))
+        }
+    }
+
+    fn previous(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.previous_index()))
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum AnimeTab {
+    #[default]
+    NewEpisode,
+    Schedule,
+}
+
+#[derive(Default)]
+pub struct AnimePageState {
+    pub state: ListState,
+    pub home: AnimeHome,
+    pub tab: AnimeTab,
+    pub items: Vec<AnimeEpisode>,
+    pub back: Page,
+}
+
+impl AnimePageState {
+    pub fn home(&mut self, home: AnimeHome) {
+        self.home = home;
+        self.tab(self.tab);
+    }
+
+    pub fn tab(&mut self, tab: AnimeTab) {
+        self.tab = tab;
+        self.items = match tab {
+            AnimeTab::NewEpisode => self.home.episodes.clone(),
+            AnimeTab::Schedule => self
+                .home
+                .schedule
+                .iter()
+                .flat_map(|day| {
+                    day.items.iter().map(|item| AnimeEpisode {
+                        date: format!("{} {}", day.day, item.date),
+                        ..item.clone()
+                    })
+                })
+                .collect(),
+        };
+        self.init_select();
+    }
+
+    pub fn toggle_tab(&mut self) {
+        match self.tab {
+            AnimeTab::NewEpisode => self.tab(AnimeTab::Schedule),
+            AnimeTab::Schedule => self.tab(AnimeTab::NewEpisode),
+        }
+    }
+
+    pub fn current(&self) -> Option<&AnimeEpisode> {
+        self.items.get(self.state.selected()?)
+    }
+}
+
+impl ListStateInit<AnimeEpisode> for AnimePageState {
+    fn lists(&self) -> &Vec<AnimeEpisode> {
+        &self.items
+    }
+
+    fn state(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+}
+
+impl CursorMoveable for AnimePageState {
+    fn index(&self) -> usize {
+        self.state.selected().map_or(1, |x| x)
+    }
+
+    fn max(&self) -> usize {
+        self.items.len()
+    }
+
+    fn next(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.next_index()))
+        }
+    }
+
+    fn previous(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.previous_index()))
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ActivityPageState {
+    pub owner: String,
+    pub items: Vec<CreationItem>,
+    // count of items by day
+    pub counts: HashMap<i64, usize>,
+    pub today: i64,
+    pub selected: i64,
+    // items of the selected day
+    pub state: ListState,
+    pub back: Page,
+}
+
+impl ActivityPageState {
+    pub fn items(&mut self, items: Vec<CreationItem>) {
+        self.counts.clear();
+        for day in items
+            .iter()
+            .filter_map(|item| parse_day(item.date.as_ref()))
+        {
+            *self.counts.entry(day).or_insert(0) += 1;
+        }
+
+        self.items = items;
+        self.today = today();
+        // the latest day with posts
+        self.selected = self.counts.keys().max().copied().unwrap_or(self.today);
+        self.select_first();
+    }
+
+    pub fn day_items(&self) -> Vec<&CreationItem> {
+        self.items
+            .iter()
+            .filter(|item| parse_day(item.date.as_ref()) == Some(self.selected))
+            .collect()
+    }
+
+    // days in the heatmap are never after today
+    pub fn move_day(&mut self, days: i64) {
+        self.selected = (self.selected + days).min(self.today);
+        self.select_first();
+    }
+
+    fn select_first(&mut self) {
+        let selected = if self.day_items().is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.state.select(selected);
+    }
+
+    pub fn next(&mut self) {
+        let max = self.day_items().len();
+        if max > 0 {
+            let i = self.state.selected().map_or(0, |i| (i + 1) % max);
+            self.state.select(Some(i));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let max = self.day_items().len();
+        if max > 0 {
+            let i = self
+                .state
+                .selected()
+                .map_or(0, |i| if i == 0 { max - 1 } else { i - 1 });
+            self.state.select(Some(i));
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BookmarkPageState {
+    pub state: ListState,
+    pub items: Vec<Bookmark>,
+    pub back: Page,
+}
+
+impl BookmarkPageState {
+    pub fn open(&mut self, back: Page, items: Vec<Bookmark>) {
+        self.back = back;
+        self.items(items);
+    }
+
+    // keep the cursor around the same place after removing one
+    pub fn items(&mut self, items: Vec<Bookmark>) {
+        self.items = items;
+        match self.state.selected() {
+            Some(i) if i < self.items.len() => (),
+            Some(_) if !self.items.is_empty() => self.state.select(Some(self.items.len() - 1)),
+            _ => self.init_select(),
+        }
+    }
+
+    pub fn selected(&self) -> Option<&Bookmark> {
+        self.items.get(self.state.selected()?)
+    }
+}
+
+impl ListStateInit<Bookmark> for BookmarkPageState {
+    fn lists(&self) -> &Vec<Bookmark> {
+        &self.items
+    }
+
+    fn state(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+}
+
+impl CursorMoveable for BookmarkPageState {
+    fn index(&self) -> usize {
+        self.state.selected().map_or(1, |x| x)
+    }
+
+    fn max(&self) -> usize {
+        self.items.len()
+    }
+
+    fn next(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.next_index()))
+        }
+    }
+
+    fn previous(&mut self) {
+        if !self.items.is_empty() {
+            self.state.select(Some(self.previous_index()))
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CachePageState {
+    pub state: ListState,
+    pub stats: CacheStats,
+    pub back: Page,
+}
+
+impl CachePageState {
+    pub fn open(&mut self, back: Page, cache: Option<&Cache>) {
+        self.back = back;
+        self.refresh(cache);
+    }
+
+    pub fn refresh(&mut self, cache: Option<&Cache>) {
+        self.stats = cache.map(Cache::stats).unwrap_or_default();
+        match self.state.selected() {
+            Some(i) if i < self.stats.boards.len() => (),
+            _ => self.init_select(),
+        }
+    }
+
+    pub fn selected_board(&self) -> Option<String> {
+        let i = self.state.selected()?;
+        self.stats.boards.get(i).map(|b| b.board.to_owned())
+    }
+}
+
+impl ListStateInit<BoardCacheStats> for CachePageState {
+    fn lists(&self) -> &Vec<BoardCacheStats> {
+        &self.stats.boards
+    }
+
+    fn state(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+}
+
+impl CursorMoveable for CachePageState {
+    fn index(&self) -> usize {
+        self.state.selected().map_or(1, |x| x)
+    }
+
+    fn max(&self) -> usize {
+        self.stats.boards.len()
+    }
+
+    fn next(&mut self) {
+        if !self.stats.boards.is_empty() {
+            self.state.select(Some(self.next_index()))
+        }
+    }
+
+    fn previous(&mut self) {
+        if !self.stats.boards.is_empty() {
+            self.state.select(Some(self.previous_index()))
+        }
+    }
+}
+
+// case and full width insensitive, so ＡＢＣ matches abc
+fn fold(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0).unwrap_or(c),
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// pages of a board list kept in memory while scrolling
+static MAX_LOADED_PAGES: usize = 5;
+
+#[derive(Default)]
+pub struct BoardPageState {
+    pub state: ListState,
+    // items of the page matching the prefix filter
+    pub items: Vec<BoardPost>,
+    pub all_items: Vec<BoardPost>,
+    // page number of each item, the list holds several pages with infinite scroll
+    pub item_pages: Vec<u16>,
+    all_pages: Vec<u16>,
+    // page being loaded by scrolling
+    pub more: Option<u16>,
+    pub prefix: Option<String>,
+    // narrows the loaded items by title and author while typing
+    pub filter: Option<String>,
+    pub filter_input: Option<Input>,
+    // threads with replies after the read position float to the top
+    pub unread_first: bool,
+    // urls of the threads with unread replies
+    pub unread: HashSet<String>,
+    // tombstones of deleted threads are left out instead of struck through
+    pub hide_deleted: bool,
+    pub id: String,
+    pub name: String,
+    pub last_page: u16,
+    pub page: u16,
+    pub restore: Option<usize>,
+    // thread to select when the list is loaded, from the post page
+    pub restore_thread: Option<String>,
+    pub query: Option<String>,
+    pub keywords: Vec<BoardKeyword>,
+    pub keywords_of: String,
+    pub keyword: Option<ListState>,
+    // first floor snippets by thread url, None while fetching
+    pub previews: HashMap<String, Option<String>>,
+    // items shown in the last render
+    pub visible: std::ops::Range<usize>,
+    // titles of the visible items
+    pub links: Vec<Hyperlink>,
+    // titles shown translated, see [translate]
+    pub translate: bool,
+}
+
+impl BoardPageState {
+    // entering a board leaves the search result
+    pub fn id(&mut self, id: String) {
+        self.id = id;
+        self.query = None;
+        self.keyword = None;
+        self.prefix = None;
+        self.filter = None;
+    }
+
+    pub fn keywords(&mut self, keywords: Vec<BoardKeyword>) {
+        self.keywords = keywords;
+        self.keywords_of = self.id.to_owned();
+        if let Some(state) = self.keyword.as_mut() {
+            state.select(if self.keywords.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        }
+    }
+
+    pub fn toggle_keyword(&mut self) {
+        if self.keyword.take().is_some() {
+            return;
+        }
+
+        let mut state = ListState::default();
+        if !self.keywords.is_empty() && self.keywords_of == self.id {
+            state.select(Some(0));
+        }
+        self.keyword = Some(state);
+    }
+
+    pub fn keyword_next(&mut self) {
+        let max = self.keywords.len();
+        if let Some(state) = self.keyword.as_mut() {
+            let i = state.selected().map_or(0, |i| (i + 1) % max.max(1));
+            state.select(Some(i));
+        }
+    }
+
+    pub fn keyword_previous(&mut self) {
+        let max = self.keywords.len();
+        if let Some(state) = self.keyword.as_mut() {
+            let i = state
+                .selected()
+                .map_or(0, |i| if i == 0 { max.max(1) - 1 } else { i - 1 });
+            state.select(Some(i));
+        }
+    }
+
+    pub fn current_keyword(&self) -> Option<String> {
+        let i = self.keyword.as_ref()?.selected()?;
+        self.keywords.get(i).map(|k| k.word.to_owned())
+    }
+
+    pub fn name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn items(&mut self, page: u16, items: Vec<BoardPost>) {
+        self.all_pages = vec![page; items.len()];
+        self.all_items = items;
+        self.filter_items();
+    }
+
+    // a page loaded by scrolling, only the nearest pages are kept
+    pub fn extend_items(&mut self, page: u16, items: Vec<BoardPost>) {
+        let selected = self
+            .state
+            .selected()
+            .and_then(|i| self.items.get(i))
+            .map(|item| item.url.to_owned());
+        // threads bumped by new replies can show up on two pages
+        let items: Vec<BoardPost> = items
+            .into_iter()
+            .filter(|item| !self.all_items.iter().any(|i| i.url == item.url))
+            .collect();
+
+        // the list was reloaded to another page while loading
+        let (first, last) = match self.loaded_range() {
+            Some(range) => range,
+            None => return,
+        };
+        let before = page + 1 == first;
+        if !before && page != last + 1 {
+            return;
+        }
+        if before {
+            self.all_pages.splice(0..0, vec![page; items.len()]);
+            self.all_items.splice(0..0, items);
+        } else {
+            self.all_pages.extend(vec![page; items.len()]);
+            self.all_items.extend(items);
+        }
+
+        while self.loaded_pages() > MAX_LOADED_PAGES {
+            let dropped = if before {
+                self.all_pages.last()
+            } else {
+                self.all_pages.first()
+            };
+            let dropped = match dropped {
+                Some(page) => *page,
+                None => break,
+            };
+            let (pages, items): (Vec<u16>, Vec<BoardPost>) = self
+                .all_pages
+                .drain(..)
+                .zip(self.all_items.drain(..))
+                .filter(|(page, item)| {
+                    let keep = *page != dropped;
+                    if !keep {
+                        self.previews.remove(&item.url);
+                    }
+                    keep
+                })
+                .unzip();
+            self.all_pages = pages;
+            self.all_items = items;
+        }
+
+        self.filter_items();
+        let index = selected.and_then(|url| self.items.iter().position(|i| i.url == url));
+        if let Some(i) = index {
+            self.state.select(Some(i));
+        }
+    }
+
+    fn loaded_pages(&self) -> usize {
+        match (self.all_pages.first(), self.all_pages.last()) {
+            (Some(first), Some(last)) => (last - first) as usize + 1,
+            _ => 0,
+        }
+    }
+
+    // first and last page in the list
+    pub fn loaded_range(&self) -> Option<(u16, u16)> {
+        Some((*self.all_pages.first()?, *self.all_pages.last()?))
+    }
+
+    // the page shown in the title is the one of the selected item
+    pub fn follow_page(&mut self) {
+        if let Some(page) = self.state.selected().and_then(|i| self.item_pages.get(i)) {
+            self.page = *page;
+        }
+    }
+
+    // index of the selected item in its own page, restored after reloading that page only
+    pub fn page_index(&self) -> Option<usize> {
+        let i = self.state.selected()?;
+        let page = self.item_pages.get(i)?;
+        let start = self.item_pages.iter().position(|p| p == page)?;
+        Some(i - start)
+    }
+
+    pub fn select_in_page(&mut self, index: Option<usize>) {
+        let start = self.item_pages.iter().position(|p| *p == self.page);
+        self.state.select(index.map(|i| start.unwrap_or(0) + i));
+    }
+
+    pub fn open_filter(&mut self) {
+        let source = self.filter.to_owned().unwrap_or_default();
+        self.filter_input = Some(Input::new(source));
+    }
+
+    // applied on every key, the selected item is kept when it still matches
+    pub fn update_filter(&mut self) {
+        let selected = self
+            .state
+            .selected()
+            .and_then(|i| self.items.get(i))
+            .map(|item| item.url.to_owned());
+        let source = self.filter_input.as_ref().map_or("", |i| i.value()).trim();
+        self.filter = if source.is_empty() {
+            None
+        } else {
+            Some(source.to_string())
+        };
+
+        self.filter_items();
+        match selected.and_then(|url| self.items.iter().position(|i| i.url == url)) {
+            Some(i) => self.state.select(Some(i)),
+            None => self.init_select(),
+        }
+        self.follow_page();
+    }
+
+    // enter keeps the filter and esc clears it
+    pub fn close_filter(&mut self, keep: bool) {
+        if !keep {
+            self.filter_input = Some(Input::default());
+            self.update_filter();
+        }
+        self.filter_input = None;
+    }
+
+    // read positions changed or the order toggled, the selected item is kept
+    pub fn reorder(&mut self, read: &ReadPositionStore) {
+        let selected = self
+            .state
+            .selected()
+            .and_then(|i| self.items.get(i))
+            .map(|item| item.url.to_owned());
+        self.unread = self
+            .all_items
+            .iter()
+            .filter(|item| {
+                let last = item.floor.max(item.reply + 1);
+                read.get(item.url.as_ref()).is_some_and(|p| last > p.floor)
+            })
+            .map(|item| item.url.to_owned())
+            .collect();
+
+        self.filter_items();
+        if let Some(i) = selected.and_then(|url| self.items.iter().position(|i| i.url == url)) {
+            self.state.select(Some(i));
+        }
+    }
+
+    fn filter_items(&mut self) {
+        let words: Vec<String> = self
+            .filter
+            .as_ref()
+            .map_or(vec![], |f| f.split_whitespace().map(fold).collect());
+        let mut items: Vec<(u16, BoardPost)> = self
+            .all_pages
+            .iter()
+            .zip(self.all_items.iter())
+            .filter(|(_, item)| !(self.hide_deleted && item.deleted))
+            .filter(|(_, item)| self.prefix.as_ref().is_none_or(|p| *p == item.prefix))
+            .filter(|(_, item)| {
+                let title = fold(&item.title);
+                let author = fold(&item.author);
+                words
+                    .iter()
+                    .all(|w| title.contains(w.as_str()) || author.contains(w.as_str()))
+            })
+            .map(|(page, item)| (*page, item.clone()))
+            .collect();
+        // stable, so the recency is kept in each group
+        if self.unread_first {
+            items.sort_by_key(|(_, item)| !self.unread.contains(&item.url));
+        }
+        let (pages, items) = items.into_iter().unzip();
+        self.item_pages = pages;
+        self.items = items;
+    }
+
+    // title prefixes of the page in order of appearance
+    pub fn prefixes(&self) -> Vec<&str> {
+        let mut prefixes: Vec<&str> = vec![];
+        for item in self.all_items.iter().filter(|item| !item.prefix.is_empty()) {
+            if !prefixes.contains(&item.prefix.as_str()) {
+                prefixes.push(item.prefix.as_str());
+            }
+        }
+        prefixes
+    }
+
+    // no filter -> each prefix of the page -> no filter
+    pub fn cycle_prefix(&mut self) {
+        let prefixes = self.prefixes();
+        let next = match self.prefix.as_ref() {
+            None => prefixes.first(),
+            Some(current) => prefixes
+                .iter()
+                .position(|p| p == current)
+                .and_then(|i| prefixes.get(i + 1)),
+        }
+        .map(|p| p.to_string());
+
+        self.prefix = next;
+        self.filter_items();
+        self.init_select();
+    }
+
+    pub fn last_page(&mut self, page: u16) {
+        self.last_page = page;
+    }
+
+    pub fn page(&mut self, page: u16) {
+        self.page = page;
+    }
+}
+
+impl ListStateInit<BoardPost> for BoardPageState {
+    fn lists(&self) -> &Vec<BoardPost> {
+        &self.items
+    }
+
+    fn state(&mut self) -> &mut ListState {
+        &mut self.state
+    }
+}
+
+impl CursorMoveable for BoardPageState {
+    fn index(&self) -> usize {
+        self.state.selected().map_or(1, |x| x)
+    }
+
+    fn max(&self) -> usize {
+        self.items.len()
+    }
+
+    fn next(&mut self) {
+        self.state.select(Some(self.next_index()));
+        self.follow_page();
+    }
+
+    fn previous(&mut self) {
+        self.state.select(Some(self.previous_index()));
+        self.follow_page();
+    }
+}
+
+// popup of the thread summary, the result is None while summarizing
+pub struct SummaryState {
+    pub title: String,
+    pub result: Option<Result<String, String>>,
+    pub scroll: u16,
+}
+
+// zoom is a power of two, the center is in permille of the image size
+#[derive(Clone, PartialEq)]
+pub struct ImageView {
+    pub url: String,
+    pub zoom: u32,
+    pub center: (u32, u32),
+    pub area: Rect,
+}
+
+// "3, 17, 42" of a repost
+pub fn join_floors(floors: &[u16]) -> String {
+    floors
+        .iter()
+        .map(u16::to_string)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// an image of the loaded floors, with every floor it was posted in
+pub struct GalleryImage {
+    pub url: String,
+    pub floors: Vec<u16>,
+}
+
+pub struct ImageViewer {
+    pub images: Vec<GalleryImage>,
+    pub index: usize,
+    pub zoom: u32,
+    pub center: (u32, u32),
+    // inside the border, set in the last render
+    pub area: Rect,
+    // of the fetched bytes by link
+    hashes: HashMap<String, u64>,
+    // direction of the last move, a skipped repost moves on the same way
+    backward: bool,
+}
+
+static MAX_ZOOM: u32 = 16;
+
+impl ImageViewer {
+    pub fn url(&self) -> &str {
+        self.images[self.index].url.as_ref()
     }
 
-    pub fn page(&mut self, page: u16) {
+    pub fn floors(&self) -> &[u16] {
+        &self.images[self.index].floors
+    }
+
+    // the same bytes under a later link are a repost, dropped from the list
+    // with their floors added to the first one
+    pub fn hashed(&mut self, url: &str, hash: u64) {
+        self.hashes.insert(url.to_string(), hash);
+        let same: Vec<usize> = (0..self.images.len())
+            .filter(|i| self.hashes.get(&self.images[*i].url) == Some(&hash))
+            .collect();
+        let (first, reposts) = match same.split_first() {
+            Some((first, reposts)) if !reposts.is_empty() => (*first, reposts),
+            _ => return,
+        };
+
+        for i in reposts.iter().rev() {
+            let repost = self.images.remove(*i);
+            self.images[first].floors.extend(repost.floors);
+            if *i < self.index || (*i == self.index && self.backward) {
+                self.index -= 1;
+            }
+        }
+        self.images[first].floors.sort();
+        self.images[first].floors.dedup();
+        self.index = self.index.min(self.images.len() - 1);
+    }
+
+    pub fn view(&self) -> ImageView {
+        ImageView {
+            url: self.url().to_string(),
+            zoom: self.zoom,
+            center: self.center,
+            area: self.area,
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.index = (self.index + 1) % self.images.len();
+        self.backward = false;
+        self.reset();
+    }
+
+    pub fn previous(&mut self) {
+        self.index = (self.index + self.images.len() - 1) % self.images.len();
+        self.backward = true;
+        self.reset();
+    }
+
+    pub fn reset(&mut self) {
+        self.zoom = 1;
+        self.center = (500, 500);
+    }
+
+    pub fn zoom(&mut self, zoom_in: bool) {
+        self.zoom = if zoom_in {
+            (self.zoom * 2).min(MAX_ZOOM)
+        } else {
+            (self.zoom / 2).max(1)
+        };
+        self.center = (self.clamp(self.center.0), self.clamp(self.center.1));
+    }
+
+    // a quarter of the visible part each step
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        let step = (250 / self.zoom) as i32;
+        let (x, y) = self.center;
+        self.center = (
+            self.clamp((x as i32 + dx * step).max(0) as u32),
+            self.clamp((y as i32 + dy * step).max(0) as u32),
+        );
+    }
+
+    // the visible part stays inside the image
+    fn clamp(&self, v: u32) -> u32 {
+        let half = 500 / self.zoom;
+        v.clamp(half, 1000 - half)
+    }
+}
+
+#[derive(Default)]
+pub struct PostPageState {
+    // author of the current floor
+    pub links: Vec<Hyperlink>,
+    pub data: Post,
+    pub index: u16,
+    pub page: u16,
+    pub last_page: u16,
+    pub url: String,
+    pub scroll_offset: usize,
+    pub scroll_size: usize,
+    pub dual_pane: bool,
+    pub comments: HashMap<String, Option<Vec<PostComment>>>,
+    // preview cards by link, None while fetching
+    pub previews: HashMap<String, Option<LinkPreview>>,
+    pub outline: Option<ListState>,
+    pub start_page: u16,
+    pub pages: Vec<u16>,
+    pub restore: Option<ReadPosition>,
+    // content before the refresh of edited floors, by floor id
+    pub edits: HashMap<String, PostDescription>,
+    // computed when opened, not in every render
+    pub diff: Option<Vec<Vec<(DiffKind, String)>>>,
+    pub filter: FloorFilterConfig,
+    // ids of the low content floors, skipped unless show_hidden
+    pub hidden: HashSet<String>,
+    pub show_hidden: bool,
+    pub line_numbers: bool,
+    // (anchor, cursor) blocks of the visual selection
+    pub visual: Option<(usize, usize)>,
+    // floors not matching the search are skipped like the hidden ones
+    pub find: Option<FindQuery>,
+    // query being typed, with the regex and case sensitive toggles
+    pub find_input: Option<Input>,
+    pub find_regex: bool,
+    pub find_case: bool,
+    // participants popup, listed when opened
+    pub participants_state: Option<ListState>,
+    pub participants: Vec<Participant>,
+    // saved attachments by thread and hash of the file, with the floors having it
+    pub downloads: HashMap<(String, u64), (PathBuf, Vec<u16>)>,
+    // reply templates popup, filled for the floor when opened
+    pub reply_state: Option<ListState>,
+    pub replies: Vec<String>,
+    // reply tree popup, built when opened
+    pub conversation_state: Option<ListState>,
+    pub conversation: Vec<ReplyNode>,
+    pub image: Option<ImageViewer>,
+    // decoded images by link, None while fetching, dropped when the viewer closes
+    pub images: HashMap<String, Option<Result<Arc<DynamicImage>, String>>>,
+    // avatars by user id, kept across threads
+    pub avatars: HashMap<String, Option<Result<Arc<DynamicImage>, String>>>,
+    // user id and the area the avatar is drawn over, set in the last render
+    pub avatar_area: Option<(String, Rect)>,
+    // user ids from the user lists
+    pub followed: HashSet<String>,
+    pub blocked: HashSet<String>,
+}
+
+impl PostPageState {
+    // the thread can be opened from a timeline, bookmark or link without visiting its board
+    pub fn board_id(&self) -> Option<String> {
+        PostPageUrlParameter::try_from(self.url.to_owned())
+            .ok()
+            .map(|param| param.board_id)
+    }
+
+    pub fn data(&mut self, page: u16, data: Post) {
+        self.mark_edits(&data.posts);
+        self.hidden.clear();
+        self.mark_hidden(&data.posts);
+        self.diff = None;
+        self.find = None;
+        self.pages = vec![page; data.posts.len()];
+        self.data = data;
+        self.start_page = page;
+        self.page = page;
+        self.comments.clear();
+    }
+
+    // selection starts from the top block on screen
+    pub fn toggle_visual(&mut self) {
+        self.visual = match self.visual {
+            Some(_) => None,
+            None if self.current().is_some_and(|c| !c.desc.is_empty()) => {
+                Some((self.scroll_offset, self.scroll_offset))
+            }
+            None => None,
+        };
+    }
+
+    pub fn move_visual(&mut self, down: bool) {
+        let len = self.current().map_or(0, |c| c.desc.len());
+        if let Some((_, cursor)) = self.visual.as_mut() {
+            *cursor = if down {
+                (*cursor + 1).min(len.saturating_sub(1))
+            } else {
+                cursor.saturating_sub(1)
+            };
+        }
+        self.follow_visual();
+    }
+
+    // keep the cursor on screen, without the borders
+    fn follow_visual(&mut self) {
+        if let Some((_, cursor)) = self.visual {
+            let rows = self.scroll_size.saturating_sub(2).max(1);
+            if cursor < self.scroll_offset {
+                self.scroll_offset = cursor;
+            } else if cursor >= self.scroll_offset + rows {
+                self.scroll_offset = cursor + 1 - rows;
+            }
+        }
+    }
+
+    pub fn visual_range(&self) -> Option<std::ops::Range<usize>> {
+        let (anchor, cursor) = self.visual?;
+        Some(anchor.min(cursor)..anchor.max(cursor) + 1)
+    }
+
+    // selected blocks joined by lines, leaves the visual mode
+    pub fn yank_visual(&mut self) -> Option<String> {
+        let range = self.visual_range()?;
+        self.visual = None;
+        let desc = &self.current()?.desc;
+        let lines = desc.get(range.start..range.end.min(desc.len()))?;
+        Some(lines.join("\n"))
+    }
+
+    pub fn chain_posts(&mut self, page: u16, posts: Vec<Arc<PostContent>>) {
+        self.mark_edits(&posts);
+        self.mark_hidden(&posts);
+        self.pages.extend(vec![page; posts.len()]);
+        self.data.posts.extend(posts);
         self.page = page;
     }
 
+    pub fn prepend_posts(&mut self, page: u16, posts: Vec<Arc<PostContent>>) {
+        self.mark_edits(&posts);
+        self.mark_hidden(&posts);
+        let len = posts.len();
+        self.pages.splice(0..0, vec![page; len]);
+        self.data.posts.splice(0..0, posts);
+        self.start_page = page;
+        self.index += len as u16;
+    }
+
+    // compare the refreshed floors with the loaded ones
+    fn mark_edits(&mut self, posts: &[Arc<PostContent>]) {
+        for post in posts {
+            let old = self.data.posts.iter().find(|p| p.id == post.id);
+            if let Some(old) = old.filter(|old| old.desc != post.desc) {
+                // keep the first seen content when edited again
+                if !self.edits.contains_key(&post.id) {
+                    self.edits.insert(post.id.to_owned(), old.desc.clone());
+                }
+            }
+        }
+    }
+
+    fn mark_hidden(&mut self, posts: &[Arc<PostContent>]) {
+        let hidden = posts
+            .iter()
+            .filter(|post| {
+                self.filter.is_low_content(post.floor, &post.desc)
+                    || (self.filter.hide_notices && post.kind == FloorKind::Notice)
+            })
+            .map(|post| post.id.to_owned());
+        self.hidden.extend(hidden);
+    }
+
+    // count of loaded floors skipped by the filter and the block list
+    pub fn hidden_count(&self) -> usize {
+        if self.show_hidden {
+            0
+        } else {
+            self.data.posts.iter().filter(|p| self.is_hidden(p)).count()
+        }
+    }
+
+    pub fn toggle_participants(&mut self) {
+        if self.participants_state.take().is_some() {
+            return;
+        }
+
+        self.participants = self.data.participants();
+        let mut state = ListState::default();
+        if !self.participants.is_empty() {
+            state.select(Some(0));
+        }
+        self.participants_state = Some(state);
+    }
+
+    // the templates of [reply] filled with the current floor
+    pub fn toggle_reply(&mut self, templates: &[String]) -> bool {
+        if self.reply_state.take().is_some() {
+            return true;
+        }
+
+        let current = match self.current() {
+            Some(current) if !templates.is_empty() => current,
+            _ => return false,
+        };
+        let fields = [
+            ("author", current.user.name.to_owned()),
+            ("id", current.user.id.to_owned()),
+            ("floor", current.floor.to_string()),
+            ("title", self.data.title.to_owned()),
+        ];
+        self.replies = templates
+            .iter()
+            .map(|template| fill_template(template, &fields))
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(0));
+        self.reply_state = Some(state);
+        true
+    }
+
+    pub fn reply_next(&mut self) {
+        let max = self.replies.len();
+        if let Some(state) = self.reply_state.as_mut() {
+            let i = state.selected().map_or(0, |i| (i + 1) % max.max(1));
+            state.select(Some(i));
+        }
+    }
+
+    pub fn reply_previous(&mut self) {
+        let max = self.replies.len();
+        if let Some(state) = self.reply_state.as_mut() {
+            let i = state
+                .selected()
+                .map_or(0, |i| if i == 0 { max.max(1) - 1 } else { i - 1 });
+            state.select(Some(i));
+        }
+    }
+
+    // the selected reply, closing the popup
+    pub fn take_reply(&mut self) -> Option<String> {
+        let i = self.reply_state.take()?.selected()?;
+        self.replies.get(i).cloned()
+    }
+
+    // reply form of the thread on the site
+    pub fn reply_url(&self) -> Option<String> {
+        let param = PostPageUrlParameter::try_from(self.url.to_owned()).ok()?;
+        Some(format!(
+            "{}post1.php?bsn={}&snA={}&type=2",
+            DN, param.board_id, param.id
+        ))
+    }
+
+    pub fn participants_next(&mut self) {
+        let max = self.participants.len();
+        if let Some(state) = self.participants_state.as_mut() {
+            let i = state.selected().map_or(0, |i| (i + 1) % max.max(1));
+            state.select(Some(i));
+        }
+    }
+
+    pub fn participants_previous(&mut self) {
+        let max = self.participants.len();
+        if let Some(state) = self.participants_state.as_mut() {
+            let i = state
+                .selected()
+                .map_or(0, |i| if i == 0 { max.max(1) - 1 } else { i - 1 });
+            state.select(Some(i));
+        }
+    }
+
+    pub fn current_participant(&self) -> Option<&Participant> {
+        let i = self.participants_state.as_ref()?.selected()?;
+        self.participants.get(i)
+    }
+
+    // first or last floor of the selected participant, closing the popup
+    pub fn participant_jump(&mut self, last: bool) {
+        let floor = match self.current_participant() {
+            Some(p) if last => p.last_floor(),
+            Some(p) => p.first_floor(),
+            None => return,
+        };
+
+        if self.jump_floor(floor) {
+            self.participants_state = None;
+        }
+    }
+
+    fn jump_floor(&mut self, floor: u16) -> bool {
+        match self.data.posts.iter().position(|p| p.floor == floor) {
+            Some(i) => {
+                self.index = i as u16;
+                self.scroll_offset = 0;
+                self.visual = None;
+                self.diff = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn toggle_conversation(&mut self) {
+        if self.conversation_state.take().is_some() {
+            return;
+        }
+
+        self.conversation = self.data.conversation();
+        // starts at the current floor when it's in a conversation
+        let floor = self.current().map(|c| c.floor);
+        let i = self
+            .conversation
+            .iter()
+            .position(|n| Some(n.floor) == floor);
+        let mut state = ListState::default();
+        if !self.conversation.is_empty() {
+            state.select(Some(i.unwrap_or(0)));
+        }
+        self.conversation_state = Some(state);
+    }
+
+    pub fn conversation_next(&mut self) {
+        let max = self.conversation.len();
+        if let Some(state) = self.conversation_state.as_mut() {
+            let i = state.selected().map_or(0, |i| (i + 1) % max.max(1));
+            state.select(Some(i));
+        }
+    }
+
+    pub fn conversation_previous(&mut self) {
+        let max = self.conversation.len();
+        if let Some(state) = self.conversation_state.as_mut() {
+            let i = state
+                .selected()
+                .map_or(0, |i| if i == 0 { max.max(1) - 1 } else { i - 1 });
+            state.select(Some(i));
+        }
+    }
+
+    // the parent or the first child of the selected floor, like a threaded mail reader
+    pub fn conversation_move(&mut self, parent: bool) {
+        let (state, nodes) = match self.conversation_state.as_mut() {
+            Some(state) => (state, &self.conversation),
+            None => return,
+        };
+        let i = match state.selected() {
+            Some(i) => i,
+            None => return,
+        };
+        let depth = nodes[i].depth;
+        let target = if parent {
+            nodes[..i].iter().rposition(|n| n.depth + 1 == depth)
+        } else {
+            nodes
+                .get(i + 1)
+                .filter(|n| n.depth == depth + 1)
+                .map(|_| i + 1)
+        };
+        if let Some(target) = target {
+            state.select(Some(target));
+        }
+    }
+
+    pub fn conversation_jump(&mut self) {
+        let i = self.conversation_state.as_ref().and_then(|s| s.selected());
+        let floor = match i.and_then(|i| self.conversation.get(i)) {
+            Some(node) => node.floor,
+            None => return,
+        };
+
+        if self.jump_floor(floor) {
+            self.conversation_state = None;
+        }
+    }
+
+    pub fn toggle_hidden(&mut self) -> bool {
+        self.show_hidden = !self.show_hidden;
+        self.show_hidden
+    }
+
+    fn is_hidden(&self, post: &PostContent) -> bool {
+        self.hidden.contains(&post.id) || self.blocked.contains(&post.user.id)
+    }
+
+    fn is_visible(&self, i: usize) -> bool {
+        self.data.posts.get(i).is_some_and(|p| {
+            (self.show_hidden || !self.is_hidden(p))
+                && self.find.as_ref().is_none_or(|f| f.matches(p))
+        })
+    }
+
+    pub fn open_find(&mut self) {
+        let source = self.find.as_ref().map(|f| f.source.to_owned());
+        self.find_input = Some(Input::new(source.unwrap_or_default()));
+    }
+
+    // count of the loaded floors matched, None when cleared by an empty query
+    pub fn apply_find(&mut self) -> Result<Option<usize>, String> {
+        let source = self
+            .find_input
+            .take()
+            .unwrap_or_default()
+            .value()
+            .trim()
+            .to_string();
+        if source.is_empty() {
+            self.find = None;
+            return Ok(None);
+        }
+
+        let find = FindQuery::parse(&source, self.find_regex, self.find_case)?;
+        let count = self.data.posts.iter().filter(|p| find.matches(p)).count();
+        self.find = Some(find);
+
+        if !self.is_visible(self.index as usize) && self.next().is_none() {
+            self.previous();
+        }
+        Ok(Some(count))
+    }
+
+    pub fn clear_find(&mut self) -> bool {
+        self.find.take().is_some()
+    }
+
+    pub fn is_edited(&self) -> bool {
+        self.current()
+            .is_some_and(|c| self.edits.contains_key(&c.id))
+    }
+
+    pub fn toggle_diff(&mut self) -> bool {
+        self.scroll_offset = 0;
+        self.diff = match self.diff.take() {
+            Some(_) => None,
+            None => self.current_diff(),
+        };
+        self.diff.is_some()
+    }
+
+    // word diff of the current floor between the content before and after editing
+    fn current_diff(&self) -> Option<Vec<Vec<(DiffKind, String)>>> {
+        let current = self.current()?;
+        let old = self.edits.get(&current.id)?;
+        Some(word_diff(&old.join("\n"), &current.desc.join("\n")))
+    }
+
+    pub fn has_previous_page(&self) -> bool {
+        self.previous_index().is_none() && self.start_page > 1
+    }
+
+    pub fn position(&self) -> Option<ReadPosition> {
+        let current = self.current()?;
+        Some(ReadPosition {
+            page: *self.pages.get(self.index as usize)?,
+            floor: current.floor,
+            offset: self.scroll_offset,
+        })
+    }
+
+    pub fn is_restorable(&self) -> bool {
+        self.restore
+            .is_some_and(|r| self.data.posts.iter().any(|p| p.floor == r.floor))
+    }
+
+    pub fn apply_restore(&mut self) {
+        if let Some(position) = self.restore.take() {
+            if let Some(i) = self
+                .data
+                .posts
+                .iter()
+                .position(|p| p.floor == position.floor)
+            {
+                self.index = i as u16;
+                self.scroll_offset = position.offset;
+            }
+        }
+    }
+
+    pub fn index(&mut self, index: u16) {
+        self.index = index;
+    }
+
     pub fn last_page(&mut self, page: u16) {
         self.last_page = page;
     }
@@ -243,41 +2717,89 @@ impl PostPageState {
     pub fn first(&mut self) {
         self.scroll_offset = 0;
         self.index = 0;
+        self.outline = None;
+        self.diff = None;
     }
 
     pub fn next(&mut self) -> Option<()> {
-        let next_index = self.next_index();
-        if self.index < self.data.posts.len() as u16 && self.data.posts.get(next_index).is_some() {
-            self.scroll_offset = 0;
-            self.index = next_index as u16;
-            Some(())
-        } else {
-            None
-        }
+        let next_index = self.next_index()?;
+        self.scroll_offset = 0;
+        self.index = next_index as u16;
+        self.outline = None;
+        self.diff = None;
+        Some(())
     }
 
     pub fn previous(&mut self) {
-        if self.index > 0 {
+        if let Some(i) = self.previous_index() {
             self.scroll_offset = 0;
-            self.index -= 1;
+            self.index = i as u16;
+            self.outline = None;
+            self.diff = None;
         }
     }
 
-    pub fn has_next(&self) -> bool {
-        let next_index = self.next_index();
-        if self.data.posts.get(next_index).is_some() {
-            true
-        } else {
-            self.page < self.last_page
+    pub fn toggle_outline(&mut self) {
+        if self.outline.take().is_some() {
+            return;
+        }
+
+        let outline = self.current().map_or(vec![], |c| c.outline());
+        if !outline.is_empty() {
+            let selected = outline
+                .iter()
+                .rposition(|o| o.line <= self.scroll_offset)
+                .unwrap_or(0);
+            let mut state = ListState::default();
+            state.select(Some(selected));
+            self.outline = Some(state);
         }
     }
 
-    fn next_index(&self) -> usize {
-        (self.index + 1) as usize
+    pub fn outline_next(&mut self) {
+        let max = self.current().map_or(0, |c| c.outline().len());
+        if let Some(state) = self.outline.as_mut() {
+            let i = state.selected().map_or(0, |i| (i + 1) % max.max(1));
+            state.select(Some(i));
+        }
+    }
+
+    pub fn outline_previous(&mut self) {
+        let max = self.current().map_or(0, |c| c.outline().len());
+        if let Some(state) = self.outline.as_mut() {
+            let i = state
+                .selected()
+                .map_or(0, |i| if i == 0 { max.max(1) - 1 } else { i - 1 });
+            state.select(Some(i));
+        }
+    }
+
+    pub fn outline_jump(&mut self) {
+        let selected = self.outline.as_ref().and_then(|s| s.selected());
+        let line = self
+            .current()
+            .and_then(|c| c.outline().get(selected?).map(|o| o.line));
+
+        if let Some(line) = line {
+            self.scroll_offset = line;
+        }
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.next_index().is_some() || self.page < self.last_page
+    }
+
+    // next and previous visible floor
+    fn next_index(&self) -> Option<usize> {
+        (self.index as usize + 1..self.data.posts.len()).find(|i| self.is_visible(*i))
+    }
+
+    fn previous_index(&self) -> Option<usize> {
+        (0..self.index as usize).rev().find(|i| self.is_visible(*i))
     }
 
     pub fn current(&self) -> Option<&PostContent> {
-        self.data.posts.get(self.index as usize)
+        self.data.posts.get(self.index as usize).map(Arc::as_ref)
     }
 
     pub fn scroll_up(&mut self) {
@@ -292,8 +2814,86 @@ impl PostPageState {
         }
     }
 
+    // images of the loaded floors, a link posted again is listed once,
+    // starting from the first one on screen or after the current floor
+    pub fn open_image_viewer(&mut self) -> bool {
+        let mut images: Vec<GalleryImage> = vec![];
+        let mut index = None;
+        for (i, content) in self.data.posts.iter().enumerate() {
+            let blocks = content.desc.iter().enumerate().map(|(j, s)| (j, s.trim()));
+            for (j, url) in blocks.filter(|(_, s)| is_image(s)) {
+                let at = match images.iter().position(|image| image.url == url) {
+                    Some(at) => at,
+                    None => {
+                        images.push(GalleryImage {
+                            url: url.to_string(),
+                            floors: vec![],
+                        });
+                        images.len() - 1
+                    }
+                };
+                if !images[at].floors.contains(&content.floor) {
+                    images[at].floors.push(content.floor);
+                }
+
+                let shown = i > self.index as usize
+                    || (i == self.index as usize && j >= self.scroll_offset);
+                if index.is_none() && shown {
+                    index = Some(at);
+                }
+            }
+        }
+        if images.is_empty() {
+            return false;
+        }
+
+        self.image = Some(ImageViewer {
+            images,
+            index: index.unwrap_or(0),
+            zoom: 1,
+            center: (500, 500),
+            area: Rect::default(),
+            hashes: HashMap::new(),
+            backward: false,
+        });
+        true
+    }
+
+    // left when the viewer was closed meanwhile
+    pub fn image_loaded(&mut self, link: String, image: Result<(u64, Arc<DynamicImage>), String>) {
+        let viewer = match self.image.as_mut() {
+            Some(viewer) => viewer,
+            None => return,
+        };
+        let image = image.map(|(hash, image)| {
+            viewer.hashed(&link, hash);
+            image
+        });
+        self.images.insert(link, Some(image));
+    }
+
+    pub fn close_image_viewer(&mut self) {
+        self.image = None;
+        self.images.clear();
+    }
+
+    // first gif on screen, to be opened outside
+    pub fn visible_animation(&self) -> Option<&str> {
+        self.current()?
+            .desc
+            .iter()
+            .skip(self.scroll_offset)
+            .take(self.scroll_size)
+            .map(|s| s.trim())
+            .find(|s| is_animated(s))
+    }
+
+    // the offset is a content block, so only the selection needs to follow a resize
     pub fn scroll_size(&mut self, size: usize) {
-        self.scroll_size = size;
+        if self.scroll_size != size {
+            self.scroll_size = size;
+            self.follow_visual();
+        }
     }
 
     fn scrollable(&self) -> bool {
@@ -309,16 +2909,92 @@ impl PostPageState {
     }
 }
 
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum CommentFilter {
+    #[default]
+    All,
+    FloorAuthor,
+    Starter,
+}
+
+impl CommentFilter {
+    pub fn next(self) -> CommentFilter {
+        match self {
+            CommentFilter::All => CommentFilter::FloorAuthor,
+            CommentFilter::FloorAuthor => CommentFilter::Starter,
+            CommentFilter::Starter => CommentFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CommentFilter::All => tr!("全部留言"),
+            CommentFilter::FloorAuthor => tr!("只看樓層作者"),
+            CommentFilter::Starter => tr!("只看樓主"),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct PostCommentState {
     pub offset: usize,
     pub items: Vec<PostComment>,
+    pub all_items: Vec<PostComment>,
     pub scroll_size: usize,
+    pub filter: CommentFilter,
+    // user ids of the floor and the thread, for the filter
+    pub floor_author: String,
+    pub starter: String,
+    // comment count by commenter instead of the comments
+    pub summary: bool,
 }
 
 impl PostCommentState {
     pub fn items(&mut self, items: Vec<PostComment>) {
-        self.items = items;
+        self.all_items = items;
+        self.filter_items();
+    }
+
+    fn filter_items(&mut self) {
+        let author = match self.filter {
+            CommentFilter::All => None,
+            CommentFilter::FloorAuthor => Some(&self.floor_author),
+            CommentFilter::Starter => Some(&self.starter),
+        };
+        self.items = self
+            .all_items
+            .iter()
+            .filter(|c| author.is_none_or(|a| *a == c.userid))
+            .cloned()
+            .collect();
+        self.offset = 0;
+    }
+
+    pub fn cycle_filter(&mut self) -> CommentFilter {
+        self.filter = self.filter.next();
+        self.filter_items();
+        self.filter
+    }
+
+    pub fn toggle_summary(&mut self) {
+        self.summary = !self.summary;
+        self.offset = 0;
+    }
+
+    // (user id, nick, count) of the commenters, most first
+    pub fn commenters(&self) -> Vec<(&str, &str, usize)> {
+        let mut commenters: Vec<(&str, &str, usize)> = vec![];
+        for comment in self.all_items.iter() {
+            match commenters
+                .iter_mut()
+                .find(|(id, _, _)| *id == comment.userid)
+            {
+                Some((_, _, count)) => *count += 1,
+                None => commenters.push((&comment.userid, &comment.nick, 1)),
+            }
+        }
+        commenters.sort_by_key(|(_, _, count)| std::cmp::Reverse(*count));
+        commenters
     }
 
     pub fn scroll_size(&mut self, size: usize) {
@@ -343,5 +3019,46 @@ impl PostCommentState {
 
     pub fn init(&mut self) {
         self.offset = 0;
+        self.summary = false;
+    }
+}
+
+#[derive(Default)]
+pub struct SettingsPageState {
+    pub state: ListState,
+    pub back: Page,
+    // new value of the selected option being typed
+    pub input: Option<Input>,
+}
+
+impl SettingsPageState {
+    pub fn open(&mut self, back: Page) {
+        self.back = back;
+        self.input = None;
+        if self.state.selected().is_none() {
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn selected(&self) -> Option<&'static Setting> {
+        SETTINGS.get(self.state.selected()?)
+    }
+}
+
+impl CursorMoveable for SettingsPageState {
+    fn index(&self) -> usize {
+        self.state.selected().map_or(1, |x| x)
+    }
+
+    fn max(&self) -> usize {
+        SETTINGS.len()
+    }
+
+    fn next(&mut self) {
+        self.state.select(Some(self.next_index()))
+    }
+
+    fn previous(&mut self) {
+        self.state.select(Some(self.previous_index()))
     }
 }
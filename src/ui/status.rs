@@ -0,0 +1,18 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Clear, Paragraph, Widget},
+};
+
+pub struct StatusLine<'a>(pub &'a str);
+
+impl Widget for StatusLine<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        Paragraph::new(self.0)
+            .style(Style::default().add_modifier(Modifier::REVERSED))
+            .render(area, buf);
+    }
+}
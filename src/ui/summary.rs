@@ -0,0 +1,37 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+use super::state::SummaryState;
+
+pub struct SummaryPopup<'a> {
+    pub state: &'a SummaryState,
+}
+
+impl Widget for SummaryPopup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let block = Block::default()
+            .title(tr!("摘要 - {title}", title = self.state.title))
+            .borders(Borders::ALL);
+
+        let lines: Vec<Line> = match self.state.result.as_ref() {
+            Some(Ok(summary)) => summary.lines().map(Line::from).collect(),
+            Some(Err(e)) => vec![Line::styled(
+                tr!("無法產生摘要: {error}", error = e),
+                Style::default().fg(Color::Red),
+            )],
+            None => vec![Line::from(tr!("摘要中..."))],
+        };
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((self.state.scroll, 0))
+            .block(block)
+            .render(area, buf);
+    }
+}
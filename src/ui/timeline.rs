@@ -0,0 +1,123 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
+};
+
+use crate::store::TranslationStore;
+
+use super::{
+    activity::{civil_from_days, days_from_civil, parse_day, today},
+    state::TimelinePageState,
+};
+
+// a day without the year, like 10/12 in the list, is the last one before tomorrow
+fn month_day(date: &str) -> Option<i64> {
+    let (m, d) = date.split_once('/')?;
+    let (m, d) = (m.parse::<i64>().ok()?, d.parse::<i64>().ok()?);
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+
+    let today = today();
+    let (y, _, _) = civil_from_days(today);
+    let day = days_from_civil(y, m, d);
+    Some(if day > today {
+        days_from_civil(y - 1, m, d)
+    } else {
+        day
+    })
+}
+
+// minutes since the epoch of the last reply in a board list, like
+// 今日 12:34, 昨日 08:00, 10/12 or 2023/10/12
+pub fn reply_time(date: &str) -> Option<i64> {
+    let mut day = today();
+    let mut minutes = 0;
+    for part in date.split_whitespace() {
+        if let Some((h, m)) = part.split_once(':') {
+            minutes = h.parse::<i64>().ok()? * 60 + m.parse::<i64>().ok()?;
+        } else if part.contains('今') {
+            day = today();
+        } else if part.contains('昨') {
+            day = today() - 1;
+        } else {
+            day = parse_day(part).or_else(|| month_day(part))?;
+        }
+    }
+
+    Some(day * 24 * 60 + minutes)
+}
+
+pub struct TimelinePageUI<'a> {
+    // translations and their language when the titles are translated
+    pub translation: Option<(&'a TranslationStore, &'a str)>,
+}
+
+impl StatefulWidget for TimelinePageUI<'_> {
+    type State = TimelinePageState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::default()
+            .title(format!(
+                "{}{}",
+                tr!("看板動態 - {count} 個訂閱看板", count = state.boards),
+                if self.translation.is_some() {
+                    tr!(" - 翻譯")
+                } else {
+                    ""
+                }
+            ))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+
+        if state.items.is_empty() {
+            Paragraph::new(tr!("訂閱的看板沒有貼文"))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let board_style = Style::default().fg(Color::LightBlue);
+        let info_style = Style::default().fg(Color::DarkGray);
+        let items: Vec<ListItem> = state
+            .items
+            .iter()
+            .map(|item| {
+                let post = &item.post;
+                let title = self
+                    .translation
+                    .and_then(|(store, language)| store.get(language, &post.title))
+                    .unwrap_or(post.title.as_str());
+                ListItem::new(vec![
+                    Line::from(vec![
+                        Span::styled(format!("[{}] ", item.board), board_style),
+                        Span::raw(title),
+                    ]),
+                    Line::styled(
+                        tr!(
+                            "  {date}  {author}  回覆 {reply}",
+                            date = post.date,
+                            author = post.author,
+                            reply = post.reply
+                        )
+                        .to_string(),
+                        info_style,
+                    ),
+                ])
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        StatefulWidget::render(list, area, buf, &mut state.state);
+
+        // two rows an item
+        let shown = area.height.saturating_sub(2) as usize / 2;
+        let offset = state.state.offset();
+        state.visible = offset..(offset + shown + 1).min(state.items.len());
+    }
+}
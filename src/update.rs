@@ -0,0 +1,69 @@
+use std::error::Error;
+
+use reqwest::header::USER_AGENT;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    pub html_url: String,
+}
+
+fn releases_url() -> String {
+    let repo = env!("CARGO_PKG_REPOSITORY").trim_start_matches("https://github.com/");
+    format!("https://api.github.com/repos/{}/releases", repo)
+}
+
+// newest first
+pub async fn releases(count: u8) -> Result<Vec<Release>, Box<dyn Error>> {
+    let url = format!("{}?per_page={}", releases_url(), count.max(1));
+    let releases = reqwest::Client::new()
+        .get(url)
+        .header(USER_AGENT, concat!("bahamut/", env!("CARGO_PKG_VERSION")))
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Vec<Release>>()
+        .await?;
+
+    Ok(releases)
+}
+
+fn version_numbers(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split(|c: char| !c.is_ascii_digit())
+        .map_while(|n| n.parse().ok())
+        .collect()
+}
+
+pub fn is_newer(tag: &str, current: &str) -> bool {
+    version_numbers(tag) > version_numbers(current)
+}
+
+// the latest release when it is newer than this build
+pub async fn check() -> Option<Release> {
+    releases(1)
+        .await
+        .ok()?
+        .into_iter()
+        .next()
+        .filter(|release| is_newer(release.tag_name.as_ref(), env!("CARGO_PKG_VERSION")))
+}
+
+pub async fn print_changelog(count: u8) -> Result<(), Box<dyn Error>> {
+    for release in releases(count).await? {
+        let date = release.published_at.as_deref().unwrap_or("");
+        println!("## {} {}", release.tag_name, date.get(..10).unwrap_or(date));
+        println!("{}", release.html_url);
+        println!();
+        println!("{}", release.body.as_deref().unwrap_or("").trim());
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use std::error::Error;
+
+use futures::executor::block_on;
+use serde_json::json;
+
+use crate::{
+    config::{WebhookConfig, WebhookFormat},
+    subscription::SubscriptionEvent,
+    template::fill_template,
+};
+
+pub fn send(config: &WebhookConfig, event: &SubscriptionEvent) -> Result<(), Box<dyn Error>> {
+    if config.url.is_empty() {
+        return Ok(());
+    }
+
+    let template = match event {
+        SubscriptionEvent::NewFloor { .. } => &config.new_floor,
+        SubscriptionEvent::NewThread { .. } => &config.new_thread,
+        SubscriptionEvent::FloorEdited { .. } => &config.floor_edited,
+    };
+
+    let message = fill_template(template, &event.fields());
+    let body = match config.format {
+        WebhookFormat::Discord => json!({ "content": message }),
+        WebhookFormat::Slack => json!({ "text": message }),
+    };
+
+    block_on(
+        reqwest::Client::new()
+            .post(config.url.as_str())
+            .json(&body)
+            .send(),
+    )?
+    .error_for_status()?;
+
+    Ok(())
+}